@@ -0,0 +1,316 @@
+//! Cellular automaton on a grid of live and dead cells
+//!
+//! The engine keeps the number of live neighbors of every cell and, after a step, only
+//! re-evaluates the cells around the ones that changed. A step therefore costs in proportion to
+//! how much changed rather than to the size of the grid.
+use std::fmt;
+
+use rayon::prelude::*;
+
+/// All eight surrounding cells
+pub const MOORE: &[(isize, isize)] = &[
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// Only the four orthogonally adjacent cells
+pub const VON_NEUMANN: &[(isize, isize)] = &[(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Update rule giving the next state of a cell from its state and its number of live neighbors
+///
+/// Implemented for any `Fn(bool, usize) -> bool` closure
+pub trait Rule {
+    fn next(&self, live: bool, live_neighbors: usize) -> bool;
+}
+impl<F: Fn(bool, usize) -> bool> Rule for F {
+    fn next(&self, live: bool, live_neighbors: usize) -> bool {
+        self(live, live_neighbors)
+    }
+}
+
+/// Synchronous automaton where every cell is updated by the same `Rule`
+#[derive(Clone)]
+pub struct Automaton<R> {
+    cells: Vec<Vec<bool>>,              // Whether each cell is live
+    counts: Vec<Vec<usize>>,            // Number of live neighbors of each cell
+    changing: Vec<Vec<bool>>,           // Whether the rule flips the cell in the next step
+    pending: Vec<(usize, usize)>,       // Every cell flagged in `changing`, once
+    offsets: &'static [(isize, isize)], // Offsets (row, col) to each neighbor
+    reach: usize,                       // Largest row offset to a neighbor
+    rule: R,
+    parallel: bool,
+}
+
+impl<R> fmt::Debug for Automaton<R> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Automaton")
+            .field("cells", &self.cells)
+            .field("pending", &self.pending)
+            .field("offsets", &self.offsets)
+            .field("parallel", &self.parallel)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<R: Rule + Sync> Automaton<R> {
+    /// Start from `cells`, which must all be the same length
+    pub fn new(cells: Vec<Vec<bool>>, offsets: &'static [(isize, isize)], rule: R) -> Self {
+        let height = cells.len();
+        let width = cells.first().map_or(0, Vec::len);
+        // Every row only reads `cells`, so rows can be computed independently
+        let counts: Vec<Vec<usize>> = (0..height)
+            .into_par_iter()
+            .map(|idx_r| {
+                (0..width)
+                    .map(|idx_c| {
+                        neighbors(height, width, offsets, idx_r, idx_c)
+                            .filter(|(r, c)| cells[*r][*c])
+                            .count()
+                    })
+                    .collect()
+            })
+            .collect();
+        let changing: Vec<Vec<bool>> = cells
+            .par_iter()
+            .zip(counts.par_iter())
+            .map(|(row, row_counts)| {
+                row.iter()
+                    .zip(row_counts)
+                    .map(|(live, count)| rule.next(*live, *count) != *live)
+                    .collect()
+            })
+            .collect();
+        let pending = changing
+            .iter()
+            .enumerate()
+            .flat_map(|(idx_r, row)| {
+                row.iter()
+                    .enumerate()
+                    .filter(|(_, flips)| **flips)
+                    .map(move |(idx_c, _)| (idx_r, idx_c))
+            })
+            .collect();
+        let reach = offsets
+            .iter()
+            .map(|(offset_r, _)| offset_r.unsigned_abs())
+            .max()
+            .unwrap_or(0);
+        Self {
+            cells,
+            counts,
+            changing,
+            pending,
+            offsets,
+            reach,
+            rule,
+            parallel: false,
+        }
+    }
+
+    /// Update the rows in parallel on every step
+    pub fn parallel(mut self) -> Self {
+        self.parallel = true;
+        self
+    }
+
+    pub fn cells(&self) -> &[Vec<bool>] {
+        &self.cells
+    }
+
+    /// Cells that the next step will flip
+    pub fn pending(&self) -> &[(usize, usize)] {
+        &self.pending
+    }
+
+    /// Whether the next step would change nothing
+    pub fn is_stable(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Flip every pending cell at once, then work out which cells flip next
+    ///
+    /// Returns the flipped cells, sorted
+    pub fn step(&mut self) -> Vec<(usize, usize)> {
+        let changed = std::mem::take(&mut self.pending);
+        self.apply(changed)
+    }
+
+    /// Flip the given cells regardless of the rule, e.g. to take back a step
+    ///
+    /// Flipping the cells returned by `step` restores the automaton to how it was before it.
+    pub fn flip(&mut self, cells: &[(usize, usize)]) {
+        self.apply(cells.to_vec());
+    }
+
+    /// Flip `changed` and re-evaluate the cells whose neighborhood changed
+    fn apply(&mut self, mut changed: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+        if self.parallel {
+            changed.par_sort_unstable();
+        } else {
+            changed.sort_unstable();
+        }
+        changed.dedup();
+        for (idx_r, idx_c) in &changed {
+            self.cells[*idx_r][*idx_c] ^= true;
+            self.changing[*idx_r][*idx_c] = false;
+        }
+        // Flipped cells are re-evaluated below, so they get queued again if they still flip
+        self.pending
+            .retain(|(idx_r, idx_c)| self.changing[*idx_r][*idx_c]);
+
+        let height = self.cells.len();
+        let width = self.cells.first().map_or(0, Vec::len);
+        let (cells, offsets, reach, rule) = (&self.cells, self.offsets, self.reach, &self.rule);
+        let changed_ref = &changed;
+        // Each row takes the changes within `reach` rows of it and only writes to itself
+        let update_row =
+            |(idx_r, (counts, changing)): (usize, (&mut Vec<usize>, &mut Vec<bool>))| {
+                // Sorted, so the changes near this row are contiguous
+                let first = changed_ref.partition_point(|(r, _)| r + reach < idx_r);
+                let last = changed_ref.partition_point(|(r, _)| *r <= idx_r + reach);
+                let mut candidates = Vec::new();
+                for (row, col) in &changed_ref[first..last] {
+                    if *row == idx_r {
+                        candidates.push(*col);
+                    }
+                    for (_, nc) in
+                        neighbors(height, width, offsets, *row, *col).filter(|(nr, _)| *nr == idx_r)
+                    {
+                        if cells[*row][*col] {
+                            counts[nc] += 1;
+                        } else {
+                            counts[nc] -= 1;
+                        }
+                        candidates.push(nc);
+                    }
+                }
+                candidates
+                    .into_iter()
+                    .filter_map(|idx_c| {
+                        let live = cells[idx_r][idx_c];
+                        let flips = rule.next(live, counts[idx_c]) != live;
+                        let was_flagged = std::mem::replace(&mut changing[idx_c], flips);
+                        // Candidates can repeat, so only queue the first time a cell is flagged
+                        (flips && !was_flagged).then_some((idx_r, idx_c))
+                    })
+                    .collect::<Vec<_>>()
+            };
+        let flagged: Vec<(usize, usize)> = if self.parallel {
+            self.counts
+                .par_iter_mut()
+                .zip(self.changing.par_iter_mut())
+                .enumerate()
+                .flat_map_iter(update_row)
+                .collect()
+        } else {
+            self.counts
+                .iter_mut()
+                .zip(self.changing.iter_mut())
+                .enumerate()
+                .flat_map(update_row)
+                .collect()
+        };
+        // Cells that were already queued may no longer flip
+        self.pending
+            .retain(|(idx_r, idx_c)| self.changing[*idx_r][*idx_c]);
+        self.pending.extend(flagged);
+        changed
+    }
+}
+
+/// Neighbors of (row, col) that lie inside a `height` x `width` grid
+fn neighbors(
+    height: usize,
+    width: usize,
+    offsets: &[(isize, isize)],
+    row: usize,
+    col: usize,
+) -> impl Iterator<Item = (usize, usize)> + '_ {
+    offsets.iter().filter_map(move |(offset_r, offset_c)| {
+        let r = row.checked_add_signed(*offset_r).filter(|r| *r < height)?;
+        let c = col.checked_add_signed(*offset_c).filter(|c| *c < width)?;
+        Some((r, c))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn life(live: bool, count: usize) -> bool {
+        count == 3 || (live && count == 2)
+    }
+
+    fn parse(input: &str) -> Vec<Vec<bool>> {
+        input
+            .lines()
+            .map(|line| line.chars().map(|c| c == '#').collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_blinker() {
+        let vertical = parse(".....\n..#..\n..#..\n..#..\n.....");
+        let horizontal = parse(".....\n.....\n.###.\n.....\n.....");
+        let mut automaton = Automaton::new(vertical.clone(), MOORE, life);
+        assert_eq!(automaton.pending().len(), 4);
+        assert_eq!(automaton.step(), vec![(1, 2), (2, 1), (2, 3), (3, 2)]);
+        assert_eq!(automaton.cells(), horizontal);
+        automaton.step();
+        assert_eq!(automaton.cells(), vertical);
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let glider = parse(".#......\n..#.....\n###.....\n........\n........\n........");
+        let mut sequential = Automaton::new(glider.clone(), MOORE, life);
+        let mut parallel = Automaton::new(glider, MOORE, life).parallel();
+        for _ in 0..4 {
+            assert_eq!(parallel.step(), sequential.step());
+            assert_eq!(parallel.cells(), sequential.cells());
+        }
+        // Four steps move the glider one cell down and right
+        let moved = parse("........\n..#.....\n...#....\n.###....\n........\n........");
+        assert_eq!(sequential.cells(), moved);
+        // Carry on into the bottom edge, where cells are cut off
+        for _ in 0..8 {
+            assert_eq!(parallel.step(), sequential.step());
+            assert_eq!(parallel.cells(), sequential.cells());
+        }
+    }
+
+    #[test]
+    fn test_flip_undoes_step() {
+        let glider = parse(".#......\n..#.....\n###.....\n........\n........\n........");
+        let mut automaton = Automaton::new(glider.clone(), MOORE, life);
+        let mut history = Vec::new();
+        for _ in 0..6 {
+            history.push((automaton.cells().to_vec(), automaton.pending().to_vec()));
+            let changed = automaton.step();
+            history.push((automaton.cells().to_vec(), automaton.pending().to_vec()));
+            automaton.flip(&changed);
+            let mut pending = automaton.pending().to_vec();
+            pending.sort();
+            let (cells, mut expected) = history[history.len() - 2].clone();
+            expected.sort();
+            assert_eq!(automaton.cells(), cells);
+            assert_eq!(pending, expected);
+            automaton.step();
+        }
+    }
+
+    #[test]
+    fn test_von_neumann_stable() {
+        // Nothing is ever born and lone cells die
+        let rule = |live: bool, count: usize| live && count > 0;
+        let mut automaton = Automaton::new(parse("#.#\n.##\n#.."), VON_NEUMANN, rule);
+        assert_eq!(automaton.step(), vec![(0, 0), (2, 0)]);
+        assert!(automaton.is_stable());
+    }
+}
@@ -0,0 +1,107 @@
+//! Stopping a solver that runs past `--timeout`
+//!
+//! The timeout only raises a flag; the slow loops check it and stop early, logging how far they
+//! got. Solvers that never check it are stopped outright a moment later.
+use std::{
+    process,
+    sync::{
+        OnceLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use tracing::warn;
+
+/// Exit status of a run that timed out, as `timeout(1)` uses
+pub const TIMED_OUT: u8 = 124;
+
+/// How long solvers get to stop on their own after the timeout
+const GRACE: Duration = Duration::from_secs(1);
+
+static CANCELLED: AtomicBool = AtomicBool::new(false);
+static TIMEOUT: OnceLock<Duration> = OnceLock::new();
+
+/// The run was stopped by `--timeout`
+#[derive(thiserror::Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("timed out after {0:?}")]
+pub struct TimedOut(pub Duration);
+
+/// Ask every solver to stop
+pub fn cancel() {
+    CANCELLED.store(true, Ordering::Relaxed);
+}
+
+/// Whether solvers have been asked to stop, cheap enough to check on every step of a loop
+pub fn is_cancelled() -> bool {
+    CANCELLED.load(Ordering::Relaxed)
+}
+
+/// Fail if solvers have been asked to stop, so a partial answer is never printed as the answer
+pub fn check() -> Result<(), TimedOut> {
+    if is_cancelled() {
+        Err(TimedOut(TIMEOUT.get().copied().unwrap_or_default()))
+    } else {
+        Ok(())
+    }
+}
+
+/// Iterators that stop once solvers have been asked to
+pub trait CancelIterator: Iterator + Sized {
+    fn until_cancelled(self) -> std::iter::TakeWhile<Self, fn(&Self::Item) -> bool> {
+        self.take_while(|_| !is_cancelled())
+    }
+}
+impl<I: Iterator> CancelIterator for I {}
+
+/// Parse a number of seconds, fractions included
+fn seconds(s: &str) -> Result<Duration, String> {
+    let secs: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    Duration::try_from_secs_f64(secs).map_err(|e| e.to_string())
+}
+
+/// How long solving may run before it is stopped
+#[derive(clap::Args, Debug, Clone, Copy, Default)]
+#[command(about = None, long_about = None)]
+pub struct TimeoutArgs {
+    /// Stop solving after this many seconds, exiting with status 124
+    #[arg(long, global = true, value_parser = seconds)]
+    pub timeout: Option<Duration>,
+}
+impl TimeoutArgs {
+    /// Start the clock, if there is a timeout
+    pub fn init(&self) {
+        let Some(timeout) = self.timeout else {
+            return;
+        };
+        TIMEOUT.get_or_init(|| timeout);
+        thread::spawn(move || {
+            thread::sleep(timeout);
+            warn!("Timed out after {timeout:?}, stopping");
+            cancel();
+            thread::sleep(GRACE);
+            eprintln!("error: {}", TimedOut(timeout));
+            process::exit(TIMED_OUT.into());
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel() {
+        assert_eq!(seconds("1.5"), Ok(Duration::from_millis(1500)));
+        assert!(seconds("-1").is_err());
+        assert!(seconds("soon").is_err());
+
+        assert_eq!(check(), Ok(()));
+        assert_eq!((0..5).until_cancelled().count(), 5);
+        cancel();
+        assert!(is_cancelled());
+        assert_eq!(check(), Err(TimedOut(Duration::ZERO)));
+        assert_eq!((0..5).until_cancelled().count(), 0);
+    }
+}
@@ -0,0 +1,178 @@
+//! Linear equations over GF(2), the integers mod 2, with up to 64 unknowns held as bits
+
+/// One equation: the unknowns set in `row` add up to `rhs`, mod 2
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Equation {
+    pub row: u64,
+    pub rhs: bool,
+}
+impl Equation {
+    /// Whether the unknowns set in `x` satisfy the equation
+    pub fn holds(&self, x: u64) -> bool {
+        (self.row & x).count_ones() % 2 == u32::from(self.rhs)
+    }
+}
+
+/// Every solution of a system: `particular` xor any combination of the `nullspace` basis
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Solutions {
+    pub particular: u64,
+    pub nullspace: Vec<u64>,
+}
+impl Solutions {
+    /// The solution with the fewest unknowns set, trying every combination of the basis
+    pub fn fewest_ones(&self) -> u64 {
+        // Brute force nullspace (usually small)
+        (0..1_u64 << self.nullspace.len())
+            .map(|mask| {
+                self.nullspace
+                    .iter()
+                    .enumerate()
+                    .filter(|(i, _)| (mask >> i) & 1 == 1)
+                    .fold(self.particular, |x, (_, nspace)| x ^ nspace)
+            })
+            .min_by_key(|x| x.count_ones())
+            .expect("There is always the particular solution")
+    }
+}
+
+/// Use Gaussian elimination to solve for the `n_vars` unknowns, or `None` if the equations
+/// contradict each other
+///
+/// Trying to solve Ax = b (mod 2)
+pub fn solve(mut eqs: Vec<Equation>, n_vars: usize) -> Option<Solutions> {
+    // Bookkeeping -- which row is the pivot off the column `col`
+    let mut pivot_col = vec![None; n_vars];
+    // Current pivot row during elimination
+    let mut row = 0;
+
+    // Iterate through the various columns trying to remove redundent scenarios
+    for (col, item) in pivot_col.iter_mut().enumerate() {
+        // Look for a row >= row where variable col appears with coefficient 1
+        let Some(pivot) = (row..eqs.len()).find(|&r| (eqs[r].row >> col) & 1 == 1) else {
+            // If non exist, this variable is free, because it does not affect any of the
+            // equations left -- skip this column
+            continue;
+        };
+
+        // Standard Gaussian elimination -- swap pivot row upward, record where the pivot lives
+        eqs.swap(row, pivot);
+        *item = Some(row);
+
+        // Eliminate this variable from all other rows
+        for r in 0..eqs.len() {
+            // if row r has a 1 in this pivot column, then subtract pivot row from it -- this
+            // zeros out column col in row r and preserves the equation's validity
+            if r != row && ((eqs[r].row >> col) & 1) == 1 {
+                eqs[r].row ^= eqs[row].row;
+                eqs[r].rhs ^= eqs[row].rhs;
+            }
+        }
+        row += 1;
+    }
+
+    // Consistency check -- detects 0 == 1 mod 2
+    if eqs.iter().any(|eq| eq.row == 0 && eq.rhs) {
+        return None;
+    }
+    // Particular solution (set free vars = 0)
+    // Build one concrete solution x
+    let mut particular = 0u64;
+    for (col, item) in pivot_col.iter().enumerate() {
+        if let Some(r) = item
+            && eqs[*r].rhs
+        {
+            particular |= 1 << col;
+        }
+    }
+
+    // Nullspace basis
+    let mut nullspace = Vec::new();
+    for free_col in 0..n_vars {
+        if pivot_col[free_col].is_none() {
+            // Start with free variable == 1, all others == 0
+            let mut vec = 1u64 << free_col;
+            // Enforces A vec = 0 -- turning on this free variable forces some pivot variables
+            // to flip, so overall effect is no equation changes
+            for (col, item) in pivot_col.iter().enumerate() {
+                if let Some(r) = item
+                    && ((eqs[*r].row >> free_col) & 1) == 1
+                {
+                    vec |= 1 << col;
+                }
+            }
+            nullspace.push(vec);
+        }
+    }
+
+    Some(Solutions {
+        particular,
+        nullspace,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Every assignment of the unknowns satisfying all the equations, found by trying them all
+    fn brute_force(eqs: &[Equation], n_vars: usize) -> Vec<u64> {
+        (0..1_u64 << n_vars)
+            .filter(|x| eqs.iter().all(|eq| eq.holds(*x)))
+            .collect()
+    }
+
+    #[test]
+    fn test_solve() {
+        // x0 + x1 = 1, x1 + x2 = 0, with x2 left free
+        let eqs = vec![
+            Equation {
+                row: 0b011,
+                rhs: true,
+            },
+            Equation {
+                row: 0b110,
+                rhs: false,
+            },
+        ];
+        let solutions = solve(eqs, 3).unwrap();
+        assert_eq!(solutions.particular, 0b001);
+        assert_eq!(solutions.nullspace, vec![0b111]);
+        assert_eq!(solutions.fewest_ones(), 0b001);
+        // x0 = 1 and x0 = 0
+        let eqs = vec![
+            Equation { row: 1, rhs: true },
+            Equation { row: 1, rhs: false },
+        ];
+        assert_eq!(solve(eqs, 1), None);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_solve_matches_brute_force(
+            n_vars in 0_usize..8,
+            eqs in prop::collection::vec((any::<u64>(), any::<bool>()), 0..8),
+        ) {
+            let eqs: Vec<Equation> = eqs
+                .into_iter()
+                .map(|(row, rhs)| Equation { row: row & ((1 << n_vars) - 1), rhs })
+                .collect();
+            let all = brute_force(&eqs, n_vars);
+            match solve(eqs.clone(), n_vars) {
+                None => prop_assert!(all.is_empty()),
+                Some(solutions) => {
+                    prop_assert!(eqs.iter().all(|eq| eq.holds(solutions.particular)));
+                    for nspace in &solutions.nullspace {
+                        prop_assert!(eqs.iter().all(|eq| (eq.row & nspace).count_ones() % 2 == 0));
+                    }
+                    // Independent, so the basis spans exactly the solutions
+                    prop_assert_eq!(all.len(), 1 << solutions.nullspace.len());
+                    let fewest = all.iter().map(|x| x.count_ones()).min();
+                    prop_assert_eq!(Some(solutions.fewest_ones().count_ones()), fewest);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,182 @@
+//! Helpers for reading puzzle input
+use std::{
+    fmt::Display,
+    fs::File,
+    io::{self, BufRead, BufReader, IsTerminal},
+    path::Path,
+};
+
+use anyhow::{Context, anyhow};
+
+/// Path standing for stdin
+pub const STDIN: &str = "-";
+
+/// Name of the example input in messages
+pub const EXAMPLE: &str = "<example>";
+
+/// Whether stdin is piped or redirected in, rather than left waiting on a terminal
+pub fn stdin_is_piped() -> bool {
+    !io::stdin().is_terminal()
+}
+
+/// Where the puzzle input comes from, flattened into each binary's arguments
+#[derive(clap::Args, Debug, Clone, Default)]
+#[command(about = None, long_about = None)]
+pub struct InputArgs {
+    /// Input file, or `-` for stdin (the default when stdin is piped in)
+    #[arg(short)]
+    pub input_file: Option<String>,
+
+    /// Run on the example from the puzzle text instead
+    #[arg(long, conflicts_with = "input_file")]
+    pub example: bool,
+}
+impl InputArgs {
+    /// The file to read, or `-` for stdin, if there is any input
+    fn path(&self, piped: bool) -> Option<&str> {
+        match &self.input_file {
+            Some(path) => Some(path),
+            None => piped.then_some(STDIN),
+        }
+    }
+
+    /// The input as named in messages
+    pub fn name(&self) -> &str {
+        if self.example {
+            return EXAMPLE;
+        }
+        match self.input_file.as_deref() {
+            Some(path) if path != STDIN => path,
+            _ => "<stdin>",
+        }
+    }
+
+    /// The input to read line by line, which is `example` with `--example`
+    pub fn open(&self, example: &'static str) -> anyhow::Result<Box<dyn BufRead>> {
+        if self.example {
+            return Ok(Box::new(example.as_bytes()));
+        }
+        open(self.required()?)
+    }
+
+    /// The whole input, which is `example` with `--example`
+    pub fn read_to_string(&self, example: &str) -> anyhow::Result<String> {
+        if self.example {
+            return Ok(example.to_owned());
+        }
+        read_to_string(self.required()?)
+    }
+
+    fn required(&self) -> anyhow::Result<&str> {
+        self.path(stdin_is_piped())
+            .ok_or_else(|| anyhow!("no input, pass -i <INPUT_FILE> or pipe it to stdin"))
+    }
+}
+
+/// A line of input that could not be parsed, with its 1-indexed number
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("line {line}: {reason}")]
+pub struct LineError {
+    pub line: usize,
+    pub reason: String,
+}
+impl LineError {
+    pub fn new(line: usize, reason: impl Display) -> Self {
+        Self {
+            line,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// An input file to read line by line, or stdin for `-`, failing with its path if it cannot be
+/// opened
+pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Box<dyn BufRead>> {
+    let path = path.as_ref();
+    if path == Path::new(STDIN) {
+        return Ok(Box::new(io::stdin().lock()));
+    }
+    let file = File::open(path).with_context(|| format!("cannot read {}", path.display()))?;
+    Ok(Box::new(BufReader::new(file)))
+}
+
+/// Contents of an input file, or of stdin for `-`, failing with its path if it cannot be read
+pub fn read_to_string(path: impl AsRef<Path>) -> anyhow::Result<String> {
+    let path = path.as_ref();
+    if path == Path::new(STDIN) {
+        return io::read_to_string(io::stdin()).context("cannot read stdin");
+    }
+    std::fs::read_to_string(path).with_context(|| format!("cannot read {}", path.display()))
+}
+
+/// Split `input` into groups of lines separated by one or more blank lines
+///
+/// Lines are trimmed (so `\r\n` endings and whitespace-only lines are fine) and come with their
+/// 1-indexed line number. Blank lines at the start or end produce no empty groups.
+pub fn blank_line_groups(input: &str) -> Vec<Vec<(usize, &str)>> {
+    let mut groups = Vec::new();
+    let mut current = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            if !current.is_empty() {
+                groups.push(std::mem::take(&mut current));
+            }
+        } else {
+            current.push((idx + 1, line));
+        }
+    }
+    if !current.is_empty() {
+        groups.push(current);
+    }
+    groups
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_blank_line_groups() {
+        let input = "\r\n3-5\r\n 10-14 \r\n\r\n  \n\n1\n5\n\n\n";
+        assert_eq!(
+            blank_line_groups(input),
+            vec![vec![(2, "3-5"), (3, "10-14")], vec![(7, "1"), (8, "5")]]
+        );
+        assert!(blank_line_groups("\n \n").is_empty());
+    }
+
+    #[test]
+    fn test_input_args() {
+        let args = |input_file: Option<&str>| InputArgs {
+            input_file: input_file.map(str::to_owned),
+            example: false,
+        };
+        assert_eq!(args(None).path(true), Some(STDIN));
+        assert_eq!(args(None).path(false), None);
+        assert_eq!(args(Some("day.txt")).path(true), Some("day.txt"));
+        assert_eq!(args(Some("-")).path(false), Some(STDIN));
+        assert_eq!(args(Some("day.txt")).name(), "day.txt");
+        assert_eq!(args(Some("-")).name(), "<stdin>");
+
+        let example = InputArgs {
+            input_file: None,
+            example: true,
+        };
+        assert_eq!(example.name(), EXAMPLE);
+        assert_eq!(example.read_to_string("1\n2\n").unwrap(), "1\n2\n");
+        let lines: Vec<_> = example.open("1\n2\n").unwrap().lines().collect();
+        assert_eq!(lines.len(), 2);
+    }
+
+    #[test]
+    fn test_read_to_string() {
+        let e = read_to_string("no/such/input.txt").unwrap_err();
+        assert!(format!("{e:#}").starts_with("cannot read no/such/input.txt: "));
+        assert!(open("no/such/input.txt").is_err());
+        assert_eq!(
+            LineError::new(3, "not a number").to_string(),
+            "line 3: not a number"
+        );
+    }
+}
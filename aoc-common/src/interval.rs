@@ -0,0 +1,443 @@
+//! Sets of integers stored as sorted, disjoint inclusive intervals
+use std::{
+    fmt,
+    ops::{Add, Sub},
+};
+
+/// Unsigned integer that can bound an interval
+pub trait Bound: Copy + Ord + fmt::Debug + Add<Output = Self> + Sub<Output = Self> {
+    const ZERO: Self;
+    const ONE: Self;
+    const MAX: Self;
+    /// `self + 1`, or `None` at the maximum value
+    fn checked_succ(self) -> Option<Self>;
+    /// `self + rhs`, or `None` past the maximum value
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! impl_bound {
+    ($($t:ty),*) => {
+        $(
+            impl Bound for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+                const MAX: Self = <$t>::MAX;
+                fn checked_succ(self) -> Option<Self> {
+                    <$t>::checked_add(self, 1)
+                }
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+            }
+        )*
+    };
+}
+impl_bound!(u8, u16, u32, u64, u128, usize);
+
+/// Inclusive interval `start..=end`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+impl<T: Bound> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Self { start, end }
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        value >= self.start && value <= self.end
+    }
+
+    /// Number of values in the interval, or `None` for `0..=T::MAX`, which holds one more value
+    /// than `T` can count
+    pub fn len(&self) -> Option<T> {
+        (self.end - self.start).checked_succ()
+    }
+
+    /// Whether the two intervals overlap or sit right next to each other
+    fn touches(&self, other: &Self) -> bool {
+        let (first, second) = if self.start <= other.start {
+            (self, other)
+        } else {
+            (other, self)
+        };
+        first
+            .end
+            .checked_succ()
+            .is_none_or(|after_end| second.start <= after_end)
+    }
+}
+
+/// Set of integers kept as sorted intervals that neither overlap nor touch
+///
+/// Collecting intervals into a set sorts and merges them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T> Default for IntervalSet<T> {
+    fn default() -> Self {
+        Self {
+            intervals: Vec::new(),
+        }
+    }
+}
+
+impl<T: Bound> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut intervals: Vec<Interval<T>> = iter.into_iter().collect();
+        intervals.sort_unstable_by_key(|interval| interval.start);
+        Self {
+            intervals: merge_sorted(intervals),
+        }
+    }
+}
+
+impl<T: Bound> IntervalSet<T> {
+    /// Add `interval`, merging it with any interval it overlaps or touches
+    pub fn insert(&mut self, interval: Interval<T>) {
+        // Intervals before `first` end too early to touch, those from `last` start too late
+        let first = self
+            .intervals
+            .partition_point(|other| other.end < interval.start && !other.touches(&interval));
+        let last = self
+            .intervals
+            .partition_point(|other| other.start <= interval.end || other.touches(&interval));
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |acc, other| Interval {
+                start: acc.start.min(other.start),
+                end: acc.end.max(other.end),
+            });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    /// Binary search for the interval holding `value`
+    pub fn find(&self, value: T) -> Option<&Interval<T>> {
+        // The first interval that does not end before `value` is the only candidate
+        let idx = self
+            .intervals
+            .partition_point(|interval| interval.end < value);
+        self.intervals
+            .get(idx)
+            .filter(|interval| interval.contains(value))
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.find(value).is_some()
+    }
+
+    /// Number of values in the set, or `None` if it holds every value of `T`, one more than `T`
+    /// can count
+    pub fn total_len(&self) -> Option<T> {
+        self.intervals
+            .iter()
+            .try_fold(T::ZERO, |acc, interval| acc.checked_add(interval.len()?))
+    }
+
+    /// Values in either set
+    pub fn union(&self, other: &Self) -> Self {
+        // Merge the two sorted lists, then join what touches
+        let mut intervals = Vec::with_capacity(self.intervals.len() + other.intervals.len());
+        let (mut mine, mut theirs) = (
+            self.intervals.iter().peekable(),
+            other.intervals.iter().peekable(),
+        );
+        while let (Some(a), Some(b)) = (mine.peek(), theirs.peek()) {
+            if a.start <= b.start {
+                intervals.push(**a);
+                mine.next();
+            } else {
+                intervals.push(**b);
+                theirs.next();
+            }
+        }
+        intervals.extend(mine.chain(theirs).copied());
+        Self {
+            intervals: merge_sorted(intervals),
+        }
+    }
+
+    /// Values in both sets
+    pub fn intersect(&self, other: &Self) -> Self {
+        let mut intervals = Vec::new();
+        let (mut idx_a, mut idx_b) = (0, 0);
+        while let (Some(a), Some(b)) = (self.intervals.get(idx_a), other.intervals.get(idx_b)) {
+            let start = a.start.max(b.start);
+            let end = a.end.min(b.end);
+            if start <= end {
+                intervals.push(Interval { start, end });
+            }
+            // Whichever ends first can't overlap anything further along
+            if a.end < b.end {
+                idx_a += 1;
+            } else {
+                idx_b += 1;
+            }
+        }
+        Self { intervals }
+    }
+
+    /// Every value not in the set
+    pub fn complement(&self) -> Self {
+        self.complement_within(Interval::new(T::ZERO, T::MAX))
+    }
+
+    /// Values within `bounds` that are not in the set
+    pub fn complement_within(&self, bounds: Interval<T>) -> Self {
+        let mut intervals = Vec::new();
+        // Start of the next gap, or `None` once the set reaches the maximum value
+        let mut cursor = Some(bounds.start);
+        for interval in &self.intervals {
+            let Some(start) = cursor else {
+                break;
+            };
+            if interval.start > bounds.end {
+                break;
+            }
+            if interval.start > start {
+                intervals.push(Interval::new(start, interval.start - T::ONE));
+            }
+            // Intervals before `bounds` leave the cursor where it is
+            cursor = interval.end.checked_succ().map(|next| next.max(start));
+        }
+        if let Some(start) = cursor
+            && start <= bounds.end
+        {
+            intervals.push(Interval::new(start, bounds.end));
+        }
+        Self { intervals }
+    }
+
+    /// Smallest interval covering the whole set
+    pub fn span(&self) -> Option<Interval<T>> {
+        Some(Interval::new(
+            self.intervals.first()?.start,
+            self.intervals.last()?.end,
+        ))
+    }
+
+    /// Values missing between the smallest and largest values of the set
+    pub fn gaps(&self) -> Self {
+        match self.span() {
+            Some(span) => self.complement_within(span),
+            None => Self::default(),
+        }
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Interval<T>> {
+        self.intervals.iter()
+    }
+
+    /// Number of disjoint intervals
+    pub fn len(&self) -> usize {
+        self.intervals.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+}
+
+/// Merge intervals sorted by start wherever they overlap or touch
+fn merge_sorted<T: Bound>(intervals: Vec<Interval<T>>) -> Vec<Interval<T>> {
+    let mut merged: Vec<Interval<T>> = Vec::with_capacity(intervals.len());
+    for interval in intervals {
+        match merged.last_mut() {
+            Some(last) if last.touches(&interval) => last.end = last.end.max(interval.end),
+            _ => merged.push(interval),
+        }
+    }
+    merged
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::BTreeSet;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    fn set(bounds: &[(u64, u64)]) -> IntervalSet<u64> {
+        bounds
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end))
+            .collect()
+    }
+
+    fn bounds(set: &IntervalSet<u64>) -> Vec<(u64, u64)> {
+        set.iter()
+            .map(|interval| (interval.start, interval.end))
+            .collect()
+    }
+
+    #[test]
+    fn test_merge() {
+        let merged = set(&[
+            (20, 25),
+            (1, 3),
+            (4, 6),
+            (10, 12),
+            (2, 5),
+            (11, 11),
+            (26, 26),
+        ]);
+        assert_eq!(bounds(&merged), vec![(1, 6), (10, 12), (20, 26)]);
+        assert_eq!(merged.total_len(), Some(16));
+        // Inserting one at a time gives the same set
+        let mut inserted = IntervalSet::default();
+        for (start, end) in [
+            (20, 25),
+            (1, 3),
+            (4, 6),
+            (10, 12),
+            (2, 5),
+            (11, 11),
+            (26, 26),
+        ] {
+            inserted.insert(Interval::new(start, end));
+        }
+        assert_eq!(inserted, merged);
+        inserted.insert(Interval::new(7, 19));
+        assert_eq!(bounds(&inserted), vec![(1, 26)]);
+    }
+
+    #[test]
+    fn test_contains() {
+        let s = set(&[(1, 6), (10, 12), (20, 26)]);
+        for (value, expected) in [(0, false), (1, true), (6, true), (7, false), (12, true)] {
+            assert_eq!(s.contains(value), expected, "{value}");
+        }
+        assert_eq!(s.find(11), Some(&Interval::new(10, 12)));
+        assert_eq!(s.find(27), None);
+        // Intervals reaching the maximum value don't overflow
+        let top = set(&[(u64::MAX - 1, u64::MAX), (u64::MAX, u64::MAX)]);
+        assert!(top.contains(u64::MAX));
+        assert_eq!(top.total_len(), Some(2));
+
+        // Every value is one more than the type can count
+        assert_eq!(Interval::new(1, u64::MAX).len(), Some(u64::MAX));
+        assert_eq!(Interval::new(0, u64::MAX).len(), None);
+        assert_eq!(set(&[(0, 5), (6, u64::MAX)]).total_len(), None);
+        assert_eq!(set(&[(0, 5), (7, u64::MAX)]).total_len(), Some(u64::MAX));
+    }
+
+    #[test]
+    fn test_union_intersect() {
+        let a = set(&[(1, 5), (10, 15), (30, 40)]);
+        let b = set(&[(4, 11), (16, 20), (35, 35)]);
+        assert_eq!(bounds(&a.union(&b)), vec![(1, 20), (30, 40)]);
+        assert_eq!(bounds(&a.intersect(&b)), vec![(4, 5), (10, 11), (35, 35)]);
+        assert_eq!(a.intersect(&IntervalSet::default()), IntervalSet::default());
+        assert_eq!(a.union(&IntervalSet::default()), a);
+    }
+
+    #[test]
+    fn test_complement_gaps() {
+        let a = set(&[(1, 5), (10, 15), (30, 40)]);
+        assert_eq!(bounds(&a.gaps()), vec![(6, 9), (16, 29)]);
+        assert_eq!(
+            bounds(&a.complement()),
+            vec![(0, 0), (6, 9), (16, 29), (41, u64::MAX)]
+        );
+        assert_eq!(a.complement().complement(), a);
+        assert_eq!(
+            bounds(&a.complement_within(Interval::new(3, 12))),
+            vec![(6, 9)]
+        );
+        assert!(a.intersect(&a.complement()).is_empty());
+        assert!(set(&[(0, u64::MAX)]).complement().is_empty());
+        assert!(IntervalSet::<u64>::default().gaps().is_empty());
+    }
+
+    /// Every value of the intervals, one by one
+    fn values(intervals: &[(u16, u16)]) -> BTreeSet<u16> {
+        intervals
+            .iter()
+            .flat_map(|(start, end)| *start..=*end)
+            .collect()
+    }
+
+    /// Runs of consecutive values, as the merged intervals should be
+    fn runs(values: &BTreeSet<u16>) -> Vec<(u16, u16)> {
+        let mut runs: Vec<(u16, u16)> = Vec::new();
+        for value in values {
+            match runs.last_mut() {
+                Some((_, end)) if *end + 1 == *value => *end = *value,
+                _ => runs.push((*value, *value)),
+            }
+        }
+        runs
+    }
+
+    /// A few short intervals over a small range, so they often overlap or touch
+    fn intervals() -> impl Strategy<Value = Vec<(u16, u16)>> {
+        prop::collection::vec((0_u16..64, 0_u16..8), 0..8).prop_map(|intervals| {
+            intervals
+                .into_iter()
+                .map(|(start, len)| (start, start + len))
+                .collect()
+        })
+    }
+
+    fn to_set(intervals: &[(u16, u16)]) -> IntervalSet<u16> {
+        intervals
+            .iter()
+            .map(|(start, end)| Interval::new(*start, *end))
+            .collect()
+    }
+
+    fn to_bounds(set: &IntervalSet<u16>) -> Vec<(u16, u16)> {
+        set.iter()
+            .map(|interval| (interval.start, interval.end))
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn prop_merge_matches_values(intervals in intervals()) {
+            let merged = to_set(&intervals);
+            let expected = values(&intervals);
+            prop_assert_eq!(to_bounds(&merged), runs(&expected));
+            prop_assert_eq!(merged.total_len().map(usize::from), Some(expected.len()));
+            for value in 0..80 {
+                prop_assert_eq!(merged.contains(value), expected.contains(&value));
+            }
+
+            let mut inserted = IntervalSet::default();
+            for (start, end) in &intervals {
+                inserted.insert(Interval::new(*start, *end));
+            }
+            prop_assert_eq!(inserted, merged);
+        }
+
+        #[test]
+        fn prop_set_algebra_matches_values(a in intervals(), b in intervals()) {
+            let (set_a, set_b) = (to_set(&a), to_set(&b));
+            let (values_a, values_b) = (values(&a), values(&b));
+            prop_assert_eq!(
+                to_bounds(&set_a.union(&set_b)),
+                runs(&(&values_a | &values_b))
+            );
+            prop_assert_eq!(
+                to_bounds(&set_a.intersect(&set_b)),
+                runs(&(&values_a & &values_b))
+            );
+
+            let within: BTreeSet<u16> = (10..=50).collect();
+            prop_assert_eq!(
+                to_bounds(&set_a.complement_within(Interval::new(10, 50))),
+                runs(&(&within - &values_a))
+            );
+            let span: BTreeSet<u16> = match (values_a.first(), values_a.last()) {
+                (Some(first), Some(last)) => (*first..=*last).collect(),
+                _ => BTreeSet::new(),
+            };
+            prop_assert_eq!(to_bounds(&set_a.gaps()), runs(&(&span - &values_a)));
+            prop_assert_eq!(set_a.complement().complement(), set_a);
+        }
+    }
+}
@@ -0,0 +1,13 @@
+//! Building blocks shared between the days
+pub mod automaton;
+pub mod cancel;
+pub mod gf2;
+pub mod input;
+pub mod interval;
+pub mod log;
+pub mod memory;
+pub mod output;
+pub mod progress;
+pub mod seq;
+pub mod solver;
+pub mod threads;
@@ -0,0 +1,61 @@
+//! Progress and diagnostics on stderr, as loud as `-v` and `-q` ask, so stdout keeps only the
+//! answer
+use std::io::{self, IsTerminal};
+
+use tracing::level_filters::LevelFilter;
+
+use crate::progress;
+
+/// Verbosity flags for the progress logged on stderr
+#[derive(clap::Args, Debug, Clone, Copy, Default)]
+#[command(about = None, long_about = None)]
+pub struct LogArgs {
+    /// Log progress on stderr, repeated for more detail
+    #[arg(short, long, action = clap::ArgAction::Count, global = true)]
+    pub verbose: u8,
+
+    /// Log nothing but errors
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    pub quiet: bool,
+}
+impl LogArgs {
+    /// Most detailed level logged
+    pub fn level(&self) -> LevelFilter {
+        match (self.quiet, self.verbose) {
+            (true, _) => LevelFilter::ERROR,
+            (false, 0) => LevelFilter::WARN,
+            (false, 1) => LevelFilter::INFO,
+            (false, 2) => LevelFilter::DEBUG,
+            (false, _) => LevelFilter::TRACE,
+        }
+    }
+
+    /// Send events at the chosen level and above to stderr, and hide progress bars if quiet
+    pub fn init(&self) {
+        if self.quiet {
+            progress::hide();
+        }
+        tracing_subscriber::fmt()
+            .with_max_level(self.level())
+            .with_writer(io::stderr)
+            .with_ansi(io::stderr().is_terminal())
+            .with_target(false)
+            .without_time()
+            .init();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level() {
+        let args = |verbose, quiet| LogArgs { verbose, quiet };
+        assert_eq!(args(0, false).level(), LevelFilter::WARN);
+        assert_eq!(args(1, false).level(), LevelFilter::INFO);
+        assert_eq!(args(2, false).level(), LevelFilter::DEBUG);
+        assert_eq!(args(5, false).level(), LevelFilter::TRACE);
+        assert_eq!(args(0, true).level(), LevelFilter::ERROR);
+    }
+}
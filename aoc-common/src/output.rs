@@ -0,0 +1,140 @@
+//! How an answer is printed, shared by every binary
+use std::{process::ExitCode, time::Duration};
+
+use serde::Serialize;
+
+use crate::{cancel, memory};
+
+/// Format an answer is printed in
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Format {
+    /// The answer, then how long it took
+    #[default]
+    Text,
+    /// A JSON object with the day, part, answer and timings in milliseconds
+    Json,
+}
+
+/// Format the answer record is printed in
+#[derive(clap::Args, Debug, Clone, Copy, Default)]
+#[command(about = None, long_about = None)]
+pub struct OutputArgs {
+    /// How to print the answer
+    #[arg(long, value_enum, default_value_t)]
+    pub output: Format,
+}
+impl OutputArgs {
+    /// Print the record in the chosen format
+    pub fn print(&self, record: &Record) {
+        match self.output {
+            Format::Text => {
+                println!("{}", record.answer);
+                println!("{}", footer(record.parse + record.solve));
+            }
+            Format::Json => println!("{}", record.json()),
+        }
+    }
+}
+
+/// The line printed under an answer, with how long it took and the most memory held on the way
+pub fn footer(elapsed: Duration) -> String {
+    match memory::peak_rss() {
+        Some(peak) => format!(
+            "Completed in {elapsed:?}, peak memory {}",
+            memory::human_bytes(peak)
+        ),
+        None => format!("Completed in {elapsed:?}"),
+    }
+}
+
+/// Exit cleanly, or print why the run failed on one line and exit non-zero
+///
+/// Each cause is joined on, so a parse error reads `error: <file>: line <n>: <reason>`. A run
+/// that failed because it timed out exits with [`cancel::TIMED_OUT`].
+pub fn exit(result: anyhow::Result<()>) -> ExitCode {
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {e:#}");
+            if cancel::is_cancelled() {
+                ExitCode::from(cancel::TIMED_OUT)
+            } else {
+                ExitCode::FAILURE
+            }
+        }
+    }
+}
+
+/// Which part an answer is for: its number, or the name of some other mode
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum PartName {
+    Number(u8),
+    Mode(String),
+}
+impl From<u8> for PartName {
+    fn from(part: u8) -> Self {
+        Self::Number(part)
+    }
+}
+impl From<&str> for PartName {
+    fn from(mode: &str) -> Self {
+        Self::Mode(mode.to_owned())
+    }
+}
+
+/// An answer with how long parsing the input and solving took
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Record {
+    pub day: u8,
+    pub part: PartName,
+    pub answer: String,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+impl Record {
+    /// One line of JSON, with the timings in fractional milliseconds
+    pub fn json(&self) -> String {
+        #[derive(Serialize)]
+        struct Json<'a> {
+            day: u8,
+            part: &'a PartName,
+            answer: &'a str,
+            parse_ms: f64,
+            solve_ms: f64,
+        }
+        let json = Json {
+            day: self.day,
+            part: &self.part,
+            answer: &self.answer,
+            parse_ms: self.parse.as_secs_f64() * 1e3,
+            solve_ms: self.solve.as_secs_f64() * 1e3,
+        };
+        serde_json::to_string(&json).expect("Plain fields always serialize")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_json() {
+        let record = Record {
+            day: 9,
+            part: 2.into(),
+            answer: "1234".to_string(),
+            parse: Duration::from_micros(1500),
+            solve: Duration::from_millis(3),
+        };
+        assert_eq!(
+            record.json(),
+            r#"{"day":9,"part":2,"answer":"1234","parse_ms":1.5,"solve_ms":3.0}"#
+        );
+        let record = Record {
+            part: "gaps".into(),
+            ..record
+        };
+        assert!(record.json().contains(r#""part":"gaps""#));
+    }
+}
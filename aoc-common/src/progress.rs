@@ -0,0 +1,62 @@
+//! Progress bars for long-running solvers, drawn together on stderr
+//!
+//! Every bar joins one [`MultiProgress`], so bars advanced at once from rayon or plain threads
+//! stack instead of overwriting each other. Nothing is drawn unless stderr is a terminal, and
+//! bars clear themselves once dropped so only the answer is left.
+use std::{borrow::Cow, sync::OnceLock};
+
+use indicatif::{MultiProgress, ProgressDrawTarget, ProgressFinish, ProgressStyle};
+pub use indicatif::{ParallelProgressIterator, ProgressBar, ProgressIterator};
+
+/// Where every bar is drawn
+fn multi() -> &'static MultiProgress {
+    static MULTI: OnceLock<MultiProgress> = OnceLock::new();
+    MULTI.get_or_init(MultiProgress::new)
+}
+
+/// Draw no bars from now on, as `-q` asks
+pub fn hide() {
+    multi().set_draw_target(ProgressDrawTarget::hidden());
+}
+
+/// A bar counting up to `len` steps
+pub fn bar(len: u64, message: impl Into<Cow<'static, str>>) -> ProgressBar {
+    let style = ProgressStyle::with_template("{msg:>12} [{bar:40}] {pos}/{len} ({eta})")
+        .expect("The template is valid")
+        .progress_chars("=> ");
+    add(ProgressBar::new(len).with_style(style), message)
+}
+
+/// A spinner counting steps, for when there is no telling how many there will be
+pub fn spinner(message: impl Into<Cow<'static, str>>) -> ProgressBar {
+    let style = ProgressStyle::with_template("{spinner} {msg:>12} {human_pos} ({per_sec})")
+        .expect("The template is valid");
+    add(ProgressBar::new_spinner().with_style(style), message)
+}
+
+fn add(bar: ProgressBar, message: impl Into<Cow<'static, str>>) -> ProgressBar {
+    multi().add(
+        bar.with_message(message)
+            .with_finish(ProgressFinish::AndClear),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use rayon::prelude::*;
+
+    use super::*;
+
+    #[test]
+    fn test_bar() {
+        let bar = bar(100, "sum");
+        let sum: u64 = (1..=100).into_par_iter().progress_with(bar.clone()).sum();
+        assert_eq!(sum, 5050);
+        assert_eq!(bar.position(), 100);
+        assert_eq!(bar.length(), Some(100));
+
+        let spinner = spinner("count");
+        assert_eq!((0..7).progress_with(spinner.clone()).count(), 7);
+        assert_eq!(spinner.position(), 7);
+    }
+}
@@ -0,0 +1,116 @@
+//! Picking elements out of a sequence while keeping their order
+
+/// Positions of the `k` items making the best subsequence, where `better(a, b)` says whether
+/// `a` beats `b` in the same place
+///
+/// Subsequences are compared place by place, and of equal items the earliest is kept. It takes
+/// one pass, keeping the positions picked so far in a stack that each item pops while it beats
+/// the one on top and there are still items to spare.
+///
+/// `None` if `k` is more than the number of items.
+pub fn best_subsequence_by<T>(
+    items: &[T],
+    k: usize,
+    mut better: impl FnMut(&T, &T) -> bool,
+) -> Option<Vec<usize>> {
+    // Every item left out is one we can afford to drop
+    let mut to_drop = items.len().checked_sub(k)?;
+    let mut stack: Vec<usize> = Vec::with_capacity(items.len());
+    for (idx, item) in items.iter().enumerate() {
+        while to_drop > 0 && stack.last().is_some_and(|top| better(item, &items[*top])) {
+            stack.pop();
+            to_drop -= 1;
+        }
+        stack.push(idx);
+    }
+    stack.truncate(k);
+    Some(stack)
+}
+
+/// Positions of the `k` items making the largest subsequence, like the largest number made of
+/// `k` of the digits
+pub fn largest_subsequence<T: Ord>(items: &[T], k: usize) -> Option<Vec<usize>> {
+    best_subsequence_by(items, k, |a, b| a > b)
+}
+
+/// Positions of the `k` items making the smallest subsequence
+pub fn smallest_subsequence<T: Ord>(items: &[T], k: usize) -> Option<Vec<usize>> {
+    best_subsequence_by(items, k, |a, b| a < b)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cmp::Ordering;
+
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Best subsequence out of every choice of positions, the earliest positions on ties
+    fn brute_force(items: &[u8], k: usize, ordering: Ordering) -> Vec<usize> {
+        let mut best: Option<(Vec<u8>, Vec<usize>)> = None;
+        for mask in 0_u32..1 << items.len() {
+            if mask.count_ones() as usize != k {
+                continue;
+            }
+            let picked: Vec<usize> = (0..items.len())
+                .filter(|idx| mask & (1 << idx) != 0)
+                .collect();
+            let values: Vec<u8> = picked.iter().map(|idx| items[*idx]).collect();
+            let replaces = best.as_ref().is_none_or(|(best_values, best_picked)| {
+                let order = values.cmp(best_values);
+                order == ordering || (order == Ordering::Equal && picked < *best_picked)
+            });
+            if replaces {
+                best = Some((values, picked));
+            }
+        }
+        best.map(|(_, picked)| picked).unwrap_or_default()
+    }
+
+    #[test]
+    fn test_examples() {
+        let digits = [8, 1, 8, 1, 8, 1, 9, 1, 1, 1, 1, 2, 1, 1, 1];
+        assert_eq!(largest_subsequence(&digits, 2), Some(vec![6, 11]));
+        assert_eq!(smallest_subsequence(&digits, 3), Some(vec![1, 3, 5]));
+        assert_eq!(largest_subsequence(&[9, 9, 1, 9], 2), Some(vec![0, 1]));
+        assert_eq!(largest_subsequence(&[3, 1], 0), Some(Vec::new()));
+        assert_eq!(largest_subsequence(&[3, 1], 2), Some(vec![0, 1]));
+        // Compared by any rule, here the longest words
+        let words = ["b", "ccc", "a", "dd"];
+        let longest = best_subsequence_by(&words, 2, |a, b| a.len() > b.len());
+        assert_eq!(longest, Some(vec![1, 3]));
+    }
+
+    #[test]
+    fn test_too_many() {
+        assert_eq!(largest_subsequence(&[1, 2], 3), None);
+        assert_eq!(smallest_subsequence::<u8>(&[], 1), None);
+    }
+
+    proptest! {
+        #[test]
+        fn prop_largest_matches_brute_force(
+            items in prop::collection::vec(0_u8..4, 0..10),
+            k in 0_usize..10,
+        ) {
+            let k = k.min(items.len());
+            prop_assert_eq!(
+                largest_subsequence(&items, k),
+                Some(brute_force(&items, k, Ordering::Greater))
+            );
+        }
+
+        #[test]
+        fn prop_smallest_matches_brute_force(
+            items in prop::collection::vec(0_u8..4, 0..10),
+            k in 0_usize..10,
+        ) {
+            let k = k.min(items.len());
+            prop_assert_eq!(
+                smallest_subsequence(&items, k),
+                Some(brute_force(&items, k, Ordering::Less))
+            );
+        }
+    }
+}
@@ -0,0 +1,99 @@
+//! The shape every day's puzzle shares, so they can all be run, timed and checked the same way
+use std::fmt::Display;
+
+/// Why a parsed input has no answer, such as a total that overflows
+#[derive(thiserror::Error, Debug, Clone, PartialEq, Eq)]
+#[error("{0}")]
+pub struct SolveError(pub String);
+impl SolveError {
+    pub fn new(reason: impl Display) -> Self {
+        Self(reason.to_string())
+    }
+}
+
+/// A day's puzzle input, parsed once and then answered for either part
+pub trait Solver {
+    type Output: Display;
+    type Error: std::error::Error + Send + Sync + 'static;
+
+    /// The example from the puzzle text, run with `--example`
+    const EXAMPLE: &'static str;
+
+    /// The example for part two, where its text has one of its own
+    const EXAMPLE_TWO: &'static str = Self::EXAMPLE;
+
+    /// Whether there is a part two to answer, which the last day of the event goes without
+    const HAS_PART_TWO: bool = true;
+
+    /// Parse the whole puzzle input, failing if it is malformed
+    fn parse(input: &str) -> Result<Self, Self::Error>
+    where
+        Self: Sized;
+
+    /// Answer part one, failing if the input has no answer
+    fn part_one(&self) -> Result<Self::Output, SolveError>;
+
+    /// Answer part two, failing if the input has no answer or there is no part two
+    fn part_two(&self) -> Result<Self::Output, SolveError>;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::LineError;
+
+    /// Numbers on their own lines, added up or multiplied together
+    #[derive(Debug)]
+    struct Numbers(Vec<u64>);
+    impl Solver for Numbers {
+        type Output = u64;
+        type Error = LineError;
+        const EXAMPLE: &'static str = "2\n3\n4\n";
+
+        fn parse(input: &str) -> Result<Self, LineError> {
+            input
+                .lines()
+                .enumerate()
+                .map(|(idx, line)| line.parse().map_err(|e| LineError::new(idx + 1, e)))
+                .collect::<Result<_, _>>()
+                .map(Self)
+        }
+
+        fn part_one(&self) -> Result<u64, SolveError> {
+            self.0
+                .iter()
+                .try_fold(0_u64, |acc, n| acc.checked_add(*n))
+                .ok_or_else(|| SolveError::new("the sum overflows 64 bits"))
+        }
+
+        fn part_two(&self) -> Result<u64, SolveError> {
+            Ok(self.0.iter().product())
+        }
+    }
+
+    /// Both answers, the way a runner sees any day
+    fn answers<S: Solver>(input: &str) -> (String, String) {
+        let solver = S::parse(input).unwrap();
+        (
+            solver.part_one().unwrap().to_string(),
+            solver.part_two().unwrap().to_string(),
+        )
+    }
+
+    #[test]
+    fn test_solver() {
+        assert_eq!(
+            answers::<Numbers>(Numbers::EXAMPLE),
+            ("9".to_string(), "24".to_string())
+        );
+        assert_eq!(
+            Numbers::parse("2\nthree").unwrap_err(),
+            LineError::new(2, "invalid digit found in string")
+        );
+        let huge = Numbers::parse(&format!("{}\n1", u64::MAX)).unwrap();
+        assert_eq!(
+            huge.part_one(),
+            Err(SolveError::new("the sum overflows 64 bits"))
+        );
+    }
+}
@@ -0,0 +1,48 @@
+//! How many threads the parallel parts of a solver get
+use std::num::NonZeroUsize;
+
+use anyhow::Context;
+use rayon::ThreadPoolBuilder;
+
+/// Size of the rayon pool the parts are solved on
+#[derive(clap::Args, Debug, Clone, Copy, Default)]
+#[command(about = None, long_about = None)]
+pub struct ThreadArgs {
+    /// Threads to solve with (defaults to one per core)
+    #[arg(long, global = true)]
+    pub threads: Option<NonZeroUsize>,
+}
+impl ThreadArgs {
+    /// A rayon pool of the chosen size
+    pub fn builder(&self) -> ThreadPoolBuilder {
+        // Zero leaves rayon to pick one thread per core
+        ThreadPoolBuilder::new().num_threads(self.threads.map_or(0, NonZeroUsize::get))
+    }
+
+    /// Size the global rayon pool, before anything runs on it
+    pub fn init(&self) -> anyhow::Result<()> {
+        self.builder()
+            .build_global()
+            .context("cannot set up the thread pool")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_builder() {
+        let pool = |threads| {
+            ThreadArgs {
+                threads: NonZeroUsize::new(threads),
+            }
+            .builder()
+            .build()
+            .unwrap()
+        };
+        assert_eq!(pool(3).current_num_threads(), 3);
+        let cores = std::thread::available_parallelism().unwrap().get();
+        assert_eq!(pool(0).current_num_threads(), cores);
+    }
+}
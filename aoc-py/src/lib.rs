@@ -0,0 +1,102 @@
+//! Every day's solver as a Python module, for poking at the puzzles from a notebook
+//!
+//! Built and installed into the active virtualenv with `maturin develop --release -m
+//! aoc-py/Cargo.toml`, then:
+//!
+//! ```python
+//! import aoc_py
+//! aoc_py.solve(9, 2, open("inputs/day-9.txt").read())
+//! aoc_py.day_nine(2, open("inputs/day-9.txt").read())
+//! ```
+//!
+//! Answers come back as strings, and a bad day, part or input raises `ValueError`. Day ten is
+//! only built in with `maturin develop --features day-ten`.
+use aoc::days::{self, Part};
+use pyo3::{exceptions::PyValueError, prelude::*};
+
+/// The part numbered 1 or 2
+fn part(part: u8) -> Result<Part, String> {
+    match part {
+        1 => Ok(Part::One),
+        2 => Ok(Part::Two),
+        _ => Err(format!("there is no part {part}, only 1 and 2")),
+    }
+}
+
+/// Answer one part of a day's puzzle, with why not as the error
+fn answer(day: u8, part: u8, input: &str) -> Result<String, String> {
+    days::solve(day, self::part(part)?, input).map_err(|e| e.to_string())
+}
+
+/// Answer one part of a day's puzzle for the input, letting other Python threads run meanwhile
+fn solve_detached(py: Python<'_>, day: u8, part: u8, input: &str) -> PyResult<String> {
+    py.detach(|| answer(day, part, input))
+        .map_err(PyValueError::new_err)
+}
+
+/// Define `day_<name>(part, input)`, answering one part of that day's puzzle
+macro_rules! day {
+    ($name:ident, $day:literal) => {
+        #[doc = concat!("Answer one part of day ", $day, "'s puzzle for the input")]
+        #[pyfunction]
+        fn $name(py: Python<'_>, part: u8, input: &str) -> PyResult<String> {
+            solve_detached(py, $day, part, input)
+        }
+    };
+}
+
+day!(day_one, 1);
+day!(day_two, 2);
+day!(day_three, 3);
+day!(day_four, 4);
+day!(day_five, 5);
+day!(day_six, 6);
+day!(day_seven, 7);
+day!(day_eight, 8);
+day!(day_nine, 9);
+day!(day_ten, 10);
+day!(day_eleven, 11);
+day!(day_twelve, 12);
+
+/// Answer one part of a day's puzzle for the input
+#[pyfunction]
+fn solve(py: Python<'_>, day: u8, part: u8, input: &str) -> PyResult<String> {
+    solve_detached(py, day, part, input)
+}
+
+/// The example from the puzzle text for one part of a day
+#[pyfunction]
+fn example(day: u8, part: u8) -> PyResult<&'static str> {
+    days::example(day, self::part(part).map_err(PyValueError::new_err)?)
+        .map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Every day's solver, answering from an input given as a string
+#[pymodule]
+mod aoc_py {
+    #[pymodule_export]
+    use super::{
+        day_eight, day_eleven, day_five, day_four, day_nine, day_one, day_seven, day_six, day_ten,
+        day_three, day_twelve, day_two, example, solve,
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_answer() {
+        let input = days::example(1, Part::One).unwrap();
+        assert_eq!(answer(1, 1, input), Ok("3".to_string()));
+        assert_eq!(answer(1, 2, input), Ok("6".to_string()));
+        assert_eq!(
+            answer(1, 0, input),
+            Err("there is no part 0, only 1 and 2".to_string())
+        );
+        assert_eq!(
+            answer(13, 1, ""),
+            Err("there is no day 13, only 1 to 12".to_string())
+        );
+    }
+}
@@ -0,0 +1,66 @@
+//! Every day's solver compiled to WebAssembly, for the playground page in `www/`
+//!
+//! Built with
+//! `cargo build -p aoc-wasm --release --target wasm32-unknown-unknown`, then bound for the page
+//! with `wasm-bindgen --target web --out-dir aoc-wasm/www/pkg
+//! target/wasm32-unknown-unknown/release/aoc_wasm.wasm`. Errors reach JavaScript as strings.
+use aoc::days::{self, LAST_DAY, Part};
+use wasm_bindgen::prelude::*;
+
+/// The part numbered 1 or 2
+fn part(part: u8) -> Result<Part, String> {
+    match part {
+        1 => Ok(Part::One),
+        2 => Ok(Part::Two),
+        _ => Err(format!("there is no part {part}, only 1 and 2")),
+    }
+}
+
+/// Answer one part of a day's puzzle for the input
+#[wasm_bindgen]
+pub fn solve(day: u8, part: u8, input: &str) -> Result<String, String> {
+    days::solve(day, self::part(part)?, input).map_err(|e| e.to_string())
+}
+
+/// The example from the puzzle text for one part of a day
+#[wasm_bindgen]
+pub fn example(day: u8, part: u8) -> Result<String, String> {
+    days::example(day, self::part(part)?)
+        .map(str::to_owned)
+        .map_err(|e| e.to_string())
+}
+
+/// Days that can be solved here, each with whether it has a part two
+///
+/// Day ten is left out, as its CBC solver is not built for the browser.
+/// Given as `[day, parts]` pairs flattened into one list, as wasm-bindgen passes no tuples.
+#[wasm_bindgen]
+pub fn days() -> Vec<u8> {
+    (1..=LAST_DAY)
+        .filter_map(|day| {
+            let parts = if days::has_part_two(day).ok()? { 2 } else { 1 };
+            Some([day, parts])
+        })
+        .flatten()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        let input = example(11, 2).unwrap();
+        assert_eq!(solve(11, 2, &input), Ok("2".to_string()));
+        assert_eq!(
+            solve(1, 3, ""),
+            Err("there is no part 3, only 1 and 2".to_string())
+        );
+        assert_eq!(solve(12, 2, ""), Err("day 12 has no part two".to_string()));
+
+        let days = days();
+        assert_eq!(&days[..2], [1, 2]);
+        assert_eq!(&days[days.len() - 2..], [12, 1]);
+    }
+}
@@ -0,0 +1,55 @@
+//! Parsing and both parts of every day, timed against the inputs cached under `inputs/`
+//!
+//! Days without a cached input are skipped; `aoc fetch --day N` downloads one.
+use std::{fs, hint::black_box, path::Path};
+
+use aoc::{
+    days::{LAST_DAY, Visit, visit},
+    fetch::cache_path,
+};
+use aoc_common::solver::Solver;
+use criterion::{Criterion, criterion_group, criterion_main};
+
+/// Benchmark group of one day
+struct Bench<'a> {
+    c: &'a mut Criterion,
+    input: &'a str,
+}
+impl Visit for Bench<'_> {
+    type Output = ();
+
+    fn visit<S: Solver>(self, day: u8) {
+        let solver = match S::parse(self.input) {
+            Ok(solver) => solver,
+            Err(e) => {
+                eprintln!("Skipping day {day}, whose cached input is malformed: {e}");
+                return;
+            }
+        };
+        let mut group = self.c.benchmark_group(format!("day {day}"));
+        group.bench_function("parse", |b| b.iter(|| S::parse(black_box(self.input))));
+
+        group.bench_function("part one", |b| b.iter(|| black_box(&solver).part_one()));
+        if S::HAS_PART_TWO {
+            group.bench_function("part two", |b| b.iter(|| black_box(&solver).part_two()));
+        }
+        group.finish();
+    }
+}
+
+fn days(c: &mut Criterion) {
+    // Benches run from the package directory, while inputs are cached at the workspace root
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+    for day in 1..=LAST_DAY {
+        let Ok(input) = fs::read_to_string(root.join(cache_path(day))) else {
+            eprintln!("Skipping day {day}, which has no cached input");
+            continue;
+        };
+        if let Err(e) = visit(day, Bench { c, input: &input }) {
+            eprintln!("Skipping day {day}: {e}");
+        }
+    }
+}
+
+criterion_group!(benches, days);
+criterion_main!(benches);
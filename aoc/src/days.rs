@@ -0,0 +1,273 @@
+//! Every day's solver, looked up by the day's number
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use aoc_common::{cancel, output::Record, solver::Solver};
+use tracing::debug;
+
+/// Last day of the event
+pub const LAST_DAY: u8 = 12;
+
+/// Part of a day's puzzle
+#[derive(clap::ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Part {
+    #[value(name = "1")]
+    One,
+    #[value(name = "2")]
+    Two,
+}
+impl Part {
+    /// 1 or 2, as the part is written in records and answers
+    pub fn number(self) -> u8 {
+        match self {
+            Self::One => 1,
+            Self::Two => 2,
+        }
+    }
+}
+impl fmt::Display for Part {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::One => f.pad("1"),
+            Self::Two => f.pad("2"),
+        }
+    }
+}
+
+/// Why a day could not be run
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RunError {
+    UnknownDay(u8),
+    /// The day is behind a cargo feature that was left off
+    #[cfg(not(feature = "day-ten"))]
+    NotBuilt(u8),
+    NoPartTwo(u8),
+    /// The run was stopped by `--timeout`, before the day was answered
+    TimedOut(u8),
+    /// The input could not be parsed, for the reason given
+    Parse(u8, String),
+    /// The input was parsed but has no answer, for the reason given
+    Solve(u8, String),
+}
+impl fmt::Display for RunError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownDay(day) => write!(f, "there is no day {day}, only 1 to {LAST_DAY}"),
+            #[cfg(not(feature = "day-ten"))]
+            Self::NotBuilt(day) => write!(
+                f,
+                "day {day} is not built in, rebuild with `--features day-ten` to run it"
+            ),
+            Self::NoPartTwo(day) => write!(f, "day {day} has no part two"),
+            Self::TimedOut(day) => write!(f, "day {day} timed out"),
+            Self::Parse(day, reason) => write!(f, "day {day} input is malformed: {reason}"),
+            Self::Solve(day, reason) => write!(f, "day {day} has no answer: {reason}"),
+        }
+    }
+}
+impl std::error::Error for RunError {}
+
+/// Something to do with a day's solver, whichever type that is
+pub trait Visit {
+    type Output;
+
+    fn visit<S: Solver>(self, day: u8) -> Self::Output;
+}
+
+/// Hand the solver of `day` to `visitor`
+pub fn visit<V: Visit>(day: u8, visitor: V) -> Result<V::Output, RunError> {
+    Ok(match day {
+        1 => visitor.visit::<day_one::Instructions>(day),
+        2 => visitor.visit::<day_two::ProductIds>(day),
+        3 => visitor.visit::<day_three::Banks>(day),
+        4 => visitor.visit::<day_four::Grid>(day),
+        5 => visitor.visit::<day_five::Database>(day),
+        6 => visitor.visit::<day_six::Worksheet>(day),
+        7 => visitor.visit::<day_seven::Manager>(day),
+        8 => visitor.visit::<day_eight::Playground>(day),
+        9 => visitor.visit::<day_nine::Driver>(day),
+        #[cfg(feature = "day-ten")]
+        10 => visitor.visit::<day_ten::Factory>(day),
+        #[cfg(not(feature = "day-ten"))]
+        10 => return Err(RunError::NotBuilt(day)),
+        11 => visitor.visit::<day_eleven::GraphManager>(day),
+        12 => visitor.visit::<day_twelve::Driver>(day),
+        _ => return Err(RunError::UnknownDay(day)),
+    })
+}
+
+/// Answer to one part of a day's puzzle, with how long it took
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timing {
+    pub day: u8,
+    pub part: Part,
+    pub answer: String,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+impl Timing {
+    /// The timing as printed by `--output`
+    pub fn record(&self) -> Record {
+        Record {
+            day: self.day,
+            part: self.part.number().into(),
+            answer: self.answer.clone(),
+            parse: self.parse,
+            solve: self.solve,
+        }
+    }
+}
+
+/// Answer one part of a day's puzzle, timing the parsing and the solving apart
+pub fn run(day: u8, part: Part, input: &str) -> Result<Timing, RunError> {
+    visit(day, Timed { part, input })?
+}
+
+/// Answer one part of a day's puzzle, without timing it
+///
+/// Nothing here reads the clock, which panics in the browser.
+pub fn solve(day: u8, part: Part, input: &str) -> Result<String, RunError> {
+    visit(day, Solve { part, input })?
+}
+
+/// Whether a day's puzzle has a part two
+pub fn has_part_two(day: u8) -> Result<bool, RunError> {
+    visit(day, HasPartTwo)
+}
+
+/// The example from the puzzle text for one part of a day's puzzle
+pub fn example(day: u8, part: Part) -> Result<&'static str, RunError> {
+    visit(day, Example(part))
+}
+
+/// Parse the input and answer the part
+struct Solve<'a> {
+    part: Part,
+    input: &'a str,
+}
+impl Visit for Solve<'_> {
+    type Output = Result<String, RunError>;
+
+    fn visit<S: Solver>(self, day: u8) -> Self::Output {
+        if self.part == Part::Two && !S::HAS_PART_TWO {
+            return Err(RunError::NoPartTwo(day));
+        }
+        let solver = S::parse(self.input).map_err(|e| RunError::Parse(day, e.to_string()))?;
+        let answer = match self.part {
+            Part::One => solver.part_one(),
+            Part::Two => solver.part_two(),
+        };
+        // A solver stopped early only has a partial answer
+        if cancel::is_cancelled() {
+            return Err(RunError::TimedOut(day));
+        }
+        let answer = answer
+            .map_err(|e| RunError::Solve(day, e.to_string()))?
+            .to_string();
+        Ok(answer)
+    }
+}
+
+/// Whether there is a part two
+struct HasPartTwo;
+impl Visit for HasPartTwo {
+    type Output = bool;
+
+    fn visit<S: Solver>(self, _day: u8) -> Self::Output {
+        S::HAS_PART_TWO
+    }
+}
+
+/// The example for the part
+struct Example(Part);
+impl Visit for Example {
+    type Output = &'static str;
+
+    fn visit<S: Solver>(self, _day: u8) -> Self::Output {
+        match self.0 {
+            Part::One => S::EXAMPLE,
+            Part::Two => S::EXAMPLE_TWO,
+        }
+    }
+}
+
+/// Parse the input and answer the part, timing each
+struct Timed<'a> {
+    part: Part,
+    input: &'a str,
+}
+impl Visit for Timed<'_> {
+    type Output = Result<Timing, RunError>;
+
+    fn visit<S: Solver>(self, day: u8) -> Self::Output {
+        if self.part == Part::Two && !S::HAS_PART_TWO {
+            return Err(RunError::NoPartTwo(day));
+        }
+        if cancel::is_cancelled() {
+            return Err(RunError::TimedOut(day));
+        }
+        let start = Instant::now();
+        let solver = S::parse(self.input).map_err(|e| RunError::Parse(day, e.to_string()))?;
+        let parse = start.elapsed();
+
+        debug!(day, ?parse, "Parsed");
+
+        let start = Instant::now();
+        let answer = match self.part {
+            Part::One => solver.part_one(),
+            Part::Two => solver.part_two(),
+        };
+        let solve = start.elapsed();
+        // A solver stopped early only has a partial answer
+        if cancel::is_cancelled() {
+            return Err(RunError::TimedOut(day));
+        }
+        let answer = answer
+            .map_err(|e| RunError::Solve(day, e.to_string()))?
+            .to_string();
+        debug!(day, part = %self.part, ?solve, "Solved");
+        Ok(Timing {
+            day,
+            part: self.part,
+            answer,
+            parse,
+            solve,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve() {
+        let input = example(1, Part::One).unwrap();
+        assert_eq!(solve(1, Part::One, input), Ok("3".to_string()));
+        assert_eq!(solve(1, Part::Two, input), Ok("6".to_string()));
+        let input = example(11, Part::Two).unwrap();
+        assert_eq!(solve(11, Part::Two, input), Ok("2".to_string()));
+        assert_eq!(solve(12, Part::Two, ""), Err(RunError::NoPartTwo(12)));
+        assert_eq!(has_part_two(12), Ok(false));
+        assert_eq!(has_part_two(1), Ok(true));
+        assert_eq!(solve(13, Part::One, ""), Err(RunError::UnknownDay(13)));
+        assert_eq!(
+            solve(9, Part::One, "7,1\n11;1").unwrap_err().to_string(),
+            "day 9 input is malformed: line 2: expected x,y, found \"11;1\""
+        );
+        assert_eq!(
+            solve(6, Part::One, "1 x\n+ *"),
+            Err(RunError::Solve(
+                6,
+                "group 2: \"x\" is not a number".to_string()
+            ))
+        );
+        assert!(matches!(
+            solve(9, Part::One, ""),
+            Err(RunError::Solve(9, _))
+        ));
+    }
+}
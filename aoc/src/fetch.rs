@@ -0,0 +1,112 @@
+//! Puzzle inputs downloaded with the session cookie and cached on disk
+use std::{
+    env, fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Year of the event
+pub const YEAR: u16 = 2025;
+
+/// Directory the downloaded inputs are cached in, relative to where the runner is started
+pub const INPUTS_DIR: &str = "inputs";
+
+/// Sent with every request, as the puzzle site asks of automated tools
+pub const USER_AGENT: &str = "github.com/mkagie/aoc-2025";
+
+/// Failure talking to the puzzle site or keeping what it sent
+#[derive(Debug)]
+pub enum FetchError {
+    NoSession,
+    Http(ureq::Error),
+    Io(io::Error),
+}
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoSession => write!(
+                f,
+                "no session token, set AOC_SESSION or write it to ~/.config/aoc/session"
+            ),
+            Self::Http(e) => write!(f, "request to the puzzle site failed: {e}"),
+            Self::Io(e) => write!(f, "cannot use {INPUTS_DIR}/: {e}"),
+        }
+    }
+}
+impl std::error::Error for FetchError {}
+impl From<ureq::Error> for FetchError {
+    fn from(e: ureq::Error) -> Self {
+        Self::Http(e)
+    }
+}
+impl From<io::Error> for FetchError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Session cookie from `AOC_SESSION`, or else from `aoc/session` in the config directory
+pub fn session() -> Option<String> {
+    token(env::var("AOC_SESSION").ok()).or_else(|| {
+        let path = config_dir()?.join("aoc").join("session");
+        token(fs::read_to_string(path).ok())
+    })
+}
+
+/// A session token with the surrounding whitespace dropped, unless nothing is left
+fn token(value: Option<String>) -> Option<String> {
+    value
+        .map(|value| value.trim().to_owned())
+        .filter(|value| !value.is_empty())
+}
+
+/// `$XDG_CONFIG_HOME`, falling back to `~/.config`
+fn config_dir() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+}
+
+/// Where the day's input is cached
+pub fn cache_path(day: u8) -> PathBuf {
+    Path::new(INPUTS_DIR).join(format!("day-{day}.txt"))
+}
+
+/// Download the day's input, replacing any cached copy
+pub fn fetch(day: u8) -> Result<String, FetchError> {
+    let session = session().ok_or(FetchError::NoSession)?;
+    let input = ureq::get(format!("https://adventofcode.com/{YEAR}/day/{day}/input"))
+        .header("Cookie", format!("session={session}"))
+        .header("User-Agent", USER_AGENT)
+        .call()?
+        .body_mut()
+        .read_to_string()?;
+
+    fs::create_dir_all(INPUTS_DIR)?;
+    fs::write(cache_path(day), &input)?;
+    Ok(input)
+}
+
+/// The day's input, downloaded only if it is not cached yet
+pub fn input(day: u8) -> Result<String, FetchError> {
+    match fs::read_to_string(cache_path(day)) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => fetch(day),
+        cached => Ok(cached?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_token() {
+        assert_eq!(
+            token(Some("abc123\n".to_string())),
+            Some("abc123".to_string())
+        );
+        assert_eq!(token(Some("  \n".to_string())), None);
+        assert_eq!(token(None), None);
+        assert_eq!(cache_path(9), Path::new("inputs/day-9.txt"));
+    }
+}
@@ -0,0 +1,16 @@
+//! Every day's solver behind one runner, with puzzle inputs and answers going through the site
+//!
+//! Without the `runner` feature only the solvers are built, which is all the browser needs.
+pub mod days;
+#[cfg(feature = "runner")]
+pub mod fetch;
+#[cfg(feature = "runner")]
+pub mod new_day;
+#[cfg(feature = "runner")]
+pub mod serve;
+#[cfg(feature = "runner")]
+pub mod submit;
+#[cfg(feature = "runner")]
+pub mod summary;
+#[cfg(feature = "runner")]
+pub mod verify;
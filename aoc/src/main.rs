@@ -0,0 +1,282 @@
+//! Command line executable running any day's puzzle
+use std::process::ExitCode;
+
+use std::path::Path;
+
+use anyhow::{Context, anyhow, bail};
+use aoc::{
+    days::{self, LAST_DAY, Part, run, solve},
+    fetch, new_day, serve,
+    submit::{self, Verdict},
+    summary::{ReportFormat, Summary},
+    verify::{ANSWERS_FILE, Answers, Check, Verification, input_hash},
+};
+use aoc_common::{
+    cancel::{self, TimeoutArgs},
+    input::InputArgs,
+    log::LogArgs,
+    output::{self, Format, OutputArgs},
+    threads::ThreadArgs,
+};
+use clap::Parser;
+use tracing::warn;
+
+#[derive(Parser, Debug)]
+#[command(author, version, about, long_about = None)]
+struct Args {
+    #[command(subcommand)]
+    command: Command,
+
+    #[command(flatten)]
+    log: LogArgs,
+
+    #[command(flatten)]
+    threads: ThreadArgs,
+
+    #[command(flatten)]
+    timeout: TimeoutArgs,
+}
+
+#[derive(clap::Subcommand, Debug)]
+enum Command {
+    /// Answer one part of a day's puzzle
+    Run {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=LAST_DAY as i64))]
+        day: u8,
+
+        #[arg(long, value_enum)]
+        part: Part,
+
+        /// Input file, or `-` for stdin (defaults to the day's cached input, downloaded if needed)
+        #[arg(short)]
+        input_file: Option<String>,
+
+        /// Run on the example from the puzzle text instead
+        #[arg(long, conflicts_with = "input_file")]
+        example: bool,
+
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Answer both parts of every day with a cached input, in a table with their timings
+    ///
+    /// With `--output json`, each part is a line of JSON instead
+    All {
+        #[command(flatten)]
+        output: OutputArgs,
+    },
+    /// Answer both parts of every day with a cached input, writing the answers and timings as a
+    /// markdown table or CSV
+    Report {
+        #[arg(long, value_enum, default_value_t)]
+        format: ReportFormat,
+
+        /// File to write the report to, instead of printing it
+        #[arg(short)]
+        output_file: Option<String>,
+    },
+    /// Answer one part of a day's puzzle and post the answer, logging the verdict in
+    /// `inputs/submissions.tsv` and keeping a correct answer in `answers.toml`
+    Submit {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=LAST_DAY as i64))]
+        day: u8,
+
+        #[arg(long, value_enum)]
+        part: Part,
+
+        /// Input file, or `-` for stdin (defaults to the day's cached input, downloaded if needed)
+        #[arg(short)]
+        input_file: Option<String>,
+    },
+    /// Answer both parts of every day with a cached input again, checking each against the
+    /// answer known for that input in `answers.toml`
+    Verify {
+        /// Only verify this day
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=LAST_DAY as i64))]
+        day: Option<u8>,
+
+        /// Keep the answers of parts without a known answer as the right ones
+        #[arg(long)]
+        save: bool,
+    },
+    /// Answer puzzles over HTTP: `POST /solve/{day}/{part}` with the input as the body answers
+    /// with the JSON of `--output json`
+    Serve {
+        /// Address to listen on
+        #[arg(long, default_value = "127.0.0.1:8080")]
+        addr: String,
+    },
+    /// Copy the template crate into a new day and add it to the workspace, run from the
+    /// workspace root
+    NewDay {
+        /// Day to scaffold (defaults to the first day without a crate)
+        #[arg(long)]
+        day: Option<u8>,
+    },
+    /// Download a day's input into the cache, using the session token from `AOC_SESSION` or
+    /// `~/.config/aoc/session`
+    Fetch {
+        #[arg(long, value_parser = clap::value_parser!(u8).range(1..=LAST_DAY as i64))]
+        day: u8,
+    },
+}
+
+fn main() -> ExitCode {
+    output::exit(try_main())
+}
+
+fn try_main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    args.log.init();
+    args.threads.init()?;
+    if matches!(args.command, Command::Serve { .. }) && args.timeout.timeout.is_some() {
+        bail!("--timeout would stop the server, not a slow answer");
+    }
+    args.timeout.init();
+
+    match args.command {
+        Command::Run {
+            day,
+            part,
+            input_file,
+            example,
+            output,
+        } => {
+            let (name, s) = read_input(
+                day,
+                part,
+                InputArgs {
+                    input_file,
+                    example,
+                },
+            )?;
+            let timing = run(day, part, &s).context(name)?;
+            output.print(&timing.record());
+        }
+        Command::All { output } => {
+            let summary = Summary::run_all();
+            match output.output {
+                Format::Text => print!("{summary}"),
+                Format::Json => {
+                    for timing in &summary.timings {
+                        output.print(&timing.record());
+                    }
+                    for (day, reason) in &summary.skipped {
+                        warn!("Skipped day {day}: {reason}");
+                    }
+                }
+            }
+            // The days answered before the timeout are still shown
+            cancel::check()?;
+        }
+        Command::Report {
+            format,
+            output_file,
+        } => {
+            let summary = Summary::run_all();
+            for (day, reason) in &summary.skipped {
+                warn!("Skipped day {day}: {reason}");
+            }
+            let report = summary.report(format);
+            match output_file {
+                Some(path) => {
+                    std::fs::write(&path, report).with_context(|| format!("cannot write {path}"))?
+                }
+                None => print!("{report}"),
+            }
+            cancel::check()?;
+        }
+        Command::Submit {
+            day,
+            part,
+            input_file,
+        } => {
+            let (name, s) = read_input(
+                day,
+                part,
+                InputArgs {
+                    input_file,
+                    example: false,
+                },
+            )?;
+            let answer = solve(day, part, &s).context(name)?;
+            println!("{answer}");
+
+            let (verdict, logged) = submit::submit(day, part, &answer)?;
+            if logged {
+                println!("Already submitted: {verdict}");
+            } else {
+                println!("{verdict}");
+            }
+            if verdict == Verdict::Correct {
+                let path = Path::new(ANSWERS_FILE);
+                let mut answers = Answers::load(path)?;
+                if answers.insert(day, part, &input_hash(&s), &answer) {
+                    answers.save(path)?;
+                }
+            }
+        }
+        Command::Verify { day, save } => {
+            let path = Path::new(ANSWERS_FILE);
+            let mut answers = Answers::load(path)?;
+            let verification = Verification::run(&answers, day);
+            for verified in &verification.verified {
+                println!("{verified}");
+            }
+            for (day, reason) in &verification.skipped {
+                warn!("Skipped day {day}: {reason}");
+            }
+            if save {
+                let mut changed = false;
+                for verified in &verification.verified {
+                    if verified.check == Check::Unknown {
+                        let timing = &verified.timing;
+                        changed |= answers.insert(
+                            timing.day,
+                            timing.part,
+                            &verified.input,
+                            &timing.answer,
+                        );
+                    }
+                }
+                if changed {
+                    answers.save(path)?;
+                }
+            }
+            cancel::check()?;
+            match verification.failures() {
+                0 => {}
+                1 => bail!("1 answer is wrong"),
+                n => bail!("{n} answers are wrong"),
+            }
+        }
+        Command::Serve { addr } => serve::serve(&addr)?,
+        Command::NewDay { day } => {
+            let root = Path::new(".");
+            let day = day
+                .or_else(|| new_day::next_day(root))
+                .ok_or_else(|| anyhow!("every day up to {} has a crate", new_day::MAX_DAY))?;
+            let dir = new_day::new_day(root, day)?;
+            println!("Created {}", dir.display());
+            println!("Add it to the runner in aoc/src/days.rs to run it with `aoc run`");
+        }
+        Command::Fetch { day } => {
+            fetch::fetch(day)?;
+            println!("{}", fetch::cache_path(day).display());
+        }
+    }
+
+    Ok(())
+}
+
+/// Name and contents of the input file or the example, or else of the day's cached input
+fn read_input(day: u8, part: Part, input: InputArgs) -> anyhow::Result<(String, String)> {
+    if input.input_file.is_none() && !input.example {
+        return Ok((
+            fetch::cache_path(day).display().to_string(),
+            fetch::input(day)?,
+        ));
+    }
+    let s = input.read_to_string(days::example(day, part)?)?;
+    Ok((input.name().to_owned(), s))
+}
@@ -0,0 +1,154 @@
+//! New days scaffolded from the `template` crate and added to the workspace
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Files of the template crate, relative to its root
+const TEMPLATE: [(&str, &str); 4] = [
+    ("Cargo.toml", include_str!("../../template/Cargo.toml")),
+    (
+        "examples/example.txt",
+        include_str!("../../template/examples/example.txt"),
+    ),
+    ("src/lib.rs", include_str!("../../template/src/lib.rs")),
+    ("src/main.rs", include_str!("../../template/src/main.rs")),
+];
+
+/// Last day a crate can be named for
+pub const MAX_DAY: u8 = 25;
+
+/// Failure scaffolding a day
+#[derive(Debug)]
+pub enum NewDayError {
+    /// Days are numbered 1 to `MAX_DAY`
+    OutOfRange(u8),
+    Exists(PathBuf),
+    /// The manifest has no `members = [...]` list to add the day to
+    NoMembers,
+    Io(io::Error),
+}
+impl fmt::Display for NewDayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::OutOfRange(day) => write!(f, "day {day} is not between 1 and {MAX_DAY}"),
+            Self::Exists(path) => write!(f, "{} already exists", path.display()),
+            Self::NoMembers => write!(f, "Cargo.toml has no workspace members list"),
+            Self::Io(e) => write!(f, "{e}"),
+        }
+    }
+}
+impl std::error::Error for NewDayError {}
+impl From<io::Error> for NewDayError {
+    fn from(e: io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Crate name of a day, spelled out like `day-thirteen`
+pub fn crate_name(day: u8) -> Option<String> {
+    const ONES: [&str; 20] = [
+        "",
+        "one",
+        "two",
+        "three",
+        "four",
+        "five",
+        "six",
+        "seven",
+        "eight",
+        "nine",
+        "ten",
+        "eleven",
+        "twelve",
+        "thirteen",
+        "fourteen",
+        "fifteen",
+        "sixteen",
+        "seventeen",
+        "eighteen",
+        "nineteen",
+    ];
+    match day {
+        1..20 => Some(format!("day-{}", ONES[day as usize])),
+        20 => Some("day-twenty".to_string()),
+        21..=MAX_DAY => Some(format!("day-twenty-{}", ONES[day as usize - 20])),
+        _ => None,
+    }
+}
+
+/// First day without a crate in the workspace at `root`
+pub fn next_day(root: &Path) -> Option<u8> {
+    (1..=MAX_DAY).find(|&day| crate_name(day).is_some_and(|name| !root.join(name).exists()))
+}
+
+/// The manifest with `member` added to its workspace members, which are kept sorted
+pub fn add_member(manifest: &str, member: &str) -> Result<String, NewDayError> {
+    let (head, rest) = manifest
+        .split_once("members = [")
+        .ok_or(NewDayError::NoMembers)?;
+    let (list, tail) = rest.split_once(']').ok_or(NewDayError::NoMembers)?;
+
+    let mut members: Vec<&str> = list
+        .split(',')
+        .map(|entry| entry.trim().trim_matches('"'))
+        .filter(|entry| !entry.is_empty())
+        .collect();
+    if !members.contains(&member) {
+        members.push(member);
+    }
+    members.sort_unstable();
+
+    let list: String = members
+        .iter()
+        .map(|member| format!("    \"{member}\",\n"))
+        .collect();
+    Ok(format!("{head}members = [\n{list}]{tail}"))
+}
+
+/// Copy the template into a crate for `day` under the workspace at `root`, returning its path
+pub fn new_day(root: &Path, day: u8) -> Result<PathBuf, NewDayError> {
+    let name = crate_name(day).ok_or(NewDayError::OutOfRange(day))?;
+    let dir = root.join(&name);
+    if dir.exists() {
+        return Err(NewDayError::Exists(dir));
+    }
+    let manifest_path = root.join("Cargo.toml");
+    let manifest = add_member(&fs::read_to_string(&manifest_path)?, &name)?;
+
+    for (path, contents) in TEMPLATE {
+        let path = dir.join(path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let contents = contents
+            .replace("{{project-name}}", &name)
+            .replace("{{crate_name}}", &name.replace('-', "_"));
+        fs::write(path, contents)?;
+    }
+    fs::write(manifest_path, manifest)?;
+    Ok(dir)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_add_member() {
+        assert_eq!(crate_name(13).as_deref(), Some("day-thirteen"));
+        assert_eq!(crate_name(25).as_deref(), Some("day-twenty-five"));
+        assert_eq!(crate_name(26), None);
+
+        let manifest =
+            "[workspace]\nmembers = [\n    \"aoc\",\n    \"day-two\",\n]\nresolver = \"3\"\n";
+        assert_eq!(
+            add_member(manifest, "day-thirteen").unwrap(),
+            "[workspace]\nmembers = [\n    \"aoc\",\n    \"day-thirteen\",\n    \"day-two\",\n]\nresolver = \"3\"\n"
+        );
+        assert!(matches!(
+            add_member("[package]\n", "day-one"),
+            Err(NewDayError::NoMembers)
+        ));
+    }
+}
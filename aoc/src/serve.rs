@@ -0,0 +1,166 @@
+//! Puzzles answered over HTTP, for bots that would rather not shell out to the runner
+//!
+//! `POST /solve/{day}/{part}` with the input as the body answers with the same JSON as
+//! `--output json`. Anything wrong comes back as `{"error": "..."}` with a 4xx or 5xx status.
+use std::{
+    fmt,
+    io::{self, Read},
+    thread,
+    time::Instant,
+};
+
+use tiny_http::{Header, Method, Request, Response, Server};
+use tracing::{info, warn};
+
+use crate::days::{self, Part, RunError};
+
+/// Largest input accepted, far above any day's
+const MAX_INPUT: u64 = 1 << 20;
+
+/// The server could not listen on the address
+#[derive(Debug)]
+pub struct BindError {
+    addr: String,
+    reason: String,
+}
+impl fmt::Display for BindError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "cannot listen on {}: {}", self.addr, self.reason)
+    }
+}
+impl std::error::Error for BindError {}
+
+/// Status and JSON body of a response
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Reply {
+    pub status: u16,
+    pub body: String,
+}
+impl Reply {
+    fn error(status: u16, message: impl fmt::Display) -> Self {
+        Self {
+            status,
+            body: serde_json::json!({ "error": message.to_string() }).to_string(),
+        }
+    }
+}
+
+/// Day and part named by a `/solve/{day}/{part}` path
+fn route(path: &str) -> Option<(u8, Part)> {
+    let (day, part) = path.strip_prefix("/solve/")?.split_once('/')?;
+    let part = match part {
+        "1" => Part::One,
+        "2" => Part::Two,
+        _ => return None,
+    };
+    Some((day.parse().ok()?, part))
+}
+
+/// Answer a request for `url` with the input as its body
+pub fn reply(method: &Method, url: &str, input: io::Result<String>) -> Reply {
+    let path = url.split_once('?').map_or(url, |(path, _)| path);
+    let Some((day, part)) = route(path) else {
+        return Reply::error(
+            404,
+            format!("no such path {path}, only /solve/{{day}}/{{part}}"),
+        );
+    };
+    if *method != Method::Post {
+        return Reply::error(405, format!("{method} is not allowed, only POST"));
+    }
+    let input = match input {
+        Ok(input) if input.len() as u64 > MAX_INPUT => {
+            return Reply::error(413, format!("the input is over {MAX_INPUT} bytes"));
+        }
+        Ok(input) => input,
+        Err(e) => return Reply::error(400, format!("cannot read the input: {e}")),
+    };
+    match days::run(day, part, &input) {
+        Ok(timing) => Reply {
+            status: 200,
+            body: timing.record().json(),
+        },
+        Err(e) => {
+            let status = match e {
+                RunError::UnknownDay(_) | RunError::NoPartTwo(_) => 404,
+                RunError::Parse(..) | RunError::Solve(..) => 422,
+                #[cfg(not(feature = "day-ten"))]
+                RunError::NotBuilt(_) => 501,
+                RunError::TimedOut(_) => 503,
+            };
+            Reply::error(status, e)
+        }
+    }
+}
+
+/// Read the body, answer it and send the answer back
+fn respond(mut request: Request) {
+    let start = Instant::now();
+    let mut input = String::new();
+    let read = request
+        .as_reader()
+        .take(MAX_INPUT + 1)
+        .read_to_string(&mut input)
+        .map(|_| input);
+    let reply = reply(request.method(), request.url(), read);
+    info!(
+        "{} {} -> {} in {:?}",
+        request.method(),
+        request.url(),
+        reply.status,
+        start.elapsed()
+    );
+
+    let header =
+        Header::from_bytes("Content-Type", "application/json").expect("The header is valid ASCII");
+    let response = Response::from_string(reply.body)
+        .with_status_code(reply.status)
+        .with_header(header);
+    if let Err(e) = request.respond(response) {
+        warn!("Cannot send the answer: {e}");
+    }
+}
+
+/// Answer requests on `addr`, each on its own thread, until the process is stopped
+pub fn serve(addr: &str) -> Result<(), BindError> {
+    let server = Server::http(addr).map_err(|e| BindError {
+        addr: addr.to_owned(),
+        reason: e.to_string(),
+    })?;
+    info!("Listening on http://{addr}");
+    for request in server.incoming_requests() {
+        thread::spawn(move || respond(request));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reply() {
+        let input = days::example(1, Part::One).unwrap();
+        let reply = super::reply(&Method::Post, "/solve/1/2", Ok(input.to_owned()));
+        assert_eq!(reply.status, 200);
+        assert!(
+            reply
+                .body
+                .starts_with(r#"{"day":1,"part":2,"answer":"6","parse_ms":"#)
+        );
+
+        let reply = |method, url| super::reply(&method, url, Ok(String::new())).status;
+        assert_eq!(reply(Method::Get, "/solve/1/2"), 405);
+        assert_eq!(reply(Method::Post, "/solve/1/3"), 404);
+        assert_eq!(reply(Method::Post, "/solve/13/1"), 404);
+        assert_eq!(reply(Method::Post, "/solve/12/2"), 404);
+        assert_eq!(reply(Method::Post, "/answer"), 404);
+
+        let reply = super::reply(&Method::Post, "/solve/9/1?bot=1", Ok("7;1".to_string()));
+        assert_eq!(reply.status, 422);
+        assert_eq!(
+            reply.body,
+            r#"{"error":"day 9 input is malformed: line 1: expected x,y, found \"7;1\""}"#
+        );
+    }
+}
@@ -0,0 +1,144 @@
+//! Answers posted to the puzzle site, with every verdict kept in a local log
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+};
+
+use crate::{
+    days::Part,
+    fetch::{FetchError, INPUTS_DIR, USER_AGENT, YEAR, session},
+};
+
+/// What the puzzle site made of an answer
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    Correct,
+    TooHigh,
+    TooLow,
+    /// Wrong, without saying which way
+    Wrong,
+    /// Answered too soon after the last attempt, with the wait left as the site wrote it
+    TooSoon(String),
+    /// The part was already solved, so nothing was checked
+    AlreadySolved,
+    Unrecognized,
+}
+impl Verdict {
+    /// Read the verdict out of the page the site answers with
+    pub fn from_response(page: &str) -> Self {
+        if page.contains("That's the right answer") {
+            Self::Correct
+        } else if page.contains("That's not the right answer") {
+            if page.contains("too high") {
+                Self::TooHigh
+            } else if page.contains("too low") {
+                Self::TooLow
+            } else {
+                Self::Wrong
+            }
+        } else if page.contains("You gave an answer too recently") {
+            let left = page
+                .split_once("You have ")
+                .and_then(|(_, rest)| rest.split_once(" left to wait"))
+                .map_or("some time", |(left, _)| left);
+            Self::TooSoon(left.to_owned())
+        } else if page.contains("Did you already complete it?") {
+            Self::AlreadySolved
+        } else {
+            Self::Unrecognized
+        }
+    }
+
+    /// The verdict of a log entry, if it settles the answer for good
+    fn settled(text: &str) -> Option<Self> {
+        match text {
+            "correct" => Some(Self::Correct),
+            "too high" => Some(Self::TooHigh),
+            "too low" => Some(Self::TooLow),
+            "wrong" => Some(Self::Wrong),
+            _ => None,
+        }
+    }
+}
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Correct => write!(f, "correct"),
+            Self::TooHigh => write!(f, "too high"),
+            Self::TooLow => write!(f, "too low"),
+            Self::Wrong => write!(f, "wrong"),
+            Self::TooSoon(left) => write!(f, "too soon, {left} left to wait"),
+            Self::AlreadySolved => write!(f, "already solved"),
+            Self::Unrecognized => write!(f, "unrecognized response"),
+        }
+    }
+}
+
+/// Log of every submission, one tab separated `day part answer verdict` line each
+pub fn log_path() -> PathBuf {
+    Path::new(INPUTS_DIR).join("submissions.tsv")
+}
+
+/// Verdict already logged for this answer, if it was settled
+pub fn logged(log: &str, day: u8, part: Part, answer: &str) -> Option<Verdict> {
+    let prefix = format!("{day}\t{part}\t{answer}\t");
+    log.lines()
+        .filter_map(|line| line.strip_prefix(&prefix))
+        .find_map(Verdict::settled)
+}
+
+/// Post the answer and log the verdict, unless the log already settles it
+///
+/// Returns the verdict and whether it came from the log
+pub fn submit(day: u8, part: Part, answer: &str) -> Result<(Verdict, bool), FetchError> {
+    let log = match fs::read_to_string(log_path()) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+        log => log?,
+    };
+    if let Some(verdict) = logged(&log, day, part, answer) {
+        return Ok((verdict, true));
+    }
+
+    let session = session().ok_or(FetchError::NoSession)?;
+    let page = ureq::post(format!("https://adventofcode.com/{YEAR}/day/{day}/answer"))
+        .header("Cookie", format!("session={session}"))
+        .header("User-Agent", USER_AGENT)
+        .send_form([("level", part.to_string()), ("answer", answer.to_owned())])?
+        .body_mut()
+        .read_to_string()?;
+    let verdict = Verdict::from_response(&page);
+
+    fs::create_dir_all(INPUTS_DIR)?;
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(log_path())?;
+    writeln!(file, "{day}\t{part}\t{answer}\t{verdict}")?;
+    Ok((verdict, false))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_verdict() {
+        let page = "<article><p>That's not the right answer; your answer is too high.</p>";
+        assert_eq!(Verdict::from_response(page), Verdict::TooHigh);
+        let page = "<p>That's the right answer! You are one gold star closer.</p>";
+        assert_eq!(Verdict::from_response(page), Verdict::Correct);
+        let page = "<p>You gave an answer too recently; you have to wait after submitting an \
+                    answer before trying again.  You have 38s left to wait.</p>";
+        assert_eq!(
+            Verdict::from_response(page),
+            Verdict::TooSoon("38s".to_string())
+        );
+
+        let log = "9\t2\t100\ttoo soon, 38s left to wait\n9\t2\t100\ttoo low\n9\t1\t7\tcorrect\n";
+        assert_eq!(logged(log, 9, Part::Two, "100"), Some(Verdict::TooLow));
+        assert_eq!(logged(log, 9, Part::One, "100"), None);
+        assert_eq!(logged(log, 9, Part::One, "7"), Some(Verdict::Correct));
+    }
+}
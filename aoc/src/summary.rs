@@ -0,0 +1,221 @@
+//! Every day run against its cached input, for a table of answers and timings
+use std::{fmt, fs, time::Duration};
+
+use crate::{
+    days::{LAST_DAY, Part, RunError, Timing, run},
+    fetch::cache_path,
+};
+
+/// File format of a report
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// A markdown table ending with the total
+    #[default]
+    Markdown,
+    /// A header line, then one line per part
+    Csv,
+}
+
+/// Milliseconds to the microsecond, as reports write durations
+fn millis(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1e3)
+}
+
+/// Answers of every day that could be run, and why the others were not
+#[derive(Debug, Clone, Default)]
+pub struct Summary {
+    pub timings: Vec<Timing>,
+    /// Day and the reason it was skipped
+    pub skipped: Vec<(u8, String)>,
+}
+impl Summary {
+    /// Run both parts of every day that has a cached input, skipping those that are malformed
+    pub fn run_all() -> Self {
+        let mut summary = Self::default();
+        for day in 1..=LAST_DAY {
+            let path = cache_path(day);
+            let Ok(input) = fs::read_to_string(&path) else {
+                summary
+                    .skipped
+                    .push((day, format!("no input at {}", path.display())));
+                continue;
+            };
+            for part in [Part::One, Part::Two] {
+                match run(day, part, &input) {
+                    Ok(timing) => summary.timings.push(timing),
+                    Err(RunError::NoPartTwo(_)) => {}
+                    Err(e) => {
+                        summary.skipped.push((day, e.to_string()));
+                        break;
+                    }
+                }
+            }
+        }
+        summary
+    }
+
+    /// The answers and timings in milliseconds, for a results log
+    ///
+    /// Skipped days are left out.
+    pub fn report(&self, format: ReportFormat) -> String {
+        let mut out = String::new();
+        match format {
+            ReportFormat::Markdown => {
+                out.push_str("| Day | Part | Answer | Parse (ms) | Solve (ms) |\n");
+                out.push_str("|----:|-----:|-------:|-----------:|-----------:|\n");
+                for timing in &self.timings {
+                    out.push_str(&format!(
+                        "| {} | {} | {} | {} | {} |\n",
+                        timing.day,
+                        timing.part,
+                        timing.answer.replace('|', "\\|"),
+                        millis(timing.parse),
+                        millis(timing.solve)
+                    ));
+                }
+                let (parse, solve) = self.total();
+                out.push_str(&format!(
+                    "| **Total** | | | {} | {} |\n",
+                    millis(parse),
+                    millis(solve)
+                ));
+            }
+            ReportFormat::Csv => {
+                out.push_str("day,part,answer,parse_ms,solve_ms\n");
+                for timing in &self.timings {
+                    // Quote answers that would split the line, doubling their quotes
+                    let answer = if timing.answer.contains([',', '"', '\n']) {
+                        format!("\"{}\"", timing.answer.replace('"', "\"\""))
+                    } else {
+                        timing.answer.clone()
+                    };
+                    out.push_str(&format!(
+                        "{},{},{answer},{},{}\n",
+                        timing.day,
+                        timing.part,
+                        millis(timing.parse),
+                        millis(timing.solve)
+                    ));
+                }
+            }
+        }
+        out
+    }
+
+    /// Time spent parsing and solving, over every part
+    pub fn total(&self) -> (Duration, Duration) {
+        self.timings.iter().fold(
+            (Duration::ZERO, Duration::ZERO),
+            |(parse, solve), timing| (parse + timing.parse, solve + timing.solve),
+        )
+    }
+}
+impl fmt::Display for Summary {
+    /// A row per part with its answer and timings, then the total and the skipped days
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // The total time sits under the answers
+        let (parse, solve) = self.total();
+        let total = format!("{:?}", parse + solve);
+        let width = self
+            .timings
+            .iter()
+            .map(|timing| timing.answer.len())
+            .chain(["Answer".len(), total.chars().count()])
+            .max()
+            .unwrap_or_default();
+        writeln!(
+            f,
+            "Day  Part  {:<width$}  {:>12}  {:>12}",
+            "Answer", "Parse", "Solve"
+        )?;
+        for timing in &self.timings {
+            writeln!(
+                f,
+                "{:>3}  {:>4}  {:<width$}  {:>12}  {:>12}",
+                timing.day,
+                timing.part,
+                timing.answer,
+                format!("{:?}", timing.parse),
+                format!("{:?}", timing.solve),
+            )?;
+        }
+        writeln!(
+            f,
+            "{:<9}  {:<width$}  {:>12}  {:>12}",
+            "Total",
+            total,
+            format!("{parse:?}"),
+            format!("{solve:?}"),
+        )?;
+        for (day, reason) in &self.skipped {
+            writeln!(f, "Skipped day {day}: {reason}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_table() {
+        let timing = |day, part, answer: &str, micros| Timing {
+            day,
+            part,
+            answer: answer.to_string(),
+            parse: Duration::from_micros(micros),
+            solve: Duration::from_micros(2 * micros),
+        };
+        let summary = Summary {
+            timings: vec![
+                timing(1, Part::One, "1234", 10),
+                timing(1, Part::Two, "5", 20),
+            ],
+            skipped: vec![(2, "no input at inputs/day-2.txt".to_string())],
+        };
+        assert_eq!(
+            summary.to_string(),
+            "\
+Day  Part  Answer         Parse         Solve
+  1     1  1234            10µs          20µs
+  1     2  5               20µs          40µs
+Total      90µs            30µs          60µs
+Skipped day 2: no input at inputs/day-2.txt
+"
+        );
+    }
+
+    #[test]
+    fn test_report() {
+        let timing = |part, answer: &str| Timing {
+            day: 3,
+            part,
+            answer: answer.to_string(),
+            parse: Duration::from_micros(1500),
+            solve: Duration::from_micros(250),
+        };
+        let summary = Summary {
+            timings: vec![timing(Part::One, "17"), timing(Part::Two, "a,b")],
+            skipped: Vec::new(),
+        };
+        assert_eq!(
+            summary.report(ReportFormat::Markdown),
+            "\
+| Day | Part | Answer | Parse (ms) | Solve (ms) |
+|----:|-----:|-------:|-----------:|-----------:|
+| 3 | 1 | 17 | 1.500 | 0.250 |
+| 3 | 2 | a,b | 1.500 | 0.250 |
+| **Total** | | | 3.000 | 0.500 |
+"
+        );
+        assert_eq!(
+            summary.report(ReportFormat::Csv),
+            "\
+day,part,answer,parse_ms,solve_ms
+3,1,17,1.500,0.250
+3,2,\"a,b\",1.500,0.250
+"
+        );
+    }
+}
@@ -0,0 +1,255 @@
+//! Answers known to be right, kept in `answers.toml` so the solvers can be checked against them
+//! after a refactor
+use std::{fmt, fs, io, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    days::{LAST_DAY, Part, RunError, Timing, run},
+    fetch::cache_path,
+};
+
+/// File the known answers are kept in, relative to where the runner is started
+pub const ANSWERS_FILE: &str = "answers.toml";
+
+/// Failure reading or writing the known answers
+#[derive(Debug)]
+pub enum AnswersError {
+    Io(io::Error),
+    Malformed(toml::de::Error),
+}
+impl fmt::Display for AnswersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "cannot use {ANSWERS_FILE}: {e}"),
+            Self::Malformed(e) => write!(f, "{ANSWERS_FILE} is malformed: {e}"),
+        }
+    }
+}
+impl std::error::Error for AnswersError {}
+
+/// Hash identifying an input, so an answer is only checked against the input it was given for
+///
+/// FNV-1a, which stays the same across platforms and releases, over the input without its
+/// trailing whitespace.
+pub fn input_hash(input: &str) -> String {
+    let hash = input
+        .trim_end()
+        .bytes()
+        .fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+            (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+        });
+    format!("{hash:016x}")
+}
+
+/// The right answer to one part, for the input with the given hash
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KnownAnswer {
+    pub day: u8,
+    pub part: u8,
+    pub input: String,
+    pub answer: String,
+}
+
+/// Every known answer, as kept in the file
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq, Eq)]
+pub struct Answers {
+    #[serde(default, rename = "answer")]
+    pub answers: Vec<KnownAnswer>,
+}
+impl Answers {
+    /// Answers in the file, or none if there is no file yet
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        match fs::read_to_string(path) {
+            Ok(text) => toml::from_str(&text).map_err(AnswersError::Malformed),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(AnswersError::Io(e)),
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> Result<(), AnswersError> {
+        let text = toml::to_string(self).expect("Plain fields always serialize");
+        fs::write(path, text).map_err(AnswersError::Io)
+    }
+
+    /// Right answer to a part, for the input with hash `input`
+    pub fn get(&self, day: u8, part: Part, input: &str) -> Option<&str> {
+        self.answers
+            .iter()
+            .find(|known| known.day == day && known.part == part.number() && known.input == input)
+            .map(|known| known.answer.as_str())
+    }
+
+    /// Record the right answer to a part, replacing any other for the same input
+    ///
+    /// Answers are kept in order of day and part. Returns whether anything changed.
+    pub fn insert(&mut self, day: u8, part: Part, input: &str, answer: &str) -> bool {
+        if self.get(day, part, input) == Some(answer) {
+            return false;
+        }
+        self.answers.retain(|known| {
+            (known.day, known.part, known.input.as_str()) != (day, part.number(), input)
+        });
+        self.answers.push(KnownAnswer {
+            day,
+            part: part.number(),
+            input: input.to_owned(),
+            answer: answer.to_owned(),
+        });
+        self.answers.sort_by_key(|known| (known.day, known.part));
+        true
+    }
+
+    /// How an answer compares with the known one
+    pub fn check(&self, timing: &Timing, input: &str) -> Check {
+        match self.get(timing.day, timing.part, input) {
+            None => Check::Unknown,
+            Some(expected) if expected == timing.answer => Check::Pass,
+            Some(expected) => Check::Fail {
+                expected: expected.to_owned(),
+            },
+        }
+    }
+}
+
+/// How an answer compares with the known one
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Check {
+    Pass,
+    Fail {
+        expected: String,
+    },
+    /// There is no known answer for this input
+    Unknown,
+}
+
+/// One part answered again from its cached input, and checked
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verified {
+    pub timing: Timing,
+    /// Hash of the input it was answered for
+    pub input: String,
+    pub check: Check,
+}
+impl fmt::Display for Verified {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Timing {
+            day, part, answer, ..
+        } = &self.timing;
+        match &self.check {
+            Check::Pass => write!(f, "Day {day} part {part}: ok ({answer})"),
+            Check::Fail { expected } => write!(
+                f,
+                "Day {day} part {part}: FAILED, expected {expected} but got {answer}"
+            ),
+            Check::Unknown => write!(f, "Day {day} part {part}: no known answer ({answer})"),
+        }
+    }
+}
+
+/// Every part answered again, and the days that could not be
+#[derive(Debug, Clone, Default)]
+pub struct Verification {
+    pub verified: Vec<Verified>,
+    /// Day and the reason it was skipped
+    pub skipped: Vec<(u8, String)>,
+}
+impl Verification {
+    /// Answer both parts of `day`, or of every day, from the cached inputs and check each
+    pub fn run(answers: &Answers, day: Option<u8>) -> Self {
+        let mut verification = Self::default();
+        let days = match day {
+            Some(day) => day..=day,
+            None => 1..=LAST_DAY,
+        };
+        for day in days {
+            let path = cache_path(day);
+            let Ok(input) = fs::read_to_string(&path) else {
+                verification
+                    .skipped
+                    .push((day, format!("no input at {}", path.display())));
+                continue;
+            };
+            let hash = input_hash(&input);
+            for part in [Part::One, Part::Two] {
+                match run(day, part, &input) {
+                    Ok(timing) => verification.verified.push(Verified {
+                        check: answers.check(&timing, &hash),
+                        input: hash.clone(),
+                        timing,
+                    }),
+                    Err(RunError::NoPartTwo(_)) => {}
+                    Err(e) => {
+                        verification.skipped.push((day, e.to_string()));
+                        break;
+                    }
+                }
+            }
+        }
+        verification
+    }
+
+    /// Parts whose answer is not the known one
+    pub fn failures(&self) -> usize {
+        self.verified
+            .iter()
+            .filter(|verified| matches!(verified.check, Check::Fail { .. }))
+            .count()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+
+    #[test]
+    fn test_answers() {
+        assert_eq!(input_hash("abc"), "e71fa2190541574b");
+        assert_eq!(input_hash("abc\n"), input_hash("abc"));
+
+        let mut answers = Answers::default();
+        assert!(answers.insert(2, Part::One, "ff", "7"));
+        assert!(answers.insert(1, Part::Two, "ee", "5"));
+        assert!(!answers.insert(1, Part::Two, "ee", "5"));
+        assert!(answers.insert(1, Part::Two, "ee", "6"));
+        assert_eq!(answers.get(1, Part::Two, "ee"), Some("6"));
+        assert_eq!(answers.get(1, Part::Two, "ff"), None);
+
+        let text = toml::to_string(&answers).unwrap();
+        assert_eq!(
+            text,
+            "\
+[[answer]]
+day = 1
+part = 2
+input = \"ee\"
+answer = \"6\"
+
+[[answer]]
+day = 2
+part = 1
+input = \"ff\"
+answer = \"7\"
+"
+        );
+        assert_eq!(toml::from_str::<Answers>(&text).unwrap(), answers);
+
+        let timing = |answer: &str| Timing {
+            day: 2,
+            part: Part::One,
+            answer: answer.to_string(),
+            parse: Duration::ZERO,
+            solve: Duration::ZERO,
+        };
+        assert_eq!(answers.check(&timing("7"), "ff"), Check::Pass);
+        assert_eq!(
+            answers.check(&timing("8"), "ff"),
+            Check::Fail {
+                expected: "7".to_string()
+            }
+        );
+        assert_eq!(answers.check(&timing("7"), "00"), Check::Unknown);
+    }
+}
@@ -0,0 +1,993 @@
+//! Junction boxes joined into circuits by their shortest connections, a fixed number for part one
+//! and until a single circuit is left for part two
+use std::{
+    cmp::Ordering,
+    collections::{BTreeMap, BinaryHeap, HashMap, HashSet},
+    fmt,
+    io::{BufRead, Write},
+};
+
+use aoc_common::{
+    cancel,
+    solver::{SolveError, Solver},
+};
+use nalgebra::SVector;
+use rayon::prelude::*;
+use serde::Serialize;
+use tracing::warn;
+
+/// Number of connections the puzzle makes in part one
+pub const PART_ONE_ITERS: usize = 1000;
+
+/// Error from parsing the list of positions
+#[derive(Debug)]
+pub enum ParseError {
+    Io(std::io::Error),
+    Malformed {
+        line: usize,
+        column: usize,
+        reason: String,
+    },
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "failed to read input: {e}"),
+            Self::Malformed {
+                line,
+                column,
+                reason,
+            } => write!(f, "line {line}, column {column}: {reason}"),
+        }
+    }
+}
+impl std::error::Error for ParseError {}
+impl From<std::io::Error> for ParseError {
+    fn from(e: std::io::Error) -> Self {
+        Self::Io(e)
+    }
+}
+
+/// Creates UUIDs
+#[derive(Debug, Clone, Default)]
+struct UuidGenerator {
+    inner: usize,
+}
+impl UuidGenerator {
+    pub fn get_next(&mut self) -> usize {
+        let output = self.inner;
+        self.inner += 1;
+        output
+    }
+}
+
+/// Circuit manager
+///
+/// Needs to keep track of the various circuits, connect them together, and query if 2 things are
+/// connected
+/// We need something tha4t maps position to circuit and circuit to positions in the circuit
+#[derive(Debug, Clone)]
+struct CircuitManager {
+    uuid_gen: UuidGenerator,
+    /// Mapping from position (idx) to circuit
+    position_to_circuit: HashMap<usize, usize>,
+    /// Mapping from circuit to what positions it contains (idx)
+    circuit_to_position: HashMap<usize, HashSet<usize>>,
+    /// Mapping from circuit size to how many circuits have that size, kept up to date on every
+    /// combine so the largest circuits can be queried at any point
+    size_counts: BTreeMap<usize, usize>,
+}
+impl CircuitManager {
+    pub fn new<const D: usize>(poses: &[Position<D>]) -> Self {
+        let mut uuid_gen = UuidGenerator::default();
+
+        // Create position to circuit and circuit to position
+        let mut position_to_circuit = HashMap::new();
+        let mut circuit_to_position = HashMap::new();
+        for idx in 0..poses.len() {
+            let uuid = uuid_gen.get_next();
+            position_to_circuit.insert(idx, uuid);
+            let mut s = HashSet::new();
+            s.insert(idx);
+            circuit_to_position.insert(uuid, s);
+        }
+        let mut size_counts = BTreeMap::new();
+        if !poses.is_empty() {
+            size_counts.insert(1, poses.len());
+        }
+        Self {
+            uuid_gen,
+            position_to_circuit,
+            circuit_to_position,
+            size_counts,
+        }
+    }
+
+    pub fn try_combine(&mut self, idx0: usize, idx1: usize) -> bool {
+        // Make sure they do not belong to the same circuit
+        let cid0 = self.position_to_circuit.get(&idx0).unwrap();
+        let cid1 = self.position_to_circuit.get(&idx1).unwrap();
+        if cid0 == cid1 {
+            // They are already in the same circuit, return false
+            return false;
+        }
+        // Remove circuit 0 and circuit 1 from the circuit to position
+        let c0 = self.circuit_to_position.remove(cid0).unwrap();
+        let c1 = self.circuit_to_position.remove(cid1).unwrap();
+        self.remove_size(c0.len());
+        self.remove_size(c1.len());
+        *self.size_counts.entry(c0.len() + c1.len()).or_default() += 1;
+        // create a new circuit and mark that all of the positions in the c0 and c1 are now in that
+        // circuit
+        // modify position to circuit for each of the new positions to the new circuit
+        let mut new_c = HashSet::new();
+        let new_cid = self.uuid_gen.get_next();
+        for pid in c0 {
+            new_c.insert(pid);
+            *self.position_to_circuit.get_mut(&pid).unwrap() = new_cid;
+        }
+        for pid in c1 {
+            new_c.insert(pid);
+            *self.position_to_circuit.get_mut(&pid).unwrap() = new_cid;
+        }
+        self.circuit_to_position.insert(new_cid, new_c);
+        true
+    }
+
+    fn remove_size(&mut self, size: usize) {
+        let count = self.size_counts.get_mut(&size).unwrap();
+        *count -= 1;
+        if *count == 0 {
+            self.size_counts.remove(&size);
+        }
+    }
+
+    pub fn part_one(&self) -> usize {
+        // We need to determine the 3 largest circuits
+        self.largest_k_product(3)
+    }
+
+    /// Product of the sizes of the `k` largest circuits
+    ///
+    /// Walks the size counts from the largest size down, so this is O(k) rather than a sort
+    pub fn largest_k_product(&self, k: usize) -> usize {
+        self.size_counts
+            .iter()
+            .rev()
+            .flat_map(|(size, count)| std::iter::repeat_n(*size, *count))
+            .take(k)
+            .product()
+    }
+
+    pub fn is_one_large_circuit(&self) -> bool {
+        self.circuit_to_position.len() == 1
+    }
+
+    pub fn n_circuits(&self) -> usize {
+        self.circuit_to_position.len()
+    }
+
+    /// Position indices of every circuit, largest circuit first
+    pub fn circuits(&self) -> Vec<Vec<usize>> {
+        let mut circuits: Vec<Vec<usize>> = self
+            .circuit_to_position
+            .values()
+            .map(|pos_idxs| {
+                let mut pos_idxs: Vec<_> = pos_idxs.iter().copied().collect();
+                pos_idxs.sort();
+                pos_idxs
+            })
+            .collect();
+        // Largest first, then by lowest member so the output is stable
+        circuits.sort_by(|c0, c1| c1.len().cmp(&c0.len()).then_with(|| c0.cmp(c1)));
+        circuits
+    }
+}
+
+/// A candidate connection between two positions
+///
+/// Ordered so that the shortest edge is the greatest, which turns a `BinaryHeap` into a min-heap
+#[derive(Debug, Clone, Copy)]
+struct Edge {
+    distance: Distance,
+    idx0: usize,
+    idx1: usize,
+}
+impl PartialEq for Edge {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for Edge {}
+impl PartialOrd for Edge {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Edge {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .distance
+            .cmp(&self.distance)
+            .then_with(|| (other.idx0, other.idx1).cmp(&(self.idx0, self.idx1)))
+    }
+}
+
+/// Distance manager
+///
+/// Holds every pair in a min-heap so that edges are produced lazily, shortest first
+#[derive(Debug, Clone)]
+struct DistanceManager {
+    edges: BinaryHeap<Edge>,
+}
+impl DistanceManager {
+    pub fn new<const D: usize>(poses: &[Position<D>], mode: DistanceMode) -> Self {
+        // Measured in parallel, in no particular order as the heap sorts them
+        let edges: Vec<Edge> = poses
+            .par_iter()
+            .enumerate()
+            .flat_map_iter(|(idx0, pos0)| {
+                poses
+                    .iter()
+                    .enumerate()
+                    .skip(idx0 + 1)
+                    .map(move |(idx1, pos1)| Edge {
+                        distance: mode.measure(pos0, pos1),
+                        idx0,
+                        idx1,
+                    })
+            })
+            .collect();
+        // Heapify in O(n) rather than pushing one at a time
+        Self {
+            edges: BinaryHeap::from(edges),
+        }
+    }
+
+    pub fn next(&mut self) -> Option<(usize, usize)> {
+        self.edges.pop().map(|edge| (edge.idx0, edge.idx1))
+    }
+}
+
+/// Distinct color for a circuit id, spreading hues by the golden angle
+fn circuit_color(id: usize) -> [u8; 3] {
+    let hue = (id as f32 * 137.508) % 360.0;
+    let x = 1.0 - ((hue / 60.0) % 2.0 - 1.0).abs();
+    let (r, g, b) = match (hue / 60.0) as usize {
+        0 => (1.0, x, 0.0),
+        1 => (x, 1.0, 0.0),
+        2 => (0.0, 1.0, x),
+        3 => (0.0, x, 1.0),
+        4 => (x, 0.0, 1.0),
+        _ => (1.0, 0.0, x),
+    };
+    [(r * 255.0) as u8, (g * 255.0) as u8, (b * 255.0) as u8]
+}
+
+/// A position with `D` coordinates
+type Position<const D: usize> = SVector<usize, D>;
+
+/// Euclidean distance between two positions
+fn distance<const D: usize>(pos0: &Position<D>, pos1: &Position<D>) -> f32 {
+    (pos0.cast::<f32>() - pos1.cast::<f32>()).norm()
+}
+
+/// Squared Euclidean distance between two positions, computed exactly
+///
+/// Each squared difference fits in a `u128`, but their sum may not, so it is kept as how many
+/// times it wrapped past `u128::MAX` and the rest. Compared as a pair, it orders like the sum.
+fn squared_distance<const D: usize>(pos0: &Position<D>, pos1: &Position<D>) -> (usize, u128) {
+    pos0.iter()
+        .zip(pos1.iter())
+        .fold((0, 0), |(wraps, sum), (v0, v1)| {
+            let diff = v0.abs_diff(*v1) as u128;
+            let (sum, wrapped) = sum.overflowing_add(diff * diff);
+            (wraps + usize::from(wrapped), sum)
+        })
+}
+
+/// How pair distances are computed for ordering
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum DistanceMode {
+    /// Integer squared distances, which never misorder pairs
+    #[default]
+    Exact,
+    /// `f32` Euclidean distances, which can misorder pairs with large coordinates
+    Float,
+}
+impl DistanceMode {
+    fn measure<const D: usize>(&self, pos0: &Position<D>, pos1: &Position<D>) -> Distance {
+        match self {
+            Self::Exact => Distance::Squared(squared_distance(pos0, pos1)),
+            Self::Float => Distance::Float(distance(pos0, pos1)),
+        }
+    }
+}
+
+/// Distance between a pair, as used for ordering
+///
+/// The norm is only taken in float mode; exact mode keeps the squared distance, which orders
+/// pairs identically.
+#[derive(Debug, Clone, Copy)]
+enum Distance {
+    Squared((usize, u128)),
+    Float(f32),
+}
+impl Distance {
+    /// Squared distance as a float, for comparing distances of different modes
+    fn squared(&self) -> f64 {
+        match self {
+            Self::Squared((wraps, d)) => *wraps as f64 * 2f64.powi(128) + *d as f64,
+            Self::Float(d) => (*d as f64).powi(2),
+        }
+    }
+
+    /// Whether the pair is at most `radius` apart, compared exactly in exact mode
+    fn within(&self, radius: usize) -> bool {
+        match self {
+            Self::Squared(d) => *d <= (0, (radius as u128).pow(2)),
+            Self::Float(d) => *d as f64 <= radius as f64,
+        }
+    }
+}
+impl PartialEq for Distance {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+impl Eq for Distance {}
+impl PartialOrd for Distance {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+impl Ord for Distance {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (Self::Squared(d0), Self::Squared(d1)) => d0.cmp(d1),
+            (Self::Float(d0), Self::Float(d1)) => d0.total_cmp(d1),
+            _ => self.squared().total_cmp(&other.squared()),
+        }
+    }
+}
+
+/// Spatial grid that yields the shortest pairs incrementally
+///
+/// Rather than enumerating all O(n²) pairs up front, pairs are discovered in shells of increasing
+/// radius. For a radius `r`, points are bucketed into cubic cells of side `r`, so every pair within
+/// `r` of each other lives in the same or an adjacent cell. Each shell only holds the pairs whose
+/// distance falls in `(previous radius, r]`, and the radius doubles once a shell is drained.
+///
+/// Radii and cells are whole numbers, so in exact mode no pair lands in the wrong shell.
+#[derive(Debug, Clone)]
+struct SpatialGrid<const D: usize> {
+    poses: Vec<Position<D>>,
+    mode: DistanceMode,
+    /// Distance up to which every pair has already been produced, if any shell was searched
+    searched_radius: Option<usize>,
+    /// Radius of the next shell to search
+    radius: usize,
+    /// Bound on the distance between two points; the shell reaching it takes every pair left
+    max_radius: usize,
+    /// Pairs in the current shell, shortest on top
+    shell: BinaryHeap<Edge>,
+}
+impl<const D: usize> SpatialGrid<D> {
+    pub fn new(poses: &[Position<D>], mode: DistanceMode) -> Self {
+        let (min, max) = poses.iter().fold(
+            (Position::<D>::repeat(usize::MAX), Position::<D>::repeat(0)),
+            |(min, max), pos| (min.inf(pos), max.sup(pos)),
+        );
+        let extent = if poses.is_empty() {
+            SVector::zeros()
+        } else {
+            max - min
+        };
+        // The distance is never more than the sum of the extents
+        let max_radius = extent
+            .iter()
+            .fold(0, |sum: usize, e| sum.saturating_add(*e));
+        // Start with cells sized so that each holds roughly one point on average
+        let volume = extent.iter().map(|e| (*e as f32).max(1.0)).product::<f32>();
+        let radius = (volume / poses.len().max(1) as f32)
+            .powf(1.0 / D as f32)
+            .ceil()
+            .max(1.0) as usize;
+        Self {
+            poses: poses.to_vec(),
+            mode,
+            searched_radius: None,
+            radius,
+            max_radius,
+            shell: BinaryHeap::new(),
+        }
+    }
+
+    pub fn next(&mut self) -> Option<(usize, usize)> {
+        while self.shell.is_empty() {
+            if self
+                .searched_radius
+                .is_some_and(|searched| searched >= self.max_radius)
+            {
+                return None;
+            }
+            self.search_shell();
+        }
+        self.shell.pop().map(|edge| (edge.idx0, edge.idx1))
+    }
+
+    /// Collect every pair with a distance in `(searched_radius, radius]`, then grow the radius
+    fn search_shell(&mut self) {
+        let cell_size = self.radius;
+        let cell_of = |pos: &Position<D>| pos.map(|v| (v / cell_size) as i128);
+
+        // Bucket the points into cells
+        let mut cells: HashMap<SVector<i128, D>, Vec<usize>> = HashMap::new();
+        for (idx, pos) in self.poses.iter().enumerate() {
+            cells.entry(cell_of(pos)).or_default().push(idx);
+        }
+
+        // Past the bound, every remaining pair belongs to this shell
+        let is_last_shell = self.radius >= self.max_radius;
+
+        // Check each point against the points in its own and adjacent cells
+        let offsets = Self::neighbor_offsets();
+        for (idx0, pos0) in self.poses.iter().enumerate() {
+            let cell = cell_of(pos0);
+            for offset in offsets.iter() {
+                let Some(idxs) = cells.get(&(cell + offset)) else {
+                    continue;
+                };
+                for &idx1 in idxs.iter().filter(|idx1| **idx1 > idx0) {
+                    let d = self.mode.measure(pos0, &self.poses[idx1]);
+                    let beyond_searched = self.searched_radius.is_none_or(|r| !d.within(r));
+                    if beyond_searched && (is_last_shell || d.within(self.radius)) {
+                        self.shell.push(Edge {
+                            distance: d,
+                            idx0,
+                            idx1,
+                        });
+                    }
+                }
+            }
+        }
+        self.searched_radius = Some(self.radius);
+        self.radius = self.radius.saturating_mul(2);
+    }
+
+    /// Every offset in {-1, 0, 1}^D, i.e. a cell and all of its adjacent cells
+    fn neighbor_offsets() -> Vec<SVector<i128, D>> {
+        (0..3_usize.pow(D as u32))
+            .map(|mut code| {
+                SVector::from_fn(|_, _| {
+                    let offset = (code % 3) as i128 - 1;
+                    code /= 3;
+                    offset
+                })
+            })
+            .collect()
+    }
+}
+
+/// Where the manager gets its next-shortest pair from
+#[derive(Debug, Clone)]
+enum EdgeSource<const D: usize> {
+    /// Every pair enumerated up front and popped from a min-heap
+    Full(DistanceManager),
+    /// Pairs discovered incrementally through a spatial grid
+    Spatial(SpatialGrid<D>),
+}
+impl<const D: usize> EdgeSource<D> {
+    /// Number of positions above which the spatial grid is used
+    const SPATIAL_THRESHOLD: usize = 5000;
+
+    pub fn new(poses: &[Position<D>], mode: DistanceMode) -> Self {
+        if poses.len() > Self::SPATIAL_THRESHOLD {
+            Self::Spatial(SpatialGrid::new(poses, mode))
+        } else {
+            Self::Full(DistanceManager::new(poses, mode))
+        }
+    }
+}
+impl<const D: usize> Iterator for EdgeSource<D> {
+    type Item = (usize, usize);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Self::Full(distance_manager) => distance_manager.next(),
+            Self::Spatial(grid) => grid.next(),
+        }
+    }
+}
+
+/// Minimum spanning tree over the positions
+#[derive(Debug, Clone, Default)]
+pub struct MinimumSpanningTree {
+    /// Edges in the order they were added, as (idx0, idx1, length)
+    pub edges: Vec<(usize, usize, f32)>,
+    pub total_length: f32,
+}
+
+/// A position belonging to a circuit, as written by `--dump-circuits`
+#[derive(Debug, Clone, Serialize)]
+struct CircuitMember {
+    index: usize,
+    position: Vec<usize>,
+}
+
+/// A circuit and its members, as written by `--dump-circuits`
+#[derive(Debug, Clone, Serialize)]
+pub struct CircuitDump {
+    id: usize,
+    members: Vec<CircuitMember>,
+}
+
+/// Manager
+#[derive(Debug, Clone)]
+pub struct Manager<const D: usize> {
+    poses: Vec<Position<D>>,
+    edge_source: EdgeSource<D>,
+    circuit_manager: CircuitManager,
+}
+impl<const D: usize> Manager<D> {
+    pub fn new<R: BufRead>(reader: R, mode: DistanceMode) -> Result<Self, ParseError> {
+        let poses = Self::parse(reader)?;
+        let edge_source = EdgeSource::new(&poses, mode);
+        Ok(Self::with_edge_source(poses, edge_source))
+    }
+
+    /// Create a manager that always uses the spatial grid, regardless of input size
+    pub fn new_spatial<R: BufRead>(reader: R, mode: DistanceMode) -> Result<Self, ParseError> {
+        let poses = Self::parse(reader)?;
+        let edge_source = EdgeSource::Spatial(SpatialGrid::new(&poses, mode));
+        Ok(Self::with_edge_source(poses, edge_source))
+    }
+
+    fn with_edge_source(poses: Vec<Position<D>>, edge_source: EdgeSource<D>) -> Self {
+        let circuit_manager = CircuitManager::new(&poses);
+        Self {
+            poses,
+            edge_source,
+            circuit_manager,
+        }
+    }
+
+    /// Parse one position per line, skipping blank lines
+    fn parse<R: BufRead>(reader: R) -> Result<Vec<Position<D>>, ParseError> {
+        let mut poses = Vec::new();
+        for (idx, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            poses.push(Self::parse_line(&line, idx + 1)?);
+        }
+        Ok(poses)
+    }
+
+    /// Parse a single `x,y,z` line, reporting the 1-indexed column of anything malformed
+    fn parse_line(line: &str, line_num: usize) -> Result<Position<D>, ParseError> {
+        let malformed = |column: usize, reason: String| ParseError::Malformed {
+            line: line_num,
+            column,
+            reason,
+        };
+        let mut pos = Position::zeros();
+        let mut n_fields = 0;
+        let mut column = 1;
+        for field in line.split(",") {
+            if n_fields == D {
+                return Err(malformed(
+                    column - 1,
+                    format!("expected {D} coordinates, found more"),
+                ));
+            }
+            // Point the column at the number itself rather than any padding before it
+            let padding = field.len() - field.trim_start().len();
+            pos[n_fields] = field.trim().parse().map_err(|e| {
+                malformed(
+                    column + padding,
+                    format!("invalid coordinate {field:?}: {e}"),
+                )
+            })?;
+            n_fields += 1;
+            column += field.len() + 1;
+        }
+        if n_fields < D {
+            return Err(malformed(
+                line.len() + 1,
+                format!("expected {D} coordinates, found {n_fields}"),
+            ));
+        }
+        Ok(pos)
+    }
+
+    pub fn part_one(&mut self, n_iters: usize) -> usize {
+        self.part_one_watched(n_iters, |_, _| {})
+    }
+
+    /// Same as `part_one`, but calls `watch` with the iteration and the current product of the
+    /// three largest circuits after every connection
+    ///
+    /// Stops early once every pair has been tried, which leaves a single circuit
+    pub fn part_one_watched(
+        &mut self,
+        n_iters: usize,
+        mut watch: impl FnMut(usize, usize),
+    ) -> usize {
+        for iter in 0..n_iters {
+            // Find the shortest
+            // let (idx0, idx1, _) = self.distance_manager.argmin();
+            let Some((idx0, idx1)) = self.edge_source.next() else {
+                break;
+            };
+            let _ = self.circuit_manager.try_combine(idx0, idx1);
+            watch(iter, self.largest_k_product(3));
+        }
+
+        self.circuit_manager.part_one()
+    }
+
+    /// Product of the sizes of the `k` largest circuits so far
+    pub fn largest_k_product(&self, k: usize) -> usize {
+        self.circuit_manager.largest_k_product(k)
+    }
+
+    /// Product of the xs of the pair whose connection leaves a single circuit
+    ///
+    /// `None` when stopped early, or when there are fewer than two junction boxes to connect
+    pub fn part_two(&mut self) -> Option<usize> {
+        loop {
+            if cancel::is_cancelled() {
+                warn!(
+                    circuits = self.n_circuits(),
+                    "Stopped early, with circuits left to join"
+                );
+                return None;
+            }
+            // Find the shortest
+            // let (idx0, idx1, _) = self.distance_manager.argmin();
+            let (idx0, idx1) = self.edge_source.next()?;
+            let _ = self.circuit_manager.try_combine(idx0, idx1);
+
+            if self.circuit_manager.is_one_large_circuit() {
+                // Multiple the xs of idx0 and idx1
+                let p0 = self.poses[idx0];
+                let p1 = self.poses[idx1];
+                return Some(p0[0] * p1[0]);
+            }
+        }
+    }
+
+    /// Number of separate circuits
+    pub fn n_circuits(&self) -> usize {
+        self.circuit_manager.n_circuits()
+    }
+
+    /// Keep connecting pairs until only `n_circuits` circuits remain, or every pair has been tried
+    pub fn connect_until_n_circuits(&mut self, n_circuits: usize) {
+        while self.circuit_manager.n_circuits() > n_circuits {
+            let Some((idx0, idx1)) = self.edge_source.next() else {
+                break;
+            };
+            let _ = self.circuit_manager.try_combine(idx0, idx1);
+        }
+    }
+
+    /// Write the positions as an ASCII PLY point cloud, colored by their current circuit
+    ///
+    /// Circuits with a single member are grey so the connected circuits stand out
+    pub fn write_ply<W: Write>(&self, mut writer: W) -> std::io::Result<()> {
+        let mut colors = vec![[128, 128, 128]; self.poses.len()];
+        for (id, pos_idxs) in self.circuit_manager.circuits().into_iter().enumerate() {
+            if pos_idxs.len() > 1 {
+                for idx in pos_idxs {
+                    colors[idx] = circuit_color(id);
+                }
+            }
+        }
+
+        writeln!(writer, "ply")?;
+        writeln!(writer, "format ascii 1.0")?;
+        writeln!(writer, "element vertex {}", self.poses.len())?;
+        for axis in ["x", "y", "z", "w"].iter().take(D) {
+            writeln!(writer, "property float {axis}")?;
+        }
+        for channel in ["red", "green", "blue"] {
+            writeln!(writer, "property uchar {channel}")?;
+        }
+        writeln!(writer, "end_header")?;
+        for (pos, [r, g, b]) in self.poses.iter().zip(colors) {
+            for v in pos.iter() {
+                write!(writer, "{v} ")?;
+            }
+            writeln!(writer, "{r} {g} {b}")?;
+        }
+        Ok(())
+    }
+
+    /// Snapshot of the current circuits, largest first
+    pub fn circuit_dump(&self) -> Vec<CircuitDump> {
+        self.circuit_manager
+            .circuits()
+            .into_iter()
+            .enumerate()
+            .map(|(id, pos_idxs)| CircuitDump {
+                id,
+                members: pos_idxs
+                    .into_iter()
+                    .map(|index| CircuitMember {
+                        index,
+                        position: self.poses[index].iter().copied().collect(),
+                    })
+                    .collect(),
+            })
+            .collect()
+    }
+
+    /// Run Kruskal's algorithm to completion
+    ///
+    /// This is the same process as part two, but keeps every edge that joined two circuits
+    pub fn mst(&mut self) -> MinimumSpanningTree {
+        let mut mst = MinimumSpanningTree::default();
+        while !self.circuit_manager.is_one_large_circuit() {
+            let Some((idx0, idx1)) = self.edge_source.next() else {
+                break;
+            };
+            if self.circuit_manager.try_combine(idx0, idx1) {
+                let length = distance(&self.poses[idx0], &self.poses[idx1]);
+                mst.edges.push((idx0, idx1, length));
+                mst.total_length += length;
+            }
+        }
+        mst
+    }
+}
+
+/// Three dimensional junction boxes, with the connections made lazily on each part
+#[derive(Debug, Clone)]
+pub struct Playground {
+    manager: Manager<3>,
+}
+
+/// The example from the puzzle text, run with `--example`
+pub const EXAMPLE: &str = include_str!("../examples/example.txt");
+
+impl Solver for Playground {
+    type Output = usize;
+    type Error = ParseError;
+    const EXAMPLE: &'static str = EXAMPLE;
+
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let manager = Manager::new(input.as_bytes(), DistanceMode::default())?;
+        Ok(Self { manager })
+    }
+
+    fn part_one(&self) -> Result<usize, SolveError> {
+        Ok(self.manager.clone().part_one(PART_ONE_ITERS))
+    }
+
+    fn part_two(&self) -> Result<usize, SolveError> {
+        part_two(&mut self.manager.clone())
+    }
+}
+
+pub fn part_one<const D: usize>(manager: &mut Manager<D>, n_iters: usize) -> usize {
+    manager.part_one(n_iters)
+}
+
+pub fn part_two<const D: usize>(manager: &mut Manager<D>) -> Result<usize, SolveError> {
+    manager
+        .part_two()
+        .ok_or_else(|| SolveError::new("part two needs at least two junction boxes"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one() {
+        let mut manager = Manager::<3>::new(EXAMPLE.as_bytes(), DistanceMode::Exact).unwrap();
+        let output = manager.part_one(10);
+
+        // TODO fill this out
+        assert_eq!(output, 40);
+    }
+
+    #[test]
+    fn test_solver() {
+        // The example runs out of pairs before part one's 1000 connections, joining all 20 boxes
+        let playground = Playground::parse(EXAMPLE).unwrap();
+        assert_eq!(playground.part_one(), Ok(20));
+        assert_eq!(playground.part_two(), Ok(25272));
+    }
+
+    #[test]
+    fn test_too_few_boxes() {
+        for input in ["", "1,2,3"] {
+            let playground = Playground::parse(input).unwrap();
+            assert_eq!(playground.part_one(), Ok(1));
+            assert_eq!(
+                playground.part_two(),
+                Err(SolveError::new(
+                    "part two needs at least two junction boxes"
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn test_two() {
+        let mut manager = Manager::<3>::new(EXAMPLE.as_bytes(), DistanceMode::Exact).unwrap();
+        let output = manager.part_two();
+
+        // TODO fill this out
+        assert_eq!(output, Some(25272));
+    }
+
+    #[test]
+    fn test_largest_k_product() {
+        let mut manager = Manager::<3>::new(EXAMPLE.as_bytes(), DistanceMode::Exact).unwrap();
+        assert_eq!(manager.largest_k_product(3), 1);
+        let mut products = Vec::new();
+        manager.part_one_watched(10, |_, product| products.push(product));
+        assert_eq!(products.first(), Some(&2));
+        assert_eq!(products.last(), Some(&40));
+        assert_eq!(manager.largest_k_product(1), 5);
+        assert_eq!(manager.largest_k_product(20), 5 * 4 * 2 * 2);
+    }
+
+    #[test]
+    fn test_write_ply() {
+        let mut manager = Manager::<3>::new(EXAMPLE.as_bytes(), DistanceMode::Exact).unwrap();
+        manager.connect_until_n_circuits(2);
+        assert_eq!(manager.circuit_manager.n_circuits(), 2);
+
+        let mut ply = Vec::new();
+        manager.write_ply(&mut ply).unwrap();
+        let ply = String::from_utf8(ply).unwrap();
+        let (header, body) = ply.split_once("end_header\n").unwrap();
+        assert!(header.contains("element vertex 20"));
+        assert_eq!(body.lines().count(), 20);
+        assert!(body.starts_with("162 817 812 "));
+
+        // One more connection finishes part two
+        assert_eq!(manager.part_two(), Some(25272));
+    }
+
+    #[test]
+    fn test_circuit_dump() {
+        let mut manager = Manager::<3>::new(EXAMPLE.as_bytes(), DistanceMode::Exact).unwrap();
+        manager.part_one(10);
+        let dump = manager.circuit_dump();
+        let sizes: Vec<_> = dump.iter().map(|c| c.members.len()).take(3).collect();
+        assert_eq!(sizes, vec![5, 4, 2]);
+        assert_eq!(dump.iter().map(|c| c.members.len()).sum::<usize>(), 20);
+        let member = &dump[0].members[0];
+        assert_eq!(
+            member.position,
+            manager.poses[member.index]
+                .iter()
+                .copied()
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let input = "1,2,3\n\n4,5,6\n";
+        let manager = Manager::<3>::new(input.as_bytes(), DistanceMode::Exact).unwrap();
+        assert_eq!(manager.poses.len(), 2);
+
+        let err = Manager::<3>::new("1,2,3\n4, x,6".as_bytes(), DistanceMode::Exact).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::Malformed {
+                line: 2,
+                column: 4,
+                ..
+            }
+        ));
+
+        let err = Manager::<3>::new("1,2".as_bytes(), DistanceMode::Exact).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::Malformed {
+                line: 1,
+                column: 4,
+                ..
+            }
+        ));
+
+        let err = Manager::<3>::new("1,2,3,4".as_bytes(), DistanceMode::Exact).unwrap_err();
+        assert!(matches!(
+            err,
+            ParseError::Malformed {
+                line: 1,
+                column: 6,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_mst() {
+        let mut manager = Manager::<3>::new(EXAMPLE.as_bytes(), DistanceMode::Exact).unwrap();
+        let mst = manager.mst();
+        assert_eq!(mst.edges.len(), 19);
+
+        // The final edge of the tree is the one that completes part two
+        let (idx0, idx1, _) = *mst.edges.last().unwrap();
+        assert_eq!(manager.poses[idx0][0] * manager.poses[idx1][0], 25272);
+    }
+
+    #[test]
+    fn test_two_dimensions() {
+        let input = "0,0
+1,0
+10,10
+10,12
+3,0";
+        let mut manager = Manager::<2>::new(input.as_bytes(), DistanceMode::Exact).unwrap();
+        assert_eq!(manager.part_one(2), 3);
+        let mut manager = Manager::<2>::new_spatial(input.as_bytes(), DistanceMode::Exact).unwrap();
+        // The last connection bridges (3,0) and (10,10)
+        assert_eq!(manager.part_two(), Some(30));
+    }
+
+    #[test]
+    fn test_distance_modes() {
+        for mode in [DistanceMode::Exact, DistanceMode::Float] {
+            let mut manager = Manager::<3>::new(EXAMPLE.as_bytes(), mode).unwrap();
+            assert_eq!(manager.part_one(10), 40);
+            let mut manager = Manager::<3>::new_spatial(EXAMPLE.as_bytes(), mode).unwrap();
+            assert_eq!(manager.part_two(), Some(25272));
+        }
+
+        // Adjacent large coordinates that f32 cannot tell apart
+        let pos0 = Position::<3>::new(100_000_000, 0, 0);
+        let pos1 = Position::<3>::new(100_000_001, 0, 0);
+        let pos2 = Position::<3>::new(100_000_003, 0, 0);
+        assert!(
+            DistanceMode::Exact.measure(&pos0, &pos1) < DistanceMode::Exact.measure(&pos0, &pos2)
+        );
+        assert_eq!(
+            DistanceMode::Float.measure(&pos0, &pos1),
+            DistanceMode::Float.measure(&pos0, &pos2)
+        );
+
+        // Squared distances past 64 bits, and sums past 128 bits
+        let origin = Position::<3>::zeros();
+        let far = Position::<3>::new(1 << 33, 0, 0);
+        let farther = Position::<3>::new(usize::MAX, usize::MAX, 0);
+        let farthest = Position::<3>::new(usize::MAX, usize::MAX, 1);
+        let measure = |pos| DistanceMode::Exact.measure(&origin, pos);
+        assert!(measure(&pos1) < measure(&far));
+        assert!(measure(&far) < measure(&farther));
+        assert!(measure(&farther) < measure(&farthest));
+    }
+
+    #[test]
+    fn test_spatial_large() {
+        // Distances that no float tells apart still come out in order, one pair per shell
+        let base = 1_usize << 60;
+        let input = format!("{base},0\n{},0\n{},0\n{},0\n", base + 1, base + 3, base + 7);
+        let mut full = Manager::<2>::new(input.as_bytes(), DistanceMode::Exact).unwrap();
+        let mut spatial = Manager::<2>::new_spatial(input.as_bytes(), DistanceMode::Exact).unwrap();
+        let full: Vec<_> = std::iter::from_fn(|| full.edge_source.next()).collect();
+        let spatial: Vec<_> = std::iter::from_fn(|| spatial.edge_source.next()).collect();
+        assert_eq!(full, [(0, 1), (1, 2), (0, 2), (2, 3), (1, 3), (0, 3)]);
+        assert_eq!(spatial, full);
+    }
+
+    #[test]
+    fn test_spatial() {
+        let mut manager =
+            Manager::<3>::new_spatial(EXAMPLE.as_bytes(), DistanceMode::Exact).unwrap();
+        assert_eq!(manager.part_one(10), 40);
+        let mut manager =
+            Manager::<3>::new_spatial(EXAMPLE.as_bytes(), DistanceMode::Exact).unwrap();
+        assert_eq!(manager.part_two(), Some(25272));
+    }
+}
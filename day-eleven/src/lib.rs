@@ -0,0 +1,195 @@
+//! Counting the paths through the device graph, from you to out for part one and through both
+//! fft and dac for part two
+use std::collections::HashMap;
+use std::hash::RandomState;
+
+use aoc_common::{
+    cancel::{self, CancelIterator},
+    input::LineError,
+    progress::{self, ProgressIterator},
+    solver::{SolveError, Solver},
+};
+use petgraph::algo::all_simple_paths;
+use petgraph::prelude::*;
+use rayon::prelude::*;
+use tracing::{info, info_span, warn};
+
+/// Graph Manager
+#[derive(Debug, Clone)]
+pub struct GraphManager {
+    graph: Graph<String, i32>,
+    nodes: HashMap<String, NodeIndex>,
+}
+
+/// The example from the puzzle text, run with `--example`
+pub const EXAMPLE: &str = include_str!("../examples/example.txt");
+
+/// The example from part two's text, which has one of its own
+pub const EXAMPLE_TWO: &str = include_str!("../examples/example-two.txt");
+
+impl Solver for GraphManager {
+    type Output = usize;
+    type Error = LineError;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const EXAMPLE_TWO: &'static str = EXAMPLE_TWO;
+
+    fn parse(input: &str) -> Result<Self, LineError> {
+        let mut graph = Graph::new();
+        let mut nodes = HashMap::new();
+        for (idx, line) in input.lines().enumerate() {
+            let (node, connected_to) = line.split_once(":").ok_or_else(|| {
+                LineError::new(
+                    idx + 1,
+                    format!("expected `device: outputs`, found {line:?}"),
+                )
+            })?;
+            let node = node.to_string();
+            let connected_to: Vec<_> = connected_to
+                .split_whitespace()
+                .map(|x| x.to_string())
+                .collect();
+            if !nodes.contains_key(&node) {
+                let idx = graph.add_node(node.clone());
+                nodes.insert(node.clone(), idx);
+            }
+            let source_idx = *nodes.get(&node).unwrap();
+            for node in &connected_to {
+                if !nodes.contains_key(node) {
+                    let idx = graph.add_node(node.clone());
+                    nodes.insert(node.clone(), idx);
+                }
+                // Create edges
+                let dep_idx = *nodes.get(node).unwrap();
+                graph.add_edge(source_idx, dep_idx, 1);
+            }
+        }
+        Ok(Self { graph, nodes })
+    }
+
+    fn part_one(&self) -> Result<usize, SolveError> {
+        let you_idx = self.device("you")?;
+        let out_idx = self.device("out")?;
+        let all_paths =
+            all_simple_paths::<Vec<_>, _, RandomState>(&self.graph, you_idx, out_idx, 0, None);
+        let count = all_paths.until_cancelled().count();
+        if cancel::is_cancelled() {
+            warn!(count, "Stopped early, with the paths counted so far");
+        }
+        Ok(count)
+    }
+
+    fn part_two(&self) -> Result<usize, SolveError> {
+        let [svr, dac, fft, out] = [
+            self.device("svr")?,
+            self.device("dac")?,
+            self.device("fft")?,
+            self.device("out")?,
+        ];
+        // Each leg between the devices to pass through, going by neither of the other two
+        let legs = [
+            ("svr2dac", svr, dac, [fft, out]),
+            ("dac2fft", dac, fft, [svr, out]),
+            ("fft2out", fft, out, [svr, dac]),
+            ("svr2fft", svr, fft, [dac, out]),
+            ("fft2dac", fft, dac, [svr, out]),
+            ("dac2out", dac, out, [svr, fft]),
+        ];
+        // Counted on the rayon pool, which falls back to this thread where threads cannot be
+        // spawned, as in the browser
+        let counts: Vec<usize> = legs
+            .par_iter()
+            .map(|&(name, from, to, avoid)| self.count_leg(name, from, to, avoid))
+            .collect();
+
+        // Path from svr -> dac -> fft -> out, and from svr -> fft -> dac -> out
+        Ok(counts[0] * counts[1] * counts[2] + counts[3] * counts[4] * counts[5])
+    }
+}
+
+impl GraphManager {
+    /// Node of the device called `name`, which the paths must start, end or pass at
+    fn device(&self, name: &str) -> Result<NodeIndex, SolveError> {
+        self.nodes
+            .get(name)
+            .copied()
+            .ok_or_else(|| SolveError::new(format!("no device named {name:?}")))
+    }
+
+    /// Simple paths from `from` to `to`, through at most 17 other devices and neither of `avoid`
+    fn count_leg(
+        &self,
+        name: &'static str,
+        from: NodeIndex,
+        to: NodeIndex,
+        avoid: [NodeIndex; 2],
+    ) -> usize {
+        let _span = info_span!("leg", name).entered();
+        info!("Starting");
+        let count = all_simple_paths::<Vec<_>, _, RandomState>(&self.graph, from, to, 0, Some(17))
+            .until_cancelled()
+            .progress_with(progress::spinner(name))
+            .filter(|path| !avoid.iter().any(|node| path.contains(node)))
+            .count();
+        finished(count);
+        count
+    }
+}
+
+/// Log the paths a search counted, which are only some of them if it was stopped early
+fn finished(count: usize) {
+    if cancel::is_cancelled() {
+        warn!(count, "Stopped early, with the paths counted so far");
+    } else {
+        info!(count, "Completed");
+    }
+}
+
+pub fn part_one(s: &str) -> anyhow::Result<usize> {
+    Ok(GraphManager::parse(s)?.part_one()?)
+}
+
+pub fn part_two(s: &str) -> anyhow::Result<usize> {
+    Ok(GraphManager::parse(s)?.part_two()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one() {
+        let output = part_one(EXAMPLE).unwrap();
+
+        // TODO fill this out
+        assert_eq!(output, 5);
+    }
+
+    #[test]
+    fn test_two() {
+        let output = part_two(EXAMPLE_TWO).unwrap();
+
+        // TODO fill this out
+        assert_eq!(output, 2);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            GraphManager::parse("you: bbb\nbbb out").unwrap_err(),
+            LineError::new(2, "expected `device: outputs`, found \"bbb out\"")
+        );
+    }
+
+    #[test]
+    fn test_missing_device() {
+        let manager = GraphManager::parse("you: bbb\nbbb: ccc").unwrap();
+        assert_eq!(
+            manager.part_one(),
+            Err(SolveError::new("no device named \"out\""))
+        );
+        assert_eq!(
+            manager.part_two(),
+            Err(SolveError::new("no device named \"svr\""))
+        );
+    }
+}
@@ -0,0 +1,545 @@
+//! Fresh ingredient ranges, checked against the ingredients for part one and measured for part two
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+    io::BufRead,
+};
+
+use aoc_common::{
+    input::blank_line_groups,
+    interval::{Interval, IntervalSet},
+    solver::{SolveError, Solver},
+};
+use rayon::prelude::*;
+
+/// Number of IDs fresh in each part of the overlap of two sets of ranges
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Comparison {
+    pub either: ReturnType,
+    pub both: ReturnType,
+    pub only_first: ReturnType,
+    pub only_second: ReturnType,
+}
+
+/// How the two sets of ranges overlap
+pub fn compare(ranges: &Ranges, other: &Ranges) -> Result<Comparison, SolveError> {
+    Ok(Comparison {
+        either: count(&ranges.union(other))?,
+        both: count(&ranges.intersect(other))?,
+        only_first: count(&ranges.intersect(&other.complement()))?,
+        only_second: count(&other.intersect(&ranges.complement()))?,
+    })
+}
+
+/// Number of IDs in the ranges, failing if they hold every ID
+pub fn count(ranges: &Ranges) -> Result<ReturnType, SolveError> {
+    ranges.get_n().ok_or_else(|| {
+        SolveError::new("every ID is in the ranges, one more than 128 bits can count")
+    })
+}
+
+/// Number of ingredient lines read before classifying them in parallel
+const CHUNK_LINES: usize = 1 << 16;
+
+/// Part one, counting fresh ingredients as they are read
+///
+/// The ingredient list is never held whole, but an ingredient listed twice must be counted once,
+/// so the distinct fresh IDs seen so far are kept. Memory grows with those rather than with the
+/// length of the list, which for mostly fresh input saves little.
+pub fn part_one(file: impl BufRead) -> Result<ReturnType, ParseError> {
+    part_one_chunked(file, CHUNK_LINES)
+}
+
+/// Same as `part_one`, classifying `chunk_lines` ingredients at a time in parallel
+pub fn part_one_chunked(file: impl BufRead, chunk_lines: usize) -> Result<ReturnType, ParseError> {
+    let mut lines = file.lines().enumerate().map(|(idx, line)| {
+        line.map(|text| (idx + 1, text))
+            .map_err(|e| ParseError::Read(e.to_string()))
+    });
+
+    // Everything up to the first blank line after some ranges is the range section
+    let mut fresh_ingredients = Ranges::default();
+    let mut sections = 0;
+    for line in lines.by_ref() {
+        let (line, text) = line?;
+        let text = text.trim();
+        if text.is_empty() {
+            if sections > 0 {
+                break;
+            }
+            continue;
+        }
+        sections = 1;
+        let range = parse_range(text).ok_or_else(|| invalid(Section::Ranges, line, text))?;
+        fresh_ingredients.add_range(range);
+    }
+
+    let mut fresh = HashSet::new();
+    let mut chunk = Vec::with_capacity(chunk_lines);
+    let mut in_section = false;
+    for line in lines {
+        let (line, text) = line?;
+        if text.trim().is_empty() {
+            in_section = false;
+            continue;
+        }
+        if !in_section {
+            in_section = true;
+            sections += 1;
+        }
+        // Past the ingredients there is nothing to parse, only sections to count
+        if sections == 2 {
+            chunk.push((line, text));
+            if chunk.len() == chunk_lines {
+                fresh.extend(find_fresh(&fresh_ingredients, &chunk)?);
+                chunk.clear();
+            }
+        }
+    }
+    fresh.extend(find_fresh(&fresh_ingredients, &chunk)?);
+    if sections != 2 {
+        return Err(ParseError::SectionCount(sections));
+    }
+    Ok(fresh.len() as ReturnType)
+}
+
+/// The fresh ingredients among numbered ingredient lines, classified in parallel
+///
+/// On bad lines, the error is for the first one
+fn find_fresh(
+    fresh_ingredients: &Ranges,
+    chunk: &[(usize, String)],
+) -> Result<Vec<Id>, ParseError> {
+    chunk
+        .par_iter()
+        .map(|(line, text)| {
+            let ingredient: Id = text
+                .trim()
+                .parse()
+                .map_err(|_| invalid(Section::Ingredients, *line, text.trim()))?;
+            Ok(fresh_ingredients.contains(ingredient).then_some(ingredient))
+        })
+        // Collecting keeps the order of the lines, so the earlier error wins
+        .collect::<Result<Vec<_>, _>>()
+        .map(|fresh| fresh.into_iter().flatten().collect())
+}
+
+pub fn parse_input(mut file: impl BufRead) -> Result<InputType, ParseError> {
+    let mut s = String::new();
+    file.read_to_string(&mut s)
+        .map_err(|e| ParseError::Read(e.to_string()))?;
+    parse_str(&s)
+}
+
+/// Section of the input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Section {
+    Ranges,
+    Ingredients,
+}
+impl fmt::Display for Section {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ranges => write!(f, "fresh ranges"),
+            Self::Ingredients => write!(f, "ingredients"),
+        }
+    }
+}
+
+/// Error from parsing the input, with 1-indexed lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// The input could not be read, for the reason given
+    Read(String),
+    SectionCount(usize),
+    InvalidLine {
+        section: Section,
+        line: usize,
+        text: String,
+    },
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Read(reason) => write!(f, "failed to read input: {reason}"),
+            Self::SectionCount(found) => write!(
+                f,
+                "expected 2 sections separated by a blank line, found {found}"
+            ),
+            Self::InvalidLine {
+                section,
+                line,
+                text,
+            } => write!(f, "{section} section, line {line}: cannot parse {text:?}"),
+        }
+    }
+}
+impl std::error::Error for ParseError {}
+
+/// Parse the fresh ranges and the ingredients, in blank-line separated sections
+pub fn parse_str(input: &str) -> Result<InputType, ParseError> {
+    let groups = blank_line_groups(input);
+    let [range_lines, ingredient_lines] = groups.as_slice() else {
+        return Err(ParseError::SectionCount(groups.len()));
+    };
+    let mut fresh_ingredients = Ranges::default();
+    for (line, text) in range_lines {
+        let range = parse_range(text).ok_or_else(|| invalid(Section::Ranges, *line, text))?;
+        fresh_ingredients.add_range(range);
+    }
+    let mut ingredients = IngredientsList::default();
+    for (line, text) in ingredient_lines {
+        let ingredient = text
+            .parse()
+            .map_err(|_| invalid(Section::Ingredients, *line, text))?;
+        ingredients.add_ingredient(ingredient);
+    }
+    Ok((fresh_ingredients, ingredients))
+}
+
+fn invalid(section: Section, line: usize, text: &str) -> ParseError {
+    ParseError::InvalidLine {
+        section,
+        line,
+        text: text.to_string(),
+    }
+}
+
+/// One line per checked ingredient, in increasing order, with the range it falls in
+pub fn explain(input: &InputType) -> String {
+    let (fresh_ingredients, ingredients_to_check) = input;
+    let mut ingredients: Vec<Id> = ingredients_to_check.0.keys().copied().collect();
+    ingredients.sort_unstable();
+    ingredients
+        .into_iter()
+        .map(|ingredient| match fresh_ingredients.find(ingredient) {
+            Some(range) => format!("{ingredient}: fresh ({}-{})\n", range.start, range.end),
+            None => format!("{ingredient}: spoiled\n"),
+        })
+        .collect()
+}
+
+/// Every fresh range, in increasing order, with the number of checked ingredients inside it,
+/// counting an ingredient every time it is listed
+pub fn histogram(input: &InputType) -> Vec<(Range, ReturnType)> {
+    let (fresh_ingredients, ingredients_to_check) = input;
+    // Ranges are disjoint, so their starts identify them
+    let mut counts: HashMap<Id, ReturnType> = HashMap::new();
+    for (ingredient, count) in &ingredients_to_check.0 {
+        if let Some(range) = fresh_ingredients.find(*ingredient) {
+            *counts.entry(range.start).or_default() += count;
+        }
+    }
+    fresh_ingredients
+        .0
+        .iter()
+        .map(|range| (*range, counts.get(&range.start).copied().unwrap_or(0)))
+        .collect()
+}
+
+// TODO -- Update this with the return type
+pub type ReturnType = u128;
+/// Ingredient ID -- wide enough for IDs of any puzzle input, on any target
+pub type Id = u128;
+pub type InputType = (Ranges, IngredientsList);
+
+/// Ingredients List -- a HashMap with the number of times each ingredient is listed
+///
+/// Part one checks each ingredient once, while the histogram counts every listing.
+#[derive(Debug, Clone, Default)]
+pub struct IngredientsList(HashMap<Id, ReturnType>);
+impl IngredientsList {
+    pub fn add_ingredient(&mut self, ingredient: Id) {
+        *self.0.entry(ingredient).or_default() += 1;
+    }
+}
+
+/// Inclusive range of fresh ingredient IDs
+pub type Range = Interval<Id>;
+
+/// Parse a `start-end` line, which must not run backwards
+fn parse_range(input: &str) -> Option<Range> {
+    // Convert the range to numbers
+    let (start, end) = input.trim().split_once('-')?;
+    let start: Id = start.trim().parse().ok()?;
+    let end: Id = end.trim().parse().ok()?;
+    (start <= end).then(|| Range::new(start, end))
+}
+
+/// Ranges -- merged as they are added
+#[derive(Debug, Clone, Default)]
+pub struct Ranges(IntervalSet<Id>);
+impl Ranges {
+    pub fn add_range(&mut self, range: Range) {
+        self.0.insert(range);
+    }
+
+    pub fn contains(&self, value: Id) -> bool {
+        self.0.contains(value)
+    }
+
+    /// The range holding `value`, if any
+    pub fn find(&self, value: Id) -> Option<&Range> {
+        self.0.find(value)
+    }
+
+    /// Number of IDs in the ranges, or `None` if they hold every ID, one more than `Id` can count
+    pub fn get_n(&self) -> Option<Id> {
+        self.0.total_len()
+    }
+
+    /// IDs fresh in either
+    pub fn union(&self, other: &Self) -> Self {
+        Self(self.0.union(&other.0))
+    }
+
+    /// IDs fresh in both
+    pub fn intersect(&self, other: &Self) -> Self {
+        Self(self.0.intersect(&other.0))
+    }
+
+    /// Every ID that is not fresh
+    pub fn complement(&self) -> Self {
+        Self(self.0.complement())
+    }
+
+    /// IDs that are not fresh between the lowest and highest fresh IDs
+    pub fn gaps(&self) -> Self {
+        Self(self.0.gaps())
+    }
+}
+impl fmt::Display for Ranges {
+    /// One `start-end` line per range
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for range in self.0.iter() {
+            writeln!(f, "{}-{}", range.start, range.end)?;
+        }
+        Ok(())
+    }
+}
+
+/// Fresh ranges and the ingredients to check against them
+#[derive(Debug, Clone)]
+pub struct Database(InputType);
+/// The example from the puzzle text, run with `--example`
+pub const EXAMPLE: &str = include_str!("../examples/example.txt");
+
+impl Solver for Database {
+    type Output = ReturnType;
+    type Error = ParseError;
+    const EXAMPLE: &'static str = EXAMPLE;
+
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        parse_str(input).map(Self)
+    }
+
+    fn part_one(&self) -> Result<ReturnType, SolveError> {
+        Ok(part_one_internal(self.0.clone()))
+    }
+
+    fn part_two(&self) -> Result<ReturnType, SolveError> {
+        part_two_internal(self.0.clone())
+    }
+}
+
+/// Internal logic for part_one
+pub fn part_one_internal(input: InputType) -> ReturnType {
+    let (fresh_ingredients, ingredients_to_check) = input;
+    ingredients_to_check
+        .0
+        .into_keys()
+        .filter(|ingredient| fresh_ingredients.contains(*ingredient))
+        .count() as ReturnType
+}
+
+/// Internal logic for part two
+pub fn part_two_internal(input: InputType) -> Result<ReturnType, SolveError> {
+    let (fresh_ingredients, _) = input;
+    count(&fresh_ingredients)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Function to split above into different inputs
+    fn parse_input_test(input: &str) -> InputType {
+        parse_str(input).unwrap()
+    }
+
+    #[test]
+    fn test_one() {
+        let input = parse_input_test(EXAMPLE);
+        let output = part_one_internal(input);
+
+        // TODO fill this out
+        assert_eq!(output, 3);
+    }
+
+    #[test]
+    fn test_solver() {
+        let database = Database::parse(EXAMPLE).unwrap();
+        assert_eq!((database.part_one(), database.part_two()), (Ok(3), Ok(14)));
+    }
+
+    #[test]
+    fn test_two() {
+        let input = parse_input_test(EXAMPLE);
+        let output = part_two_internal(input);
+
+        // TODO fill this out
+        assert_eq!(output, Ok(14));
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = parse_input_test(EXAMPLE);
+        assert_eq!(
+            explain(&input),
+            "1: spoiled\n5: fresh (3-5)\n8: spoiled\n11: fresh (10-20)\n17: fresh (10-20)\n32: spoiled\n"
+        );
+        assert_eq!(input.0.find(4), Some(&Range::new(3, 5)));
+    }
+
+    #[test]
+    fn test_parse_sections() {
+        // Windows line endings and extra blank or whitespace lines don't matter
+        let messy = format!(
+            "\r\n{}\r\n \r\n",
+            EXAMPLE.replace("\n\n", "\n \n\n").replace('\n', "\r\n")
+        );
+        assert_eq!(part_one_internal(parse_input_test(&messy)), 3);
+
+        assert_eq!(parse_str("3-5\n").unwrap_err(), ParseError::SectionCount(1));
+        assert_eq!(
+            parse_str("3-5\n5-x\n\n1").unwrap_err(),
+            ParseError::InvalidLine {
+                section: Section::Ranges,
+                line: 2,
+                text: "5-x".to_string()
+            }
+        );
+        assert_eq!(
+            parse_str("3-5\n\n1\n-2").unwrap_err(),
+            ParseError::InvalidLine {
+                section: Section::Ingredients,
+                line: 4,
+                text: "-2".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_part_one_streaming() {
+        assert_eq!(part_one(EXAMPLE.as_bytes()), Ok(3));
+        // Repeated ingredients count once, like in the collected list
+        let repeated = format!("{EXAMPLE}5\n\n");
+        assert_eq!(part_one(repeated.as_bytes()), Ok(3));
+        assert_eq!(part_one_internal(parse_input_test(&repeated)), 3);
+        // Read errors are returned rather than unwrapped
+        assert!(matches!(
+            part_one(&b"3-5\n\n\xff\n"[..]),
+            Err(ParseError::Read(_))
+        ));
+        assert_eq!(
+            part_one("\n3-5\n".as_bytes()),
+            Err(ParseError::SectionCount(1))
+        );
+        assert_eq!(
+            part_one("3-5\n\n1\n\n2\n\n3".as_bytes()),
+            Err(ParseError::SectionCount(4))
+        );
+        assert!(matches!(
+            part_one("3-5\n\n1\nx".as_bytes()),
+            Err(ParseError::InvalidLine {
+                section: Section::Ingredients,
+                line: 4,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn test_range_algebra() {
+        let (ranges, _) = parse_input_test(EXAMPLE);
+        let (other, _) = parse_input_test("1-4\n15-30\n\n1");
+        assert_eq!(ranges.gaps().to_string(), "6-9\n");
+        assert_eq!(ranges.union(&other).to_string(), "1-5\n10-30\n");
+        assert_eq!(ranges.intersect(&other).to_string(), "3-4\n15-20\n");
+        assert_eq!(
+            ranges.complement().to_string(),
+            format!("0-2\n6-9\n21-{}\n", Id::MAX)
+        );
+        assert_eq!(
+            compare(&ranges, &other),
+            Ok(Comparison {
+                either: 26,
+                both: 8,
+                only_first: 6,
+                only_second: 12
+            })
+        );
+    }
+
+    #[test]
+    fn test_chunked() {
+        let ids: String = (0..30).map(|id| format!("{id}\n")).collect();
+        let long = format!("{EXAMPLE}{ids}{ids}");
+        for chunk_lines in [1, 2, 7, 100] {
+            // 3-5 and 10-20, each once however the repeats fall across chunks
+            assert_eq!(part_one_chunked(long.as_bytes(), chunk_lines), Ok(14));
+        }
+        let bad = format!("{EXAMPLE}{}", "5\nx\ny\n");
+        for chunk_lines in [1, 2, 100] {
+            assert_eq!(
+                part_one_chunked(bad.as_bytes(), chunk_lines),
+                Err(invalid(Section::Ingredients, 13, "x"))
+            );
+        }
+    }
+
+    #[test]
+    fn test_wide_ids() {
+        // Past u64::MAX
+        let input = "99999999999999999990-100000000000000000010\n\n100000000000000000000\n5";
+        assert_eq!(part_one(input.as_bytes()), Ok(1));
+        assert_eq!(part_two_internal(parse_input_test(input)), Ok(21));
+
+        // Every ID is fresh, which is one more than the answer can hold
+        let every = format!("0-5\n6-{}\n\n1", Id::MAX);
+        assert_eq!(
+            part_two_internal(parse_input_test(&every))
+                .unwrap_err()
+                .to_string(),
+            "every ID is in the ranges, one more than 128 bits can count"
+        );
+    }
+
+    #[test]
+    fn test_histogram() {
+        let input = parse_input_test(&format!("{EXAMPLE}17\n4"));
+        assert_eq!(
+            histogram(&input),
+            vec![(Range::new(3, 5), 2), (Range::new(10, 20), 3)]
+        );
+        let (ranges, _) = parse_input_test(EXAMPLE);
+        let unchecked = parse_input_test("1-2\n\n3");
+        assert_eq!(
+            histogram(&(ranges, unchecked.1)),
+            vec![(Range::new(3, 5), 1), (Range::new(10, 20), 0)]
+        );
+    }
+
+    #[test]
+    fn test_ranges() {
+        let mut ranges = Ranges::default();
+        for line in ["20-25", "1-3", "4-6", "10-12", "2-5", "11-11", "26-26"] {
+            ranges.add_range(parse_range(line).unwrap());
+        }
+        let bounds: Vec<_> = ranges.0.iter().map(|r| (r.start, r.end)).collect();
+        assert_eq!(bounds, vec![(1, 6), (10, 12), (20, 26)]);
+        assert!(ranges.contains(26));
+        assert!(!ranges.contains(27));
+        assert_eq!(ranges.get_n(), Some(16));
+    }
+}
@@ -1,244 +1,138 @@
 //! Command line executable for running part one and part two
 use std::{
-    collections::HashSet,
-    fs::File,
-    io::{BufRead, BufReader},
-    time::Instant,
+    process::ExitCode,
+    time::{Duration, Instant},
 };
 
+use anyhow::Context;
+use aoc_common::{
+    cancel::TimeoutArgs,
+    input::{self, InputArgs},
+    log::LogArgs,
+    output::{self, OutputArgs, PartName, Record},
+    threads::ThreadArgs,
+};
 use clap::Parser;
+use day_five::{
+    EXAMPLE, compare, count, explain, histogram, parse_input, part_one, part_one_internal,
+    part_two_internal,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Input file
-    #[arg(short)]
-    input_file: String,
+    #[command(flatten)]
+    input: InputArgs,
 
-    #[command(subcommand)]
-    part: Part,
-}
+    /// Print the fresh range holding each checked ingredient, if any
+    #[arg(long)]
+    explain: bool,
 
-#[derive(clap::Subcommand, Debug)]
-enum Part {
-    Part1,
-    Part2,
-}
+    /// Print how many checked ingredients fall in each fresh range
+    #[arg(long)]
+    histogram: bool,
 
-fn main() {
-    let args = Args::parse();
+    #[command(flatten)]
+    output: OutputArgs,
 
-    let file = BufReader::new(File::open(args.input_file).expect("Cannot find file"));
+    #[command(flatten)]
+    log: LogArgs,
 
-    let start = Instant::now();
-    let answer = match args.part {
-        Part::Part1 => part_one(file),
-        Part::Part2 => part_two(file),
-    };
+    #[command(flatten)]
+    threads: ThreadArgs,
 
-    println!("{:?}", answer);
-    println!("Completed in {:?}", start.elapsed());
-}
+    #[command(flatten)]
+    timeout: TimeoutArgs,
 
-fn part_one(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file);
-    part_one_internal(input)
+    #[command(subcommand)]
+    part: Part,
 }
 
-fn part_two(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file);
-    part_two_internal(input)
+#[derive(clap::Subcommand, Debug)]
+enum Part {
+    Part1,
+    Part2,
+    /// Print the IDs between the fresh ranges that no range covers
+    Gaps,
+    /// Compare the fresh ranges with those of another input
+    Compare {
+        other_file: String,
+    },
 }
 
-fn parse_input(file: BufReader<File>) -> InputType {
-    // let mut fresh_ingredients = FreshIngredients::default();
-    let mut fresh_ingredients = Ranges::default();
-    let mut has_found_blank_line = false;
-    let mut ingredients = IngredientsList::default();
-    for line in file.lines() {
-        let line = line.unwrap();
-        if line.is_empty() {
-            has_found_blank_line = true;
-            continue;
-        }
-        if !has_found_blank_line {
-            fresh_ingredients.add_line(&line);
-        } else {
-            ingredients.add_ingredient(line.trim().parse().unwrap());
+impl Part {
+    fn name(&self) -> PartName {
+        match self {
+            Self::Part1 => 1.into(),
+            Self::Part2 => 2.into(),
+            Self::Gaps => "gaps".into(),
+            Self::Compare { .. } => "compare".into(),
         }
     }
-    (fresh_ingredients, ingredients)
 }
 
-// TODO -- Update this with the return type
-type ReturnType = usize;
-type InputType = (Ranges, IngredientsList);
-
-/// Ingredients List -- a HashSet with a number of times it is called
-#[derive(Debug, Clone, Default)]
-pub struct IngredientsList(HashSet<usize>);
-impl IngredientsList {
-    pub fn add_ingredient(&mut self, ingredient: usize) {
-        self.0.insert(ingredient);
-    }
+fn main() -> ExitCode {
+    output::exit(try_main())
 }
 
-/// Try without memoization
-#[derive(Debug, Clone)]
-pub struct Range {
-    pub start: usize,
-    pub end: usize,
-}
-impl Range {
-    pub fn new(input: &str) -> Self {
-        // Convert the range to numbers
-        let mut split = input.trim().split("-");
-        let start: usize = split.next().unwrap().parse().unwrap();
-        let end: usize = split.next().unwrap().parse().unwrap();
-        Self { start, end }
-    }
-
-    pub fn contains(&self, value: usize) -> bool {
-        value >= self.start && value <= self.end
-    }
-
-    pub fn contains_range(&self, other: &Range) -> bool {
-        // A range is not contained only if both start and end are either below my own start or
-        // above my own end
-        !((other.start < self.start && other.end < self.start)
-            || (other.start > self.end && other.end > self.end))
-    }
-
-    pub fn get_n(&self) -> usize {
-        self.end - self.start + 1
-    }
-}
-
-/// Ranges
-#[derive(Debug, Clone, Default)]
-pub struct Ranges(Vec<Range>);
-impl Ranges {
-    pub fn add_line(&mut self, input: &str) {
-        let r = Range::new(input);
-        self.add_range(r);
-    }
+fn try_main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    args.log.init();
+    args.threads.init()?;
+    args.timeout.init();
 
-    fn add_range(&mut self, r: Range) {
-        if let Some(idx) = self.get_contains_range_idx(&r) {
-            // Pop that range
-            let mut initial_range = self.0.swap_remove(idx);
-            // Modify the range
-            initial_range.start = initial_range.start.min(r.start);
-            initial_range.end = initial_range.end.max(r.end);
-            // Reinsert
-            self.add_range(initial_range);
-        } else {
-            self.0.push(r)
-        }
-    }
+    let file = args.input.open(EXAMPLE)?;
 
-    pub fn contains(&self, value: usize) -> bool {
-        for range in self.0.iter() {
-            if range.contains(value) {
-                return true;
-            }
-        }
-        false
-    }
-
-    fn get_contains_range_idx(&self, other: &Range) -> Option<usize> {
-        if let Some((idx, _)) = self
-            .0
-            .iter()
-            .enumerate()
-            .find(|(_, range)| range.contains_range(other))
-        {
-            Some(idx)
-        } else {
-            None
+    let name = args.part.name();
+    let mut parse = Duration::ZERO;
+    let start = Instant::now();
+    let answer = match args.part {
+        // Nothing needs the whole ingredient list, so classify it as it is read
+        Part::Part1 if !args.explain && !args.histogram => {
+            part_one(file).with_context(|| args.input.name().to_owned())?
         }
-    }
-
-    pub fn get_n(&self) -> usize {
-        self.0.iter().fold(0, |acc, range| acc + range.get_n())
-    }
-}
-
-/// Internal logic for part_one
-fn part_one_internal(input: InputType) -> ReturnType {
-    let (fresh_ingredients, ingredients_to_check) = input;
-    ingredients_to_check
-        .0
-        .into_iter()
-        .fold(0, |acc, ingredient| {
-            acc + if fresh_ingredients.contains(ingredient) {
-                1
-            } else {
-                0
+        part => {
+            let parsing = Instant::now();
+            let input = parse_input(file).with_context(|| args.input.name().to_owned())?;
+            parse = parsing.elapsed();
+            if args.explain {
+                print!("{}", explain(&input));
             }
-        })
-}
-
-/// Internal logic for part two
-fn part_two_internal(input: InputType) -> ReturnType {
-    let (fresh_ingredients, _) = input;
-    fresh_ingredients.get_n()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    /// Function to modify for input to test
-    fn input_one() -> &'static str {
-        "3-5
-10-14
-16-20
-12-18
-
-1
-5
-8
-11
-17
-32"
-    }
-
-    /// Function to split above into different inputs
-    fn parse_input_test(input: &str) -> InputType {
-        // let mut fresh_ingredients = FreshIngredients::default();
-        let mut fresh_ingredients = Ranges::default();
-        let mut has_found_blank_line = false;
-        let mut ingredients = IngredientsList::default();
-        for line in input.lines() {
-            if line.is_empty() {
-                has_found_blank_line = true;
-                continue;
+            if args.histogram {
+                for (range, count) in histogram(&input) {
+                    println!("{}-{}: {count}", range.start, range.end);
+                }
             }
-            if !has_found_blank_line {
-                fresh_ingredients.add_line(line);
-            } else {
-                ingredients.add_ingredient(line.trim().parse().unwrap());
+            match part {
+                Part::Part1 => part_one_internal(input),
+                Part::Part2 => part_two_internal(input)?,
+                Part::Gaps => {
+                    let gaps = input.0.gaps();
+                    print!("{gaps}");
+                    count(&gaps)?
+                }
+                Part::Compare { other_file } => {
+                    let other = parse_input(input::open(&other_file)?)
+                        .with_context(|| other_file.clone())?;
+                    let comparison = compare(&input.0, &other.0)?;
+                    println!("Either: {} IDs", comparison.either);
+                    println!("Both: {} IDs", comparison.both);
+                    println!("Only first: {} IDs", comparison.only_first);
+                    println!("Only second: {} IDs", comparison.only_second);
+                    comparison.both
+                }
             }
         }
-        (fresh_ingredients, ingredients)
-    }
-
-    #[test]
-    fn test_one() {
-        let input = parse_input_test(input_one());
-        let output = part_one_internal(input);
-
-        // TODO fill this out
-        assert_eq!(output, 3);
-    }
+    };
 
-    #[test]
-    fn test_two() {
-        let input = parse_input_test(input_one());
-        let output = part_two_internal(input);
+    args.output.print(&Record {
+        day: 5,
+        part: name,
+        answer: answer.to_string(),
+        // Streaming part one parses as it goes, so all of its time is solving
+        parse,
+        solve: start.elapsed() - parse,
+    });
 
-        // TODO fill this out
-        assert_eq!(output, 14);
-    }
+    Ok(())
 }
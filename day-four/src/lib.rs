@@ -0,0 +1,475 @@
+//! Paper roll grid whose accessible rolls are counted for part one and removed for part two
+use std::fmt;
+
+use aoc_common::{
+    automaton::{Automaton, MOORE, Rule, VON_NEUMANN},
+    solver::{SolveError, Solver},
+};
+
+// TODO -- Update this with the return type
+pub type ReturnType = usize;
+
+/// Which surrounding cells count as neighbors
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Neighborhood {
+    /// All eight surrounding cells
+    #[default]
+    Moore,
+    /// Only the four orthogonally adjacent cells
+    VonNeumann,
+}
+impl Neighborhood {
+    /// Offsets (row, col) to each neighbor
+    fn offsets(&self) -> &'static [(isize, isize)] {
+        match self {
+            Neighborhood::Moore => MOORE,
+            Neighborhood::VonNeumann => VON_NEUMANN,
+        }
+    }
+}
+
+/// Rules deciding when a roll is accessible
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Rules {
+    /// A roll is accessible when it has fewer than this many neighboring rolls
+    pub threshold: usize,
+    pub neighborhood: Neighborhood,
+}
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            threshold: 4,
+            neighborhood: Neighborhood::Moore,
+        }
+    }
+}
+
+/// Error from parsing a grid, with 1-indexed lines and columns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    InvalidChar {
+        line: usize,
+        column: usize,
+        c: char,
+    },
+    RaggedRow {
+        line: usize,
+        expected: usize,
+        found: usize,
+    },
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidChar { line, column, c } => {
+                write!(f, "line {line}, column {column}: unknown character {c:?}")
+            }
+            Self::RaggedRow {
+                line,
+                expected,
+                found,
+            } => write!(
+                f,
+                "line {line}: expected {expected} columns like the first line, found {found}"
+            ),
+        }
+    }
+}
+impl std::error::Error for ParseError {}
+
+/// Outcome of removing rolls until none are accessible
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Convergence {
+    /// Total rolls removed
+    pub removed: usize,
+    /// Rounds that removed at least one roll
+    pub rounds: usize,
+}
+
+/// Part two hit its round cap with rolls still accessible
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RoundLimitError {
+    pub rounds: usize,
+    pub removed: usize,
+}
+impl fmt::Display for RoundLimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rolls still accessible after {} rounds ({} removed so far)",
+            self.rounds, self.removed
+        )
+    }
+}
+impl std::error::Error for RoundLimitError {}
+
+/// A roll is removed once fewer than `threshold` of its neighbors are rolls, and rolls never
+/// come back
+#[derive(Debug, Clone, Copy)]
+struct RemovalRule {
+    threshold: usize,
+}
+impl Rule for RemovalRule {
+    fn next(&self, roll: bool, neighbors: usize) -> bool {
+        roll && neighbors >= self.threshold
+    }
+}
+
+/// Grid
+///
+/// Live cells of the automaton are rolls, and the cells it is about to flip are the accessible
+/// rolls
+#[derive(Debug, Clone)]
+pub struct Grid {
+    automaton: Automaton<RemovalRule>,
+    history: Vec<Vec<(usize, usize)>>, // Rolls removed in each round so far
+}
+impl Grid {
+    pub fn new(input: &str) -> Result<Self, ParseError> {
+        Self::with_rules(input, Rules::default())
+    }
+
+    /// Build a grid using the given accessibility rules
+    pub fn with_rules(input: &str, rules: Rules) -> Result<Self, ParseError> {
+        Self::parse(input, '@', rules)
+    }
+
+    /// Build a grid where `roll` marks a roll and `.` an empty cell
+    pub fn parse(input: &str, roll: char, rules: Rules) -> Result<Self, ParseError> {
+        let mut inner: Vec<Vec<bool>> = Vec::new();
+        for (idx_r, line) in input.lines().enumerate() {
+            // Convert a line to an array of bools
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(idx_c, c)| match c {
+                    c if c == roll => Ok(true),
+                    '.' => Ok(false),
+                    c => Err(ParseError::InvalidChar {
+                        line: idx_r + 1,
+                        column: idx_c + 1,
+                        c,
+                    }),
+                })
+                .collect::<Result<Vec<bool>, _>>()?;
+            if let Some(first) = inner.first()
+                && first.len() != row.len()
+            {
+                return Err(ParseError::RaggedRow {
+                    line: idx_r + 1,
+                    expected: first.len(),
+                    found: row.len(),
+                });
+            }
+            inner.push(row);
+        }
+        let rule = RemovalRule {
+            threshold: rules.threshold,
+        };
+        Ok(Self {
+            automaton: Automaton::new(inner, rules.neighborhood.offsets(), rule),
+            history: Vec::new(),
+        })
+    }
+
+    /// Apply each round's removals to the rows in parallel
+    pub fn parallel(self) -> Self {
+        Self {
+            automaton: self.automaton.parallel(),
+            ..self
+        }
+    }
+
+    pub fn count_roll_access(&self) -> usize {
+        self.automaton.pending().len()
+    }
+
+    /// Remove rolls until none are accessible, running at most `max_rounds` removal rounds
+    pub fn part2(&mut self, max_rounds: Option<usize>) -> Result<Convergence, RoundLimitError> {
+        self.part2_with(max_rounds, |_, _| {})
+    }
+
+    /// Same as `part2`, calling `on_round` with the grid and the removed cells after every round
+    /// that removed something
+    pub fn part2_with(
+        &mut self,
+        max_rounds: Option<usize>,
+        mut on_round: impl FnMut(&Self, &[(usize, usize)]),
+    ) -> Result<Convergence, RoundLimitError> {
+        let mut convergence = Convergence {
+            removed: 0,
+            rounds: 0,
+        };
+        loop {
+            if max_rounds == Some(convergence.rounds) && self.has_accessible() {
+                return Err(RoundLimitError {
+                    rounds: convergence.rounds,
+                    removed: convergence.removed,
+                });
+            }
+            let removed = self.step();
+            if removed.is_empty() {
+                return Ok(convergence);
+            }
+            convergence.removed += removed.len();
+            convergence.rounds += 1;
+            on_round(self, &removed);
+        }
+    }
+
+    /// Whether the next `step` would remove anything
+    fn has_accessible(&self) -> bool {
+        !self.automaton.is_stable()
+    }
+
+    /// Draw the grid, with `removed` cells highlighted in red
+    pub fn render(&self, removed: &[(usize, usize)]) -> String {
+        let mut canvas: Vec<Vec<&str>> = self
+            .automaton
+            .cells()
+            .iter()
+            .map(|row| row.iter().map(|c| if *c { "@" } else { "." }).collect())
+            .collect();
+        for (idx_r, idx_c) in removed {
+            canvas[*idx_r][*idx_c] = "\x1b[31mx\x1b[0m";
+        }
+        canvas.into_iter().map(|row| row.concat() + "\n").collect()
+    }
+
+    /// Remove every accessible roll at once, recording the round in the history
+    ///
+    /// Returns the rolls removed
+    pub fn step(&mut self) -> Vec<(usize, usize)> {
+        let removed = self.automaton.step();
+        if !removed.is_empty() {
+            self.history.push(removed.clone());
+        }
+        removed
+    }
+
+    /// Put back the rolls removed by the last round, returning them
+    pub fn undo(&mut self) -> Option<Vec<(usize, usize)>> {
+        let removed = self.history.pop()?;
+        self.automaton.flip(&removed);
+        Some(removed)
+    }
+
+    /// Rolls removed in each round so far
+    pub fn history(&self) -> &[Vec<(usize, usize)>] {
+        &self.history
+    }
+
+    pub fn removed_count(&self) -> usize {
+        self.history.iter().map(Vec::len).sum()
+    }
+}
+
+/// The example from the puzzle text, run with `--example`
+pub const EXAMPLE: &str = include_str!("../examples/example.txt");
+
+impl Solver for Grid {
+    type Output = ReturnType;
+    type Error = ParseError;
+    const EXAMPLE: &'static str = EXAMPLE;
+
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        Self::new(input)
+    }
+
+    fn part_one(&self) -> Result<ReturnType, SolveError> {
+        Ok(self.count_roll_access())
+    }
+
+    fn part_two(&self) -> Result<ReturnType, SolveError> {
+        let mut grid = self.clone();
+        let convergence = grid.part2(None).map_err(SolveError::new)?;
+        Ok(convergence.removed)
+    }
+}
+
+/// Internal logic for part_one
+pub fn part_one_internal(input: Grid) -> ReturnType {
+    input.count_roll_access()
+}
+
+/// Internal logic for part two
+pub fn part_two_internal(
+    mut input: Grid,
+    max_rounds: Option<usize>,
+) -> Result<Convergence, RoundLimitError> {
+    input.part2(max_rounds)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Function to split above into different inputs
+    fn parse_input_test(input: &str) -> Grid {
+        Grid::new(input).unwrap()
+    }
+
+    #[test]
+    fn test_one() {
+        let input = parse_input_test(EXAMPLE);
+        let output = part_one_internal(input);
+
+        // TODO fill this out
+        assert_eq!(output, 13);
+    }
+
+    #[test]
+    fn test_solver() {
+        let grid = <Grid as Solver>::parse(EXAMPLE).unwrap();
+        assert_eq!((grid.part_one(), grid.part_two()), (Ok(13), Ok(43)));
+    }
+
+    #[test]
+    fn test_two() {
+        let input = parse_input_test(EXAMPLE);
+        let output = part_two_internal(input, None).unwrap();
+
+        // TODO fill this out
+        assert_eq!(output.removed, 43);
+        assert_eq!(output.rounds, 9);
+    }
+
+    #[test]
+    fn test_evolve_rounds() {
+        let mut input = parse_input_test(EXAMPLE);
+        let mut removed = Vec::new();
+        let total = input
+            .part2_with(None, |_, round| removed.push(round.len()))
+            .unwrap();
+        // The first round removes exactly the rolls accessible in part one
+        assert_eq!(removed, vec![13, 12, 7, 5, 2, 1, 1, 1, 1]);
+        assert_eq!(total.removed, 43);
+        assert!(input.automaton.is_stable());
+    }
+
+    #[test]
+    fn test_rules() {
+        // An X shape: the centre touches the corners only diagonally
+        let x = "@.@\n.@.\n@.@";
+        assert_eq!(Grid::new(x).unwrap().count_roll_access(), 4);
+        let strict = Rules {
+            threshold: 1,
+            neighborhood: Neighborhood::Moore,
+        };
+        assert_eq!(Grid::with_rules(x, strict).unwrap().count_roll_access(), 0);
+        let von_neumann = Rules {
+            threshold: 1,
+            neighborhood: Neighborhood::VonNeumann,
+        };
+        assert_eq!(
+            Grid::with_rules(x, von_neumann)
+                .unwrap()
+                .count_roll_access(),
+            5
+        );
+        // Everything goes when the threshold exceeds the neighborhood size
+        let loose = Rules {
+            threshold: 9,
+            neighborhood: Neighborhood::Moore,
+        };
+        let mut grid = Grid::with_rules(EXAMPLE, loose).unwrap();
+        assert_eq!(grid.count_roll_access(), 71);
+        assert_eq!(grid.part2(None).unwrap().removed, 71);
+    }
+
+    #[test]
+    fn test_parallel_matches_sequential() {
+        let von_neumann = Rules {
+            threshold: 3,
+            neighborhood: Neighborhood::VonNeumann,
+        };
+        for rules in [Rules::default(), von_neumann] {
+            let mut sequential = Grid::with_rules(EXAMPLE, rules).unwrap();
+            let mut parallel = Grid::with_rules(EXAMPLE, rules).unwrap().parallel();
+            loop {
+                let expected = sequential.step();
+                let mut removed = parallel.step();
+                removed.sort();
+                let mut expected_sorted = expected.clone();
+                expected_sorted.sort();
+                assert_eq!(removed, expected_sorted);
+                assert_eq!(parallel.automaton.cells(), sequential.automaton.cells());
+                if expected.is_empty() {
+                    break;
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            Grid::new("@.@\n.#@").unwrap_err(),
+            ParseError::InvalidChar {
+                line: 2,
+                column: 2,
+                c: '#'
+            }
+        );
+        assert_eq!(
+            Grid::new("@.@\n.@").unwrap_err(),
+            ParseError::RaggedRow {
+                line: 2,
+                expected: 3,
+                found: 2
+            }
+        );
+        // A different roll character reads the same grid
+        let grid = Grid::parse(&EXAMPLE.replace('@', "o"), 'o', Rules::default()).unwrap();
+        assert_eq!(part_one_internal(grid), 13);
+        assert!(matches!(
+            Grid::parse(EXAMPLE, 'o', Rules::default()),
+            Err(ParseError::InvalidChar { c: '@', .. })
+        ));
+    }
+
+    #[test]
+    fn test_max_rounds() {
+        // Exactly enough rounds is fine
+        let output = part_two_internal(parse_input_test(EXAMPLE), Some(9)).unwrap();
+        assert_eq!(output.rounds, 9);
+        assert_eq!(
+            part_two_internal(parse_input_test(EXAMPLE), Some(3)),
+            Err(RoundLimitError {
+                rounds: 3,
+                removed: 32
+            })
+        );
+    }
+
+    #[test]
+    fn test_step_undo() {
+        let mut input = parse_input_test(EXAMPLE);
+        let start = input.render(&[]);
+        assert_eq!(input.undo(), None);
+        let first = input.step();
+        let after_first = input.render(&[]);
+        input.step();
+        assert_eq!(input.history().len(), 2);
+        assert_eq!(input.removed_count(), 25);
+        input.undo();
+        assert_eq!(input.render(&[]), after_first);
+        assert_eq!(input.count_roll_access(), 12);
+        assert_eq!(input.undo(), Some(first));
+        assert_eq!(input.render(&[]), start);
+        assert_eq!(input.count_roll_access(), 13);
+        // Running to the end from here matches a fresh run
+        assert_eq!(input.part2(None).unwrap().removed, 43);
+    }
+
+    #[test]
+    fn test_render() {
+        let mut input = parse_input_test("@@@\n.@.");
+        assert_eq!(input.render(&[]), "@@@\n.@.\n");
+        let removed = input.step();
+        assert_eq!(
+            input.render(&removed),
+            "\x1b[31mx\x1b[0m\x1b[31mx\x1b[0m\x1b[31mx\x1b[0m\n.\x1b[31mx\x1b[0m.\n"
+        );
+    }
+}
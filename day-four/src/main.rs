@@ -1,18 +1,70 @@
 //! Command line executable for running part one and part two
 use std::{
-    fs::File,
-    io::{BufReader, Read},
-    time::Instant,
+    io::BufRead,
+    process::ExitCode,
+    time::{Duration, Instant},
 };
 
+use anyhow::Context;
+use aoc_common::{
+    cancel::TimeoutArgs,
+    input::InputArgs,
+    log::LogArgs,
+    output::{self, OutputArgs, PartName, Record},
+    threads::ThreadArgs,
+};
 use clap::Parser;
+use day_four::{
+    Convergence, EXAMPLE, Grid, Neighborhood, ParseError, ReturnType, RoundLimitError, Rules,
+    part_one_internal, part_two_internal,
+};
+use tracing::info;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Input file
-    #[arg(short)]
-    input_file: String,
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Redraw the grid after every removal round of part two, highlighting removed rolls
+    #[arg(long)]
+    animate: bool,
+
+    /// Delay between animation frames, in milliseconds
+    #[arg(long, default_value_t = 200)]
+    frame_delay: u64,
+
+    /// A roll is accessible when fewer than this many neighbors are rolls
+    #[arg(long, default_value_t = 4)]
+    threshold: usize,
+
+    /// Which cells count as neighbors
+    #[arg(long, value_enum, default_value_t)]
+    neighborhood: Neighborhood,
+
+    /// Update the rows in parallel each round, every row handling only the removals near it
+    #[arg(long)]
+    parallel: bool,
+
+    /// Character marking a roll; `.` marks an empty cell
+    #[arg(long, default_value_t = '@')]
+    roll: char,
+
+    /// Give up on part two if rolls are still being removed after this many rounds
+    #[arg(long)]
+    max_rounds: Option<usize>,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(flatten)]
+    log: LogArgs,
+
+    #[command(flatten)]
+    threads: ThreadArgs,
+
+    #[command(flatten)]
+    timeout: TimeoutArgs,
 
     #[command(subcommand)]
     part: Part,
@@ -22,246 +74,115 @@ struct Args {
 enum Part {
     Part1,
     Part2,
+    /// Walk through part two from the terminal: Enter steps a round, `u` undoes one, `q` quits
+    Interactive,
+}
+impl Part {
+    fn name(&self) -> PartName {
+        match self {
+            Self::Part1 => 1.into(),
+            Self::Part2 => 2.into(),
+            Self::Interactive => "interactive".into(),
+        }
+    }
 }
 
-fn main() {
+fn main() -> ExitCode {
+    output::exit(try_main())
+}
+
+fn try_main() -> anyhow::Result<()> {
     let args = Args::parse();
+    args.log.init();
+    args.threads.init()?;
+    args.timeout.init();
+
+    let s = args.input.read_to_string(EXAMPLE)?;
+    let rules = Rules {
+        threshold: args.threshold,
+        neighborhood: args.neighborhood,
+    };
 
-    let file = BufReader::new(File::open(args.input_file).expect("Cannot find file"));
+    let start = Instant::now();
+    let input = parse_input(&s, args.roll, rules, args.parallel)
+        .with_context(|| args.input.name().to_owned())?;
+    let parse = start.elapsed();
 
     let start = Instant::now();
     let answer = match args.part {
-        Part::Part1 => part_one(file),
-        Part::Part2 => part_two(file),
+        Part::Part1 => part_one_internal(input),
+        Part::Part2 if args.animate => converged(part_two_animated(
+            input,
+            args.max_rounds,
+            Duration::from_millis(args.frame_delay),
+        ))?,
+        Part::Part2 => converged(part_two_internal(input, args.max_rounds))?,
+        Part::Interactive => interactive(input),
     };
 
-    println!("{:?}", answer);
-    println!("Completed in {:?}", start.elapsed());
-}
+    args.output.print(&Record {
+        day: 4,
+        part: args.part.name(),
+        answer: answer.to_string(),
+        parse,
+        solve: start.elapsed(),
+    });
 
-fn part_one(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file);
-    part_one_internal(input)
+    Ok(())
 }
 
-fn part_two(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file);
-    part_two_internal(input)
+/// Report how many rounds part two took, or fail if it hit the round cap
+fn converged(result: Result<Convergence, RoundLimitError>) -> Result<ReturnType, RoundLimitError> {
+    let convergence = result?;
+    info!(rounds = convergence.rounds, "Converged");
+    Ok(convergence.removed)
 }
 
-fn parse_input(mut file: BufReader<File>) -> Grid {
-    let mut s = String::new();
-    file.read_to_string(&mut s).unwrap();
-    Grid::new(&s)
+/// Part two, redrawing the grid in the terminal after every round
+fn part_two_animated(
+    mut input: Grid,
+    max_rounds: Option<usize>,
+    frame_delay: Duration,
+) -> Result<Convergence, RoundLimitError> {
+    let mut round = 0;
+    input.part2_with(max_rounds, |grid, removed| {
+        round += 1;
+        // Clear the screen and move the cursor home before drawing
+        print!("\x1b[2J\x1b[H{}", grid.render(removed));
+        println!("Round {round}: removed {} rolls", removed.len());
+        std::thread::sleep(frame_delay);
+    })
 }
 
-// TODO -- Update this with the return type
-type ReturnType = usize;
-
-/// Grid
-#[derive(Debug, Clone)]
-pub struct Grid {
-    inner: Vec<Vec<bool>>, // Represents whether or not there is a roll there
-    neighbor_map: Vec<Vec<usize>>, // Represents the number of neighbors with a roll
-    accessibility_map: Vec<Vec<bool>>, // Represents whether the roll is accessible or not
-}
-impl Grid {
-    pub fn new(input: &str) -> Self {
-        let inner: Vec<Vec<bool>> = input
-            .lines()
-            .map(|line| {
-                // Convert a line to an array of bools
-                line.chars().map(|c| matches!(c, '@')).collect()
-            })
-            .collect();
-        let (neighbor_map, accessibility_map) = Self::populate_neighbor_map(&inner);
-        Self {
-            inner,
-            neighbor_map,
-            accessibility_map,
-        }
-    }
-
-    pub fn count_roll_access(&self) -> usize {
-        self.accessibility_map.iter().fold(0, |acc, row| {
-            acc + row
-                .iter()
-                .fold(0, |acc_row, c| acc_row + if *c { 1 } else { 0 })
-        })
-    }
-
-    pub fn part2(&mut self) -> usize {
-        let mut s = 0;
-        loop {
-            let n_rolls_removed = self.evolve();
-            s += n_rolls_removed;
-            if n_rolls_removed == 0 {
-                return s;
-            }
-        }
-    }
-
-    fn populate_neighbor_map(inner: &[Vec<bool>]) -> (Vec<Vec<usize>>, Vec<Vec<bool>>) {
-        let mut neighbor_map = Vec::new();
-        let mut part1_map = Vec::new();
-        for r in 0..inner.len() {
-            let mut row_vec = Vec::new();
-            let mut row_vec_pt1 = Vec::new();
-            for c in 0..inner[0].len() {
-                let mut sum_neighbors = 0;
-                for offset_r in -1..=1 {
-                    for offset_c in -1..=1 {
-                        if let Ok((idx_r, idx_c)) =
-                            Self::check_neighbor(inner, r, c, offset_r, offset_c)
-                            && inner[idx_r][idx_c]
-                        {
-                            sum_neighbors += 1;
-                        }
-                    }
-                }
-                row_vec.push(sum_neighbors);
-                row_vec_pt1.push(sum_neighbors < 4 && inner[r][c]);
-            }
-            neighbor_map.push(row_vec);
-            part1_map.push(row_vec_pt1);
-        }
-        (neighbor_map, part1_map)
-    }
-
-    /// Validate whether this neighbor
-    fn check_neighbor(
-        inner: &[Vec<bool>],
-        row: usize,
-        col: usize,
-        offset_row: i8,
-        offset_col: i8,
-    ) -> Result<(usize, usize), ()> {
-        // This is not a neighbor
-        if offset_row == 0 && offset_col == 0 {
-            return Err(());
-        }
-
-        let new_row = (row as isize) + (offset_row as isize);
-        let new_row = if new_row >= (inner.len() as isize) || new_row < 0 {
-            return Err(());
-        } else {
-            new_row as usize
+/// Step through part two driven by commands on stdin, returning the rolls removed at the end
+fn interactive(mut input: Grid) -> ReturnType {
+    let mut removed = Vec::new();
+    let mut lines = std::io::stdin().lock().lines();
+    loop {
+        print!("{}", input.render(&removed));
+        println!(
+            "Round {}: {} rolls removed, {} accessible [Enter: step, u: undo, q: quit]",
+            input.history().len(),
+            input.removed_count(),
+            input.count_roll_access()
+        );
+        let Some(Ok(line)) = lines.next() else {
+            break;
         };
-
-        let new_col = (col as isize) + (offset_col as isize);
-        let new_col = if new_col >= (inner[0].len() as isize) || new_col < 0 {
-            return Err(());
-        } else {
-            new_col as usize
-        };
-        Ok((new_row, new_col))
-    }
-
-    /// Function to evolve -- remove the rolls and recompute everything
-    ///
-    /// Returns the number of rolls removed
-    fn evolve(&mut self) -> usize {
-        // Start by copying the accessibility_map
-        let accessibility_map = self.accessibility_map.clone();
-        let mut n_rolls_removed = 0;
-        // We do not need to copy the accessibility_map, as we can modify that in place
-
-        for (idx_r, row) in accessibility_map.iter().enumerate() {
-            for (idx_c, entry) in row.iter().enumerate() {
-                // If the entry is accessible, remove it
-                if *entry {
-                    // Accessible, let's remove
-                    n_rolls_removed += 1;
-                    // Modify the current board to be false in that location
-                    self.inner[idx_r][idx_c] = false;
-                    self.accessibility_map[idx_r][idx_c] = false;
-                    // Modify the neighbors counts to no longer consider that one as a roll
-                    Self::update_removal_and_accessibility_of_neighbors(
-                        &self.inner,
-                        &mut self.neighbor_map,
-                        &mut self.accessibility_map,
-                        idx_r,
-                        idx_c,
-                    );
-                }
-            }
-        }
-        n_rolls_removed
-    }
-
-    /// Update the removal and accessility of neighbors
-    fn update_removal_and_accessibility_of_neighbors(
-        inner: &[Vec<bool>],
-        neighbor_map: &mut [Vec<usize>],
-        accessibility_map: &mut [Vec<bool>],
-        row: usize,
-        col: usize,
-    ) {
-        for offset_row in -1..=1 {
-            for offset_col in -1..=1 {
-                if let Ok((idx_r, idx_c)) =
-                    Self::check_neighbor(inner, row, col, offset_row, offset_col)
-                {
-                    // Subtract from the neighbor map
-                    neighbor_map[idx_r][idx_c] -= 1; // We don't have to check, because we know
-                    // previously it had at least one
-                    // Re-evaluate accessibility_map
-                    accessibility_map[idx_r][idx_c] =
-                        neighbor_map[idx_r][idx_c] < 4 && inner[idx_r][idx_c]
-                }
+        match line.trim() {
+            "" => removed = input.step(),
+            "u" => {
+                input.undo();
+                removed.clear();
             }
+            "q" => break,
+            other => println!("Unknown command {other:?}"),
         }
     }
+    input.removed_count()
 }
 
-/// Internal logic for part_one
-fn part_one_internal(input: Grid) -> ReturnType {
-    input.count_roll_access()
-}
-
-/// Internal logic for part two
-fn part_two_internal(mut input: Grid) -> ReturnType {
-    input.part2()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    /// Function to modify for input to test
-    fn input_one() -> &'static str {
-        "..@@.@@@@.
-@@@.@.@.@@
-@@@@@.@.@@
-@.@@@@..@.
-@@.@@@@.@@
-.@@@@@@@.@
-.@.@.@.@@@
-@.@@@.@@@@
-.@@@@@@@@.
-@.@.@@@.@."
-    }
-
-    /// Function to split above into different inputs
-    fn parse_input_test(input: &str) -> Grid {
-        Grid::new(input)
-    }
-
-    #[test]
-    fn test_one() {
-        let input = parse_input_test(input_one());
-        let output = part_one_internal(input);
-
-        // TODO fill this out
-        assert_eq!(output, 13);
-    }
-
-    #[test]
-    fn test_two() {
-        let input = parse_input_test(input_one());
-        let output = part_two_internal(input);
-
-        // TODO fill this out
-        assert_eq!(output, 43);
-    }
+fn parse_input(s: &str, roll: char, rules: Rules, parallel: bool) -> Result<Grid, ParseError> {
+    let grid = Grid::parse(s, roll, rules)?;
+    Ok(if parallel { grid.parallel() } else { grid })
 }
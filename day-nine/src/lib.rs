@@ -0,0 +1,213 @@
+//! Largest rectangles between red tiles, anywhere for part one and inside their loop for part two
+use aoc_common::{
+    cancel,
+    input::LineError,
+    progress::{self, ProgressIterator},
+    solver::{SolveError, Solver},
+};
+use geo::{Contains as _, Coord, LineString, Polygon, Rect};
+use tracing::warn;
+
+/// Location
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+struct Location {
+    x: usize,
+    y: usize,
+}
+impl Location {
+    /// Parse `x,y`, failing with the reason
+    pub fn new(line: &str) -> Result<Self, String> {
+        let (x, y) = line
+            .trim()
+            .split_once(',')
+            .ok_or_else(|| format!("expected x,y, found {line:?}"))?;
+        let coord = |c: &str| {
+            c.parse()
+                .map_err(|e| format!("{c:?} is not a coordinate: {e}"))
+        };
+        Ok(Self {
+            x: coord(x)?,
+            y: coord(y)?,
+        })
+    }
+
+    pub fn area(&self, other: &Location) -> usize {
+        // Area is distance in x and distance in y
+        (((self.x as isize - other.x as isize).abs() + 1)
+            * ((self.y as isize - other.y as isize).abs() + 1)) as usize
+    }
+}
+
+/// Driver
+#[derive(Debug, Clone)]
+pub struct Driver {
+    red_tiles: Vec<Location>,
+}
+
+/// The example from the puzzle text, run with `--example`
+pub const EXAMPLE: &str = include_str!("../examples/example.txt");
+
+impl Solver for Driver {
+    type Output = usize;
+    type Error = LineError;
+    const EXAMPLE: &'static str = EXAMPLE;
+
+    fn parse(s: &str) -> Result<Self, LineError> {
+        let red_tiles = s
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| Location::new(line).map_err(|e| LineError::new(idx + 1, e)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { red_tiles })
+    }
+
+    fn part_one(&self) -> Result<usize, SolveError> {
+        self.check_corners()?;
+        let mut max_area = 0_usize;
+        for idx0 in 0..self.red_tiles.len() - 1 {
+            let tile0 = &self.red_tiles[idx0];
+            for idx1 in idx0 + 1..self.red_tiles.len() {
+                let tile1 = &self.red_tiles[idx1];
+                max_area = max_area.max(tile0.area(tile1));
+            }
+        }
+        Ok(max_area)
+    }
+
+    fn part_two(&self) -> Result<usize, SolveError> {
+        self.check_corners()?;
+        let board = Board::new(&self.red_tiles);
+        let mut areas = Vec::new();
+        for idx0 in 0..self.red_tiles.len() - 1 {
+            let tile0 = &self.red_tiles[idx0];
+            for idx1 in idx0 + 1..self.red_tiles.len() {
+                let tile1 = &self.red_tiles[idx1];
+                let area = tile0.area(tile1);
+                areas.push(AreaResults {
+                    area,
+                    tile0: tile0.clone(),
+                    tile1: tile1.clone(),
+                });
+            }
+        }
+        // Now, we need to sort the areas and then iterate until we find one that is valid
+        areas.sort_by_key(|val| val.area);
+        areas.reverse();
+        let checking = progress::bar(areas.len() as u64, "rectangles");
+        for area in areas.into_iter().progress_with(checking) {
+            if cancel::is_cancelled() {
+                // Every larger rectangle was already found to stick out
+                warn!(
+                    "Stopped early, the largest rectangle inside has an area of at most {}",
+                    area.area
+                );
+                return Ok(area.area);
+            }
+            if board.contains(&area) {
+                return Ok(area.area);
+            }
+        }
+        Err(SolveError::new("no rectangle fits inside the loop"))
+    }
+}
+impl Driver {
+    /// A rectangle needs two red tiles for its opposite corners
+    fn check_corners(&self) -> Result<(), SolveError> {
+        if self.red_tiles.len() < 2 {
+            return Err(SolveError::new(format!(
+                "a rectangle needs two red tiles, found {}",
+                self.red_tiles.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Results
+#[derive(Debug, Clone)]
+struct AreaResults {
+    area: usize,
+    tile0: Location,
+    tile1: Location,
+}
+
+/// A different way to represent the board
+#[derive(Debug, Clone)]
+struct Board {
+    hull: Polygon<f32>,
+}
+impl Board {
+    pub fn new(red_tiles: &[Location]) -> Self {
+        let coords: Vec<Coord<f32>> = red_tiles
+            .iter()
+            .map(|tile| Coord {
+                x: tile.x as f32,
+                y: tile.y as f32,
+            })
+            .collect();
+        let mut linestring = LineString::from(coords);
+        linestring.close(); // Make sure that it is closed
+        let polygon = Polygon::new(linestring, Vec::new());
+        Self { hull: polygon }
+    }
+
+    pub fn contains(&self, result: &AreaResults) -> bool {
+        let min_x = result.tile0.x.min(result.tile1.x) as f32;
+        let max_x = result.tile0.x.max(result.tile1.x) as f32;
+        let min_y = result.tile0.y.min(result.tile1.y) as f32;
+        let max_y = result.tile0.y.max(result.tile1.y) as f32;
+        let rect: Polygon<_> =
+            Rect::new(Coord { x: min_x, y: min_y }, Coord { x: max_x, y: max_y }).into();
+        self.hull.contains(&rect)
+    }
+}
+
+pub fn part_one(s: &str) -> anyhow::Result<usize> {
+    Ok(Driver::parse(s)?.part_one()?)
+}
+
+pub fn part_two(s: &str) -> anyhow::Result<usize> {
+    Ok(Driver::parse(s)?.part_two()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one() {
+        let output = part_one(EXAMPLE).unwrap();
+
+        // TODO fill this out
+        assert_eq!(output, 50);
+    }
+
+    #[test]
+    fn test_two() {
+        let output = part_two(EXAMPLE).unwrap();
+
+        // TODO fill this out
+        assert_eq!(output, 24);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        assert_eq!(
+            Driver::parse("7,1\n11;1").unwrap_err().to_string(),
+            "line 2: expected x,y, found \"11;1\""
+        );
+        assert_eq!(
+            Driver::parse("7,x").unwrap_err().to_string(),
+            "line 1: \"x\" is not a coordinate: invalid digit found in string"
+        );
+    }
+
+    #[test]
+    fn test_too_few_tiles() {
+        for input in ["", "7,1"] {
+            let driver = Driver::parse(input).unwrap();
+            assert!(driver.part_one().is_err(), "{input:?}");
+            assert!(driver.part_two().is_err(), "{input:?}");
+        }
+    }
+}
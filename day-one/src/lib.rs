@@ -0,0 +1,649 @@
+//! Dial rotations and the counter that follows them for part one and part two
+use std::{fmt, str::FromStr};
+
+use aoc_common::solver::{SolveError, Solver};
+
+/// Rotation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    Left(u64),
+    Right(u64),
+}
+impl Rotation {
+    /// Every instruction on a line, separated by commas and/or whitespace
+    pub fn parse_line(line: &str) -> Result<Vec<Self>, InvalidRotation> {
+        let is_separator = |c: char| c == ',' || c.is_whitespace();
+        let mut rotations = Vec::new();
+        let mut rest = line.trim_start_matches(is_separator);
+        while let Some(direction) = rest.chars().next() {
+            // The letter, any whitespace after it, and the digits
+            let after = rest[direction.len_utf8()..].trim_start();
+            let digits = after
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(after.len());
+            let (instruction, tail) = rest.split_at(rest.len() - after.len() + digits);
+            // Report the whole word, not just the part that looked like an instruction
+            let rotation = instruction.parse().map_err(|_| {
+                InvalidRotation(
+                    rest.split(is_separator)
+                        .next()
+                        .unwrap_or_default()
+                        .to_string(),
+                )
+            })?;
+            rotations.push(rotation);
+            rest = tail.trim_start_matches(is_separator);
+        }
+        Ok(rotations)
+    }
+}
+impl FromStr for Rotation {
+    type Err = InvalidRotation;
+
+    /// A single instruction such as `L68`, in either case, with optional whitespace around it and
+    /// after the letter
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || InvalidRotation(s.trim().to_string());
+        let mut chars = s.trim().chars();
+        let direction = chars.next().ok_or_else(invalid)?;
+        let value = chars.as_str().trim_start();
+        // `parse` would let a sign through
+        if !value.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(invalid());
+        }
+        let value: u64 = value.parse().map_err(|_| invalid())?;
+        match direction.to_ascii_uppercase() {
+            'L' => Ok(Rotation::Left(value)),
+            'R' => Ok(Rotation::Right(value)),
+            _ => Err(invalid()),
+        }
+    }
+}
+impl fmt::Display for Rotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Left(v) => write!(f, "L{v}"),
+            Self::Right(v) => write!(f, "R{v}"),
+        }
+    }
+}
+
+/// What a single rotation did to the dial
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Step {
+    /// Where the dial stopped
+    pub position: u16,
+    /// Number of times it pointed at the target on the way, including where it stopped
+    pub hits: u64,
+}
+
+/// Counter
+#[derive(Debug, Clone)]
+pub struct Counter {
+    /// Number of positions on the dial
+    size: u16,
+    val: u16,
+    /// Position counted, zero in the puzzle
+    target: u16,
+    counter_pt_1: u64,
+    /// `None` once the count no longer fits in 64 bits
+    counter_pt_2: Option<u64>,
+}
+impl Default for Counter {
+    fn default() -> Self {
+        Self::new(100, 50)
+    }
+}
+impl Counter {
+    /// Dial of `size` positions, from 0 to `size - 1`, pointing at `start`
+    ///
+    /// # Panics
+    /// If `start` is not on the dial
+    pub fn new(size: u16, start: u16) -> Self {
+        assert!(start < size, "{start} is not on a dial of {size} positions");
+        Self {
+            size,
+            val: start,
+            target: 0,
+            counter_pt_1: 0,
+            counter_pt_2: Some(0),
+        }
+    }
+
+    /// Same dial, counting the landings on and passes through `target` instead of zero
+    ///
+    /// # Panics
+    /// If `target` is not on the dial
+    pub fn with_target(self, target: u16) -> Self {
+        assert!(
+            target < self.size,
+            "{target} is not on a dial of {} positions",
+            self.size
+        );
+        Self { target, ..self }
+    }
+
+    pub fn rotate(&mut self, rot: &Rotation) {
+        self.rotate_traced(rot);
+    }
+
+    /// Same as `rotate`, telling where the dial stopped and how often it went by the target
+    pub fn rotate_traced(&mut self, rot: &Rotation) -> Step {
+        let size = u64::from(self.size);
+        let (val, target) = (u64::from(self.val), u64::from(self.target));
+        // Whole turns bring the dial back where it was, so only the clicks left over move it
+        let (v, stop) = match rot {
+            Rotation::Left(v) => (*v, (val + size - *v % size) % size),
+            Rotation::Right(v) => (*v, (val + *v % size) % size),
+        };
+        // Clicks until the dial first reaches the target, a full turn when it starts there
+        let diff = match rot {
+            Rotation::Left(_) => (val + size - target) % size,
+            Rotation::Right(_) => (target + size - val) % size,
+        };
+        let diff = if diff == 0 { size } else { diff };
+        let hits = v.checked_sub(diff).map_or(0, |rest| rest / size + 1);
+        self.counter_pt_2 = self.counter_pt_2.and_then(|count| count.checked_add(hits));
+        self.val = stop as u16;
+        if self.val == self.target {
+            self.counter_pt_1 += 1;
+        }
+        Step {
+            position: self.val,
+            hits,
+        }
+    }
+
+    /// Apply every rotation in order, returning the part one and part two counts
+    pub fn apply_all(&mut self, rotations: &[Rotation]) -> (u64, Option<u64>) {
+        for rot in rotations {
+            self.rotate(rot);
+        }
+        (self.counter_pt_1, self.counter_pt_2)
+    }
+
+    pub fn get_counter_pt_1(&self) -> u64 {
+        self.counter_pt_1
+    }
+
+    /// Passes through the target so far, or `None` if there have been more than fit in 64 bits
+    pub fn get_counter_pt_2(&self) -> Option<u64> {
+        self.counter_pt_2
+    }
+}
+
+/// Text that is not a rotation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidRotation(pub String);
+impl fmt::Display for InvalidRotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "expected a rotation like L68, found {:?}", self.0)
+    }
+}
+impl std::error::Error for InvalidRotation {}
+
+/// Instruction in the input that is not a rotation, on a 1-indexed line
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    line: usize,
+    rotation: InvalidRotation,
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "line {}: {}", self.line, self.rotation)
+    }
+}
+impl std::error::Error for ParseError {}
+
+/// Rotations with the 1-indexed line each is on, any number of them per line
+pub fn parse_input(input: &str) -> Result<Vec<(usize, Rotation)>, ParseError> {
+    let mut rotations = Vec::new();
+    for (idx, line) in input.lines().enumerate() {
+        let found = Rotation::parse_line(line).map_err(|rotation| ParseError {
+            line: idx + 1,
+            rotation,
+        })?;
+        rotations.extend(found.into_iter().map(|rot| (idx + 1, rot)));
+    }
+    Ok(rotations)
+}
+
+pub fn part_one(rotations: &[Rotation], mut counter: Counter) -> u64 {
+    counter.apply_all(rotations).0
+}
+
+pub fn part_two(rotations: &[Rotation], counter: Counter) -> Result<u64, SolveError> {
+    both(rotations, counter).map(|(_, two)| two)
+}
+
+/// One line per rotation with where the dial stopped, how often it went by the target, and the
+/// counts for both parts so far
+///
+/// The rotations come from the 1-indexed `lines` of the input
+pub fn trace(rotations: &[Rotation], lines: &[usize], mut counter: Counter) -> String {
+    rotations
+        .iter()
+        .zip(lines)
+        .map(|(rot, line)| {
+            let step = counter.rotate_traced(rot);
+            let part_two = counter
+                .get_counter_pt_2()
+                .map_or_else(|| "overflowed".to_string(), |count| count.to_string());
+            format!(
+                "line {line}: {rot} -> {} (hits {}, totals {} {part_two})\n",
+                step.position,
+                step.hits,
+                counter.get_counter_pt_1(),
+            )
+        })
+        .collect()
+}
+
+/// Answers to both parts, turning the dial once, failing if part two's count overflows
+pub fn both(rotations: &[Rotation], mut counter: Counter) -> Result<(u64, u64), SolveError> {
+    let (one, two) = counter.apply_all(rotations);
+    let two = two.ok_or_else(|| SolveError::new("the part two count overflows 64 bits"))?;
+    Ok((one, two))
+}
+
+/// Every rotation of the input, on the puzzle's dial
+#[derive(Debug, Clone)]
+pub struct Instructions {
+    rotations: Vec<Rotation>,
+}
+
+/// The example from the puzzle text, run with `--example`
+pub const EXAMPLE: &str = include_str!("../examples/example.txt");
+
+impl Solver for Instructions {
+    type Output = u64;
+    type Error = ParseError;
+    const EXAMPLE: &'static str = EXAMPLE;
+
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let rotations = parse_input(input)?
+            .into_iter()
+            .map(|(_, rot)| rot)
+            .collect();
+        Ok(Self { rotations })
+    }
+
+    fn part_one(&self) -> Result<u64, SolveError> {
+        Ok(part_one(&self.rotations, Counter::default()))
+    }
+
+    fn part_two(&self) -> Result<u64, SolveError> {
+        part_two(&self.rotations, Counter::default())
+    }
+}
+
+/// Why no rotations give the counts asked for
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SynthesisError {
+    TooManyLandings,
+    FewerHitsThanLandings,
+    AlwaysLands,
+    TooManyHits,
+}
+impl fmt::Display for SynthesisError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooManyLandings => write!(f, "each rotation lands on the target at most once"),
+            Self::FewerHitsThanLandings => {
+                write!(f, "every landing on the target also counts for part two")
+            }
+            Self::AlwaysLands => write!(f, "a dial of one position lands on the target every time"),
+            Self::TooManyHits => write!(f, "the passes through the target don't fit the rotations"),
+        }
+    }
+}
+impl std::error::Error for SynthesisError {}
+
+/// `length` rotations that take the dial from where `counter` points to `landings` stops on the
+/// target and `hits` passes through it, the part one and part two counts
+///
+/// The rotations that land come last, going one pass to the target each, and the others step
+/// away from it without a pass. Full turns added to the first rotation make up the other passes.
+pub fn synthesize(
+    counter: &Counter,
+    length: usize,
+    landings: usize,
+    hits: usize,
+) -> Result<Vec<Rotation>, SynthesisError> {
+    if landings > length {
+        return Err(SynthesisError::TooManyLandings);
+    }
+    if hits < landings {
+        return Err(SynthesisError::FewerHitsThanLandings);
+    }
+    if counter.size == 1 && landings < length {
+        return Err(SynthesisError::AlwaysLands);
+    }
+    let size = u64::from(counter.size);
+    let target = u64::from(counter.target);
+    let mut position = u64::from(counter.val);
+    let mut rotations = Vec::with_capacity(length);
+    for idx in 0..length {
+        let rotation = if length - idx <= landings {
+            // A full turn when it is already there
+            let clicks = (target + size - position) % size;
+            Rotation::Right(if clicks == 0 { size } else { clicks })
+        } else if (position + 1) % size != target {
+            Rotation::Right(1)
+        } else if (position + size - 1) % size != target {
+            Rotation::Left(1)
+        } else {
+            // Both neighbours are the target on a dial of two
+            Rotation::Right(0)
+        };
+        position = match rotation {
+            Rotation::Left(v) => (position + size - v % size) % size,
+            Rotation::Right(v) => (position + v) % size,
+        };
+        rotations.push(rotation);
+    }
+
+    let extra = (hits - landings) as u64;
+    if extra > 0 {
+        let turns = extra.checked_mul(size).ok_or(SynthesisError::TooManyHits)?;
+        let first = rotations.first_mut().ok_or(SynthesisError::TooManyHits)?;
+        *first = match *first {
+            Rotation::Left(v) => v.checked_add(turns).map(Rotation::Left),
+            Rotation::Right(v) => v.checked_add(turns).map(Rotation::Right),
+        }
+        .ok_or(SynthesisError::TooManyHits)?;
+    }
+    Ok(rotations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Rotations of a well formed input
+    fn rotations(input: &str) -> Vec<Rotation> {
+        parse_input(input)
+            .unwrap()
+            .into_iter()
+            .map(|(_, rot)| rot)
+            .collect()
+    }
+
+    #[test]
+    fn test_one() {
+        let output = part_one(&rotations(EXAMPLE), Counter::default());
+
+        // TODO fill this out
+        assert_eq!(output, 3);
+    }
+
+    #[test]
+    fn test_two() {
+        let output = part_two(&rotations(EXAMPLE), Counter::default());
+
+        // TODO fill this out
+        assert_eq!(output, Ok(6));
+    }
+
+    #[test]
+    fn test_solver() {
+        let instructions = Instructions::parse(EXAMPLE).unwrap();
+        assert_eq!(
+            (instructions.part_one(), instructions.part_two()),
+            (Ok(3), Ok(6))
+        );
+    }
+
+    #[test]
+    fn test_both() {
+        assert_eq!(both(&rotations(EXAMPLE), Counter::default()), Ok((3, 6)));
+        let mut counter = Counter::default();
+        assert_eq!(counter.apply_all(&[]), (0, Some(0)));
+        assert_eq!(counter.apply_all(&[Rotation::Left(50)]), (1, Some(1)));
+        // Counts carry on from earlier rotations
+        assert_eq!(counter.apply_all(&[Rotation::Right(250)]), (1, Some(3)));
+        assert_eq!(counter.get_counter_pt_2(), Some(3));
+    }
+
+    #[test]
+    fn test_trace() {
+        let mut counter = Counter::default();
+        assert_eq!(
+            counter.rotate_traced(&Rotation::Left(68)),
+            Step {
+                position: 82,
+                hits: 1
+            }
+        );
+        assert_eq!(
+            counter.rotate_traced(&Rotation::Right(18)),
+            Step {
+                position: 0,
+                hits: 1
+            }
+        );
+        // A full turn from zero comes back to it
+        assert_eq!(
+            counter.rotate_traced(&Rotation::Left(100)),
+            Step {
+                position: 0,
+                hits: 1
+            }
+        );
+        assert_eq!(
+            (counter.get_counter_pt_1(), counter.get_counter_pt_2()),
+            (2, Some(3))
+        );
+
+        let input = parse_input(EXAMPLE).unwrap();
+        let lines: Vec<usize> = input.iter().map(|(line, _)| *line).collect();
+        let rotations: Vec<Rotation> = input.iter().map(|(_, rot)| *rot).collect();
+        let lines: Vec<String> = trace(&rotations, &lines, Counter::default())
+            .lines()
+            .map(String::from)
+            .collect();
+        assert_eq!(lines.len(), 10);
+        assert_eq!(lines[0], "line 1: L68 -> 82 (hits 1, totals 0 1)");
+        assert_eq!(lines[2], "line 3: R48 -> 0 (hits 1, totals 1 2)");
+        assert_eq!(lines[9], "line 10: L82 -> 32 (hits 1, totals 3 6)");
+    }
+
+    #[test]
+    fn test_parse() {
+        use Rotation::{Left, Right};
+        assert_eq!(
+            parse_input("L68, r5\n\n  l 3 R 10,,L0  \nR7").unwrap(),
+            vec![
+                (1, Left(68)),
+                (1, Right(5)),
+                (3, Left(3)),
+                (3, Right(10)),
+                (3, Left(0)),
+                (4, Right(7))
+            ]
+        );
+        assert_eq!(rotations("L68R5"), vec![Left(68), Right(5)]);
+        assert_eq!(" r 12 ".parse(), Ok(Right(12)));
+        assert_eq!(
+            "L-4".parse::<Rotation>(),
+            Err(InvalidRotation("L-4".to_string()))
+        );
+        assert!("".parse::<Rotation>().is_err());
+        assert!("L68 R5".parse::<Rotation>().is_err());
+        for (input, line, text) in [
+            ("L68\nX5", 2, "X5"),
+            ("L68, R", 1, "R"),
+            ("R+5", 1, "R+5"),
+            ("L1\nL2 L99999999999999999999", 2, "L99999999999999999999"),
+            ("L5x", 1, "x"),
+        ] {
+            assert_eq!(
+                parse_input(input),
+                Err(ParseError {
+                    line,
+                    rotation: InvalidRotation(text.to_string())
+                }),
+                "{input:?}"
+            );
+        }
+        assert_eq!(
+            parse_input("L5 ?").unwrap_err().to_string(),
+            "line 1: expected a rotation like L68, found \"?\""
+        );
+    }
+
+    #[test]
+    fn test_target() {
+        let rotations = rotations(EXAMPLE);
+        // From 50, the dial stops at 82, 52, 0, 95, 55, 0, 99, 0, 14, 32
+        let mut counter = Counter::default().with_target(52);
+        assert_eq!(counter.apply_all(&rotations), (1, Some(5)));
+        for (size, start, target) in [
+            (100, 50, 50),
+            (100, 50, 99),
+            (7, 3, 3),
+            (7, 3, 6),
+            (1, 0, 0),
+        ] {
+            assert_eq!(
+                Counter::new(size, start)
+                    .with_target(target)
+                    .apply_all(&rotations),
+                clicks(size, start, target, &rotations),
+                "{size} {start} {target}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_large_rotations() {
+        let mut counter = Counter::default();
+        assert_eq!(
+            counter.rotate_traced(&Rotation::Left(u64::MAX)),
+            Step {
+                position: 35,
+                hits: 184467440737095516
+            }
+        );
+        assert_eq!(
+            counter.rotate_traced(&Rotation::Right(u64::MAX)),
+            Step {
+                position: 50,
+                hits: 184467440737095516
+            }
+        );
+        // Each full turn past the first passes the target once more
+        for (size, start, v) in [(7, 3, 1_000_003), (100, 0, 1_000_000), (1, 0, 12345)] {
+            for rot in [Rotation::Left(v), Rotation::Right(v)] {
+                assert_eq!(
+                    Counter::new(size, start).apply_all(&[rot]),
+                    clicks(size, start, 0, &[rot]),
+                    "{size} {start} {rot}"
+                );
+            }
+        }
+        assert_eq!(
+            parse_input("R18446744073709551615").unwrap(),
+            vec![(1, Rotation::Right(u64::MAX))]
+        );
+
+        // Every click of a one position dial passes the target, so two turns this long overflow
+        let rotations = [Rotation::Right(u64::MAX), Rotation::Left(u64::MAX)];
+        assert_eq!(part_one(&rotations, Counter::new(1, 0)), 2);
+        assert_eq!(
+            part_two(&rotations, Counter::new(1, 0)),
+            Err(SolveError::new("the part two count overflows 64 bits"))
+        );
+        assert_eq!(part_two(&rotations[..1], Counter::new(1, 0)), Ok(u64::MAX));
+    }
+
+    #[test]
+    fn test_synthesize() {
+        let synthesized = synthesize(&Counter::default(), 10, 3, 6).unwrap();
+        assert_eq!(synthesized.len(), 10);
+        // Written out and read back as an input
+        let input: Vec<String> = synthesized.iter().map(ToString::to_string).collect();
+        let parsed = rotations(&input.join("\n"));
+        assert_eq!(both(&parsed, Counter::default()), Ok((3, 6)));
+
+        for (size, start, target) in [(1, 0, 0), (2, 0, 0), (2, 1, 0), (3, 2, 1), (100, 50, 0)] {
+            let counter = Counter::new(size, start).with_target(target);
+            for length in 0..5 {
+                for landings in 0..=length + 1 {
+                    for hits in 0..8 {
+                        let expected = if landings > length {
+                            Err(SynthesisError::TooManyLandings)
+                        } else if hits < landings {
+                            Err(SynthesisError::FewerHitsThanLandings)
+                        } else if size == 1 && landings < length {
+                            Err(SynthesisError::AlwaysLands)
+                        } else if length == 0 && hits > 0 {
+                            Err(SynthesisError::TooManyHits)
+                        } else {
+                            Ok((landings as u64, hits as u64))
+                        };
+                        let found = synthesize(&counter, length, landings, hits).map(|rotations| {
+                            assert_eq!(rotations.len(), length);
+                            both(&rotations, counter.clone()).unwrap()
+                        });
+                        assert_eq!(found, expected, "{size} {start} {target} {length}");
+                    }
+                }
+            }
+        }
+        assert_eq!(
+            synthesize(&Counter::default(), 1, 0, usize::MAX),
+            Err(SynthesisError::TooManyHits)
+        );
+    }
+
+    #[test]
+    fn test_euclid() {
+        assert_eq!((-20_i16).rem_euclid(100), 80);
+        assert_eq!((-120_i16).div_euclid(100), -2);
+    }
+
+    /// One click at a time, counting the landings on the target and every pass through it
+    fn clicks(size: u16, start: u16, target: u16, rotations: &[Rotation]) -> (u64, Option<u64>) {
+        let (mut val, mut landings, mut passes) = (start, 0, 0);
+        for rot in rotations {
+            let (v, step) = match rot {
+                Rotation::Left(v) => (*v, size - 1),
+                Rotation::Right(v) => (*v, 1),
+            };
+            for _ in 0..v {
+                val = ((val as u32 + step as u32) % size as u32) as u16;
+                passes += u64::from(val == target);
+            }
+            landings += u64::from(val == target);
+        }
+        (landings, Some(passes))
+    }
+
+    #[test]
+    fn test_dial_size() {
+        let rotations = rotations(EXAMPLE);
+        for (size, start) in [
+            (100, 50),
+            (1, 0),
+            (2, 1),
+            (7, 0),
+            (7, 3),
+            (13, 12),
+            (1000, 999),
+        ] {
+            assert_eq!(
+                Counter::new(size, start).apply_all(&rotations),
+                clicks(size, start, 0, &rotations),
+                "{size} {start}"
+            );
+        }
+        let mut large = Counter::new(u16::MAX, 1);
+        large.rotate(&Rotation::Left(u16::MAX as u64));
+        large.rotate(&Rotation::Right(u16::MAX as u64));
+        assert_eq!(
+            (large.get_counter_pt_1(), large.get_counter_pt_2()),
+            (0, Some(2))
+        );
+    }
+}
@@ -1,14 +1,61 @@
 //! Command line executable for running part one and part two
-use std::{fs::File, io::Read, time::Instant};
-
-use clap::Parser;
+use std::{
+    process::ExitCode,
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, bail};
+use aoc_common::{
+    cancel::TimeoutArgs,
+    input,
+    log::LogArgs,
+    output::{self, Format, OutputArgs, Record},
+    threads::ThreadArgs,
+};
+use clap::{CommandFactory, Parser, error::ErrorKind};
+use day_one::{
+    Counter, EXAMPLE, Rotation, both, parse_input, part_one, part_two, synthesize, trace,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Input file
+    /// Input file or quoted glob pattern, repeated for several files answered one by one and then
+    /// added up, or `-` for stdin (the default when it is piped in; not needed for `synthesize`)
     #[arg(short)]
-    input_file: String,
+    input_file: Vec<String>,
+
+    /// Run on the example from the puzzle text instead
+    #[arg(long, conflicts_with = "input_file")]
+    example: bool,
+
+    /// Number of positions on the dial
+    #[arg(long, default_value_t = 100, value_parser = clap::value_parser!(u16).range(1..))]
+    dial_size: u16,
+
+    /// Position the dial starts at
+    #[arg(long, default_value_t = 50)]
+    start: u16,
+
+    /// Position to count the landings on and passes through, instead of zero
+    #[arg(long, default_value_t = 0)]
+    target: u16,
+
+    /// Print where the dial stops after each rotation and how often it went by the target
+    #[arg(long)]
+    trace: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(flatten)]
+    log: LogArgs,
+
+    #[command(flatten)]
+    threads: ThreadArgs,
+
+    #[command(flatten)]
+    timeout: TimeoutArgs,
 
     #[command(subcommand)]
     part: Part,
@@ -18,146 +65,182 @@ struct Args {
 enum Part {
     Part1,
     Part2,
+    /// Both answers from a single pass, part one's first
+    Both,
+    /// Print rotations, one per line, giving the answers asked for on this dial
+    Synthesize {
+        /// Number of rotations
+        #[arg(long)]
+        length: usize,
+        #[arg(long)]
+        part_one: usize,
+        #[arg(long)]
+        part_two: usize,
+    },
 }
 
-fn main() {
-    let args = Args::parse();
-
-    let mut s = String::new();
-    let mut file = File::open(args.input_file).expect("Cannot find file");
-    let _ = file.read_to_string(&mut s).unwrap();
-
-    let start = Instant::now();
-    let answer = match args.part {
-        Part::Part1 => part_one(&s),
-        Part::Part2 => part_two(&s),
-    };
-
-    println!("{:?}", answer);
-    println!("Completed in {:?}", start.elapsed());
+fn main() -> ExitCode {
+    output::exit(try_main())
 }
 
-/// Rotation
-#[derive(Debug)]
-pub enum Rotation {
-    Left(u16),
-    Right(u16),
-}
-impl Rotation {
-    pub fn from_line(line: &str) -> Self {
-        // Unwrap because too lazy to check -- feel free to crash
-        let rot_value: u16 = line.split_at(1).1.parse().expect("Invalid");
-        match line.chars().next().expect("Invalid line -- no characters") {
-            'L' => Rotation::Left(rot_value),
-            'R' => Rotation::Right(rot_value),
-            _ => panic!("Not valid start to line"),
-        }
+fn try_main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    args.log.init();
+    args.threads.init()?;
+    args.timeout.init();
+
+    let mut files = input_files(&args.input_file).map_err(anyhow::Error::msg)?;
+    if args.example {
+        files.push(input::EXAMPLE.to_owned());
+    } else if files.is_empty() && input::stdin_is_piped() {
+        files.push(input::STDIN.to_owned());
     }
-}
 
-/// Counter
-#[derive(Debug)]
-pub struct Counter {
-    val: u8,
-    counter_pt_1: usize,
-    counter_pt_2: usize,
-}
-impl Default for Counter {
-    fn default() -> Self {
-        Self {
-            val: 50,
-            counter_pt_1: 0,
-            counter_pt_2: 0,
+    for (name, position) in [("start", args.start), ("target", args.target)] {
+        if position >= args.dial_size {
+            bail!(
+                "the {name} is {position} but the dial only goes up to {}",
+                args.dial_size - 1
+            );
         }
     }
-}
-impl Counter {
-    pub fn rotate(&mut self, rot: &Rotation) {
-        let (int_val, v) = match rot {
-            Rotation::Left(v) => (self.val as i16 - *v as i16, *v as i16),
-            Rotation::Right(v) => (self.val as i16 + *v as i16, *v as i16),
-        };
-        let diff = if self.val == 0 {
-            100
-        } else {
-            match rot {
-                Rotation::Left(_) => self.val as i16,
-                Rotation::Right(_) => 100 - self.val as i16,
-            }
-        };
-        if v >= diff {
-            self.counter_pt_2 += ((v - diff) / 100) as usize + 1;
-        }
-        self.val = int_val.rem_euclid(100_i16) as u8;
-        if self.val == 0 {
-            self.counter_pt_1 += 1;
+    let counter = Counter::new(args.dial_size, args.start).with_target(args.target);
+
+    if let Part::Synthesize {
+        length,
+        part_one,
+        part_two,
+    } = args.part
+    {
+        let rotations = synthesize(&counter, length, part_one, part_two)
+            .context("cannot synthesize the rotations")?;
+        for rot in rotations {
+            println!("{rot}");
         }
+        return Ok(());
     }
-
-    pub fn get_counter_pt_1(&self) -> usize {
-        self.counter_pt_1
+    if files.is_empty() {
+        Args::command()
+            .error(
+                ErrorKind::MissingRequiredArgument,
+                "-i <INPUT_FILE> is required",
+            )
+            .exit();
     }
 
-    pub fn get_counter_pt_2(&self) -> usize {
-        self.counter_pt_2
-    }
-}
+    let mut parsing = Duration::ZERO;
+    let mut elapsed = Duration::ZERO;
+    let mut answers = Vec::with_capacity(files.len());
+    for input_file in &files {
+        let s = if args.example {
+            EXAMPLE.to_owned()
+        } else {
+            input::read_to_string(input_file)?
+        };
 
-fn part_one(input: &str) -> usize {
-    let rotations: Vec<_> = input.lines().map(Rotation::from_line).collect();
-    let mut counter = Counter::default();
-    for rot in rotations {
-        counter.rotate(&rot);
-    }
-    counter.get_counter_pt_1()
-}
+        let start = Instant::now();
+        let (lines, rotations): (Vec<usize>, Vec<Rotation>) = parse_input(&s)
+            .with_context(|| input_file.clone())?
+            .into_iter()
+            .unzip();
+        parsing += start.elapsed();
+        if args.trace {
+            if files.len() > 1 {
+                println!("{input_file}:");
+            }
+            print!("{}", trace(&rotations, &lines, counter.clone()));
+        }
 
-fn part_two(input: &str) -> usize {
-    let rotations: Vec<_> = input.lines().map(Rotation::from_line).collect();
-    let mut counter = Counter::default();
-    for rot in rotations {
-        counter.rotate(&rot);
+        let start = Instant::now();
+        let answer = match args.part {
+            Part::Part1 => vec![part_one(&rotations, counter.clone())],
+            Part::Part2 => {
+                vec![part_two(&rotations, counter.clone()).with_context(|| input_file.clone())?]
+            }
+            Part::Both => {
+                let (one, two) =
+                    both(&rotations, counter.clone()).with_context(|| input_file.clone())?;
+                vec![one, two]
+            }
+            Part::Synthesize { .. } => unreachable!("Handled before reading input"),
+        };
+        elapsed += start.elapsed();
+        answers.push(answer);
     }
-    counter.get_counter_pt_2()
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    /// Function to modify for input to test
-    fn input_one() -> &'static str {
-        "L68
-L30
-R48
-L5
-R60
-L55
-L1
-L99
-R14
-L82"
+    if args.output.output == Format::Json {
+        // Both parts come out of a single pass, so they share its timings
+        let parts: &[u8] = match args.part {
+            Part::Part1 => &[1],
+            Part::Part2 => &[2],
+            _ => &[1, 2],
+        };
+        let total = add_up(&answers)?;
+        for (&part, answer) in parts.iter().zip(total) {
+            args.output.print(&Record {
+                day: 1,
+                part: part.into(),
+                answer: answer.to_string(),
+                parse: parsing,
+                solve: elapsed,
+            });
+        }
+        return Ok(());
     }
-
-    #[test]
-    fn test_one() {
-        let output = part_one(input_one());
-
-        // TODO fill this out
-        assert_eq!(output, 3);
+    if let [answer] = &answers[..] {
+        for value in answer {
+            println!("{value}");
+        }
+    } else {
+        for (input_file, answer) in files.iter().zip(&answers) {
+            println!("{input_file}: {}", join(answer));
+        }
+        println!("total: {}", join(&add_up(&answers)?));
     }
+    println!("{}", output::footer(elapsed));
 
-    #[test]
-    fn test_two() {
-        let output = part_two(input_one());
+    Ok(())
+}
 
-        // TODO fill this out
-        assert_eq!(output, 6);
+/// Each part's answers summed over the input files
+fn add_up(answers: &[Vec<u64>]) -> anyhow::Result<Vec<u64>> {
+    let mut total = vec![0_u64; answers[0].len()];
+    for answer in answers {
+        for (sum, value) in total.iter_mut().zip(answer) {
+            *sum = sum
+                .checked_add(*value)
+                .context("the total over the input files overflows 64 bits")?;
+        }
     }
+    Ok(total)
+}
 
-    #[test]
-    fn test_euclid() {
-        assert_eq!((-20_i16).rem_euclid(100), 80);
-        assert_eq!((-120_i16).div_euclid(100), -2);
+/// Files named on the command line in order, expanding glob patterns the shell left alone
+fn input_files(patterns: &[String]) -> Result<Vec<String>, String> {
+    let mut files = Vec::new();
+    for pattern in patterns {
+        if !pattern.contains(['*', '?', '[']) {
+            files.push(pattern.clone());
+            continue;
+        }
+        let matches = glob::glob(pattern).map_err(|e| format!("{pattern}: {e}"))?;
+        let start = files.len();
+        for path in matches {
+            let path = path.map_err(|e| e.to_string())?;
+            files.push(path.display().to_string());
+        }
+        if files.len() == start {
+            return Err(format!("{pattern}: no input files match"));
+        }
     }
+    Ok(files)
+}
+
+/// Answers on one line
+fn join(values: &[u64]) -> String {
+    values
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join(" ")
 }
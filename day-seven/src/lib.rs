@@ -0,0 +1,709 @@
+//! Tachyon manifold parsing and beam propagation for part one and part two
+use std::fmt;
+
+use aoc_common::solver::{SolveError, Solver};
+use rand::{Rng, SeedableRng, rngs::StdRng};
+
+/// Error from parsing a manifold, with 1-indexed rows and columns
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    Empty,
+    InvalidEntry {
+        row: usize,
+        col: usize,
+        c: char,
+    },
+    RaggedRow {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    MissingStart,
+    /// A second start, after the first at `first`
+    DuplicateStart {
+        row: usize,
+        col: usize,
+        first: (usize, usize),
+    },
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "manifold is empty"),
+            Self::InvalidEntry { row, col, c } => {
+                write!(f, "row {row}, column {col}: not a valid entry {c:?}")
+            }
+            Self::RaggedRow {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {row}: expected {expected} columns like the first row, found {found}"
+            ),
+            Self::MissingStart => write!(f, "manifold has no start 'S'"),
+            Self::DuplicateStart {
+                row,
+                col,
+                first: (first_row, first_col),
+            } => write!(
+                f,
+                "row {row}, column {col}: second start 'S', the first is at row {first_row}, \
+                 column {first_col}"
+            ),
+        }
+    }
+}
+impl std::error::Error for ParseError {}
+
+/// Generate a random manifold in the same layout as the puzzle input
+///
+/// The start is centered on the first row and splitters only appear on every other row, each cell
+/// of those rows being a splitter with probability `density`, which must be from 0 to 1. The same
+/// seed always produces the same manifold.
+pub fn generate_manifold(width: usize, height: usize, density: f64, seed: u64) -> String {
+    let mut rng = StdRng::seed_from_u64(seed);
+    let mut s = String::with_capacity(height * (width + 1));
+    for idx_r in 0..height {
+        for idx_c in 0..width {
+            let c = if idx_r == 0 {
+                if idx_c == width / 2 { 'S' } else { '.' }
+            } else if idx_r % 2 == 0 && rng.random_bool(density) {
+                '^'
+            } else {
+                '.'
+            };
+            s.push(c);
+        }
+        s.push('\n');
+    }
+    s
+}
+
+// TODO -- Update this with the return type
+pub type ReturnType = usize;
+
+/// Type of Spot
+#[derive(Debug, Clone)]
+pub enum TachyonEntry {
+    Start,
+    Splitter,
+    Open,
+    /// `/` -- deflects the beam down and to the left
+    MirrorLeft,
+    /// `\` -- deflects the beam down and to the right
+    MirrorRight,
+    /// `X` -- the beam stops here
+    Absorber,
+}
+impl TachyonEntry {
+    pub fn from_char(c: &char) -> Option<Self> {
+        match c {
+            '.' => Some(Self::Open),
+            '^' => Some(Self::Splitter),
+            'S' => Some(Self::Start),
+            '/' => Some(Self::MirrorLeft),
+            '\\' => Some(Self::MirrorRight),
+            'X' => Some(Self::Absorber),
+            _ => None,
+        }
+    }
+
+    pub fn to_char(&self) -> char {
+        match self {
+            Self::Start => 'S',
+            Self::Splitter => '^',
+            Self::Open => '.',
+            Self::MirrorLeft => '/',
+            Self::MirrorRight => '\\',
+            Self::Absorber => 'X',
+        }
+    }
+
+    /// Column offsets of the beams leaving this tile when a beam enters it from above
+    ///
+    /// Every beam moves down one row as it leaves, so this is all a tile needs to describe. Beams
+    /// only leave the start, but one entering it would pass straight through like open space.
+    pub fn propagate(&self) -> &'static [isize] {
+        match self {
+            Self::Splitter => &[-1, 1],
+            Self::Start | Self::Open => &[0],
+            Self::MirrorLeft => &[-1],
+            Self::MirrorRight => &[1],
+            Self::Absorber => &[],
+        }
+    }
+}
+
+/// Tachyon Manifold
+#[derive(Debug, Clone)]
+pub struct TachyonManifold {
+    inner: Vec<Vec<TachyonEntry>>,
+    n_rows: usize,
+    n_cols: usize,
+    /// 0-indexed row and column of the only start
+    start: (usize, usize),
+    boundary: BoundaryPolicy,
+}
+impl TachyonManifold {
+    pub fn new(input: &str) -> Result<Self, ParseError> {
+        let mut inner: Vec<Vec<_>> = Vec::new();
+        let mut start = None;
+        for (idx_r, line) in input.lines().enumerate() {
+            let row = line
+                .chars()
+                .enumerate()
+                .map(|(idx_c, c)| {
+                    TachyonEntry::from_char(&c).ok_or(ParseError::InvalidEntry {
+                        row: idx_r + 1,
+                        col: idx_c + 1,
+                        c,
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+            if let Some(first) = inner.first()
+                && first.len() != row.len()
+            {
+                return Err(ParseError::RaggedRow {
+                    row: idx_r + 1,
+                    expected: first.len(),
+                    found: row.len(),
+                });
+            }
+            for (idx_c, _) in row
+                .iter()
+                .enumerate()
+                .filter(|(_, entry)| matches!(entry, TachyonEntry::Start))
+            {
+                if let Some((first_r, first_c)) = start {
+                    return Err(ParseError::DuplicateStart {
+                        row: idx_r + 1,
+                        col: idx_c + 1,
+                        first: (first_r + 1, first_c + 1),
+                    });
+                }
+                start = Some((idx_r, idx_c));
+            }
+            inner.push(row);
+        }
+        if inner.is_empty() {
+            return Err(ParseError::Empty);
+        }
+        let start = start.ok_or(ParseError::MissingStart)?;
+        Ok(Self {
+            n_rows: inner.len(),
+            n_cols: inner[0].len(),
+            start,
+            inner,
+            boundary: BoundaryPolicy::default(),
+        })
+    }
+
+    pub fn with_boundary(mut self, boundary: BoundaryPolicy) -> Self {
+        self.boundary = boundary;
+        self
+    }
+
+    /// Whether the tile below the beam splits it, even if some of its children are lost
+    pub fn is_splitting(&self, beam: &TachyonBeam) -> bool {
+        self.query_location(beam.pos_r, beam.pos_c, 1, 0)
+            .is_some_and(|entry| entry.propagate().len() > 1)
+    }
+
+    /// Column a beam ends up in after moving `offset_c` from `idx_c`, per the boundary policy
+    pub fn resolve_column(&self, idx_c: usize, offset_c: isize) -> Option<usize> {
+        let n_cols = self.n_cols as isize;
+        let t = idx_c as isize + offset_c;
+        let t = match self.boundary {
+            BoundaryPolicy::Clip => t,
+            BoundaryPolicy::Wrap => t.rem_euclid(n_cols),
+            BoundaryPolicy::Reflect if t < 0 => -t,
+            BoundaryPolicy::Reflect if t >= n_cols => 2 * (n_cols - 1) - t,
+            BoundaryPolicy::Reflect => t,
+        };
+        // Reflecting can still land outside a manifold that is only one column wide
+        (0..n_cols).contains(&t).then_some(t as usize)
+    }
+
+    pub fn get_start(&self) -> (usize, usize) {
+        self.start
+    }
+
+    pub fn query_location(
+        &self,
+        idx_r: usize,
+        idx_c: usize,
+        offset_r: isize,
+        offset_c: isize,
+    ) -> Option<&TachyonEntry> {
+        let new_r = {
+            let t = idx_r as isize + offset_r;
+            if t < 0 {
+                return None;
+            } else {
+                t as usize
+            }
+        };
+        if new_r >= self.n_rows {
+            return None;
+        }
+
+        let new_c = {
+            let t = idx_c as isize + offset_c;
+            if t < 0 {
+                return None;
+            } else {
+                t as usize
+            }
+        };
+        if new_c >= self.n_cols {
+            return None;
+        }
+        Some(&self.inner[new_r][new_c])
+    }
+
+    /// Draw the manifold, marking covered open cells with `|`
+    pub fn render(&self, coverage: &[Vec<bool>]) -> String {
+        let mut s = String::with_capacity(self.n_rows * (self.n_cols + 1));
+        for (row, covered_row) in self.inner.iter().zip(coverage) {
+            for (entry, covered) in row.iter().zip(covered_row) {
+                s.push(match entry {
+                    TachyonEntry::Open if *covered => '|',
+                    _ => entry.to_char(),
+                });
+            }
+            s.push('\n');
+        }
+        s
+    }
+}
+
+/// What happens to a beam that would leave the side of the manifold
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum BoundaryPolicy {
+    /// The beam is lost
+    #[default]
+    Clip,
+    /// The beam reappears on the opposite side
+    Wrap,
+    /// The beam bounces back off the edge column, so -1 becomes 1
+    Reflect,
+}
+
+/// Tachyon Beam
+#[derive(Debug, Clone, Hash, Eq, PartialEq)]
+pub struct TachyonBeam {
+    pub pos_r: usize,
+    pub pos_c: usize,
+}
+impl TachyonBeam {
+    /// Move the beam down a row, letting the tile it enters decide where it goes
+    ///
+    /// Children that would leave the side of the manifold are handled by its boundary policy
+    pub fn evolve(self, manifold: &TachyonManifold) -> Vec<Self> {
+        self.children(manifold).collect()
+    }
+
+    /// Same as `evolve`, without collecting the children
+    pub fn children<'a>(&self, manifold: &'a TachyonManifold) -> impl Iterator<Item = Self> + 'a {
+        let (pos_r, pos_c) = (self.pos_r, self.pos_c);
+        let offsets = manifold
+            .query_location(pos_r, pos_c, 1, 0)
+            .map_or(&[][..], |entry| entry.propagate());
+        offsets.iter().filter_map(move |offset_c| {
+            manifold
+                .resolve_column(pos_c, *offset_c)
+                .map(|pos_c| TachyonBeam {
+                    pos_r: pos_r + 1,
+                    pos_c,
+                })
+        })
+    }
+}
+
+/// Totals from propagating the beams row by row
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Frontier {
+    /// Number of occupied cells where a beam split
+    pub splits: usize,
+    /// Number of timelines that ended
+    pub timelines: usize,
+}
+
+/// Counter
+#[derive(Debug, Clone)]
+pub struct Manager {
+    manifold: TachyonManifold,
+}
+impl Manager {
+    pub fn new(manifold: TachyonManifold) -> Self {
+        Self { manifold }
+    }
+
+    pub fn run_p1(&self) -> usize {
+        self.propagate_frontier().splits
+    }
+
+    /// Advance a row of per-column timeline counts from the start to the bottom of the manifold
+    ///
+    /// A column is occupied when its count is non-zero, so splits are counted once per occupied
+    /// splitter no matter how many timelines reach it, while the counts themselves give the number
+    /// of timelines.
+    pub fn propagate_frontier(&self) -> Frontier {
+        self.propagate_frontier_with(|_, _| {})
+    }
+
+    /// Same as `propagate_frontier`, calling `on_row` with each row's counts before advancing it
+    pub fn propagate_frontier_with(&self, mut on_row: impl FnMut(usize, &[usize])) -> Frontier {
+        let (start_r, start_c) = self.manifold.get_start();
+        let mut frontier = Frontier {
+            splits: 0,
+            timelines: 0,
+        };
+        let mut counts = vec![0; self.manifold.n_cols];
+        counts[start_c] = 1;
+        for pos_r in start_r..self.manifold.n_rows {
+            on_row(pos_r, &counts);
+            let mut next_counts = vec![0; self.manifold.n_cols];
+            for (pos_c, count) in counts.iter().enumerate().filter(|(_, count)| **count > 0) {
+                let beam = TachyonBeam { pos_r, pos_c };
+                if self.manifold.is_splitting(&beam) {
+                    // We split, increment the pt1_ctr
+                    frontier.splits += 1;
+                }
+                let mut n_children = 0;
+                for child in beam.children(&self.manifold) {
+                    next_counts[child.pos_c] += count;
+                    n_children += 1;
+                }
+                if n_children == 0 {
+                    frontier.timelines += count;
+                }
+            }
+            counts = next_counts;
+        }
+        frontier
+    }
+
+    /// Which cells any beam passes through, starting from the start
+    pub fn coverage_map(&self) -> Vec<Vec<bool>> {
+        let mut coverage = vec![vec![false; self.manifold.n_cols]; self.manifold.n_rows];
+        let (pos_r, pos_c) = self.manifold.get_start();
+        let mut beams = vec![TachyonBeam { pos_r, pos_c }];
+        while let Some(beam) = beams.pop() {
+            if coverage[beam.pos_r][beam.pos_c] {
+                continue;
+            }
+            coverage[beam.pos_r][beam.pos_c] = true;
+            beams.extend(beam.evolve(&self.manifold));
+        }
+        coverage
+    }
+
+    /// The manifold with the beams drawn over it
+    pub fn render_coverage(&self) -> String {
+        self.manifold.render(&self.coverage_map())
+    }
+
+    /// The rendered manifold after each row of propagation, with beams drawn down to that row
+    pub fn animation_frames(&self) -> Vec<String> {
+        let mut coverage = vec![vec![false; self.manifold.n_cols]; self.manifold.n_rows];
+        let mut frames = Vec::new();
+        self.propagate_frontier_with(|pos_r, counts| {
+            for (covered, count) in coverage[pos_r].iter_mut().zip(counts) {
+                *covered = *count > 0;
+            }
+            frames.push(self.manifold.render(&coverage));
+        });
+        frames
+    }
+
+    /// Number of timelines reaching each cell from the start, i.e. every row of the frontier
+    pub fn count_timelines_to(&self) -> Vec<Vec<usize>> {
+        let mut counts = vec![vec![0; self.manifold.n_cols]; self.manifold.n_rows];
+        self.propagate_frontier_with(|pos_r, row_counts| {
+            counts[pos_r].copy_from_slice(row_counts);
+        });
+        counts
+    }
+
+    /// Number of distinct start-to-bottom timelines passing through a 0-indexed cell, or `None` if
+    /// the cell is outside the manifold
+    ///
+    /// Every timeline through the cell is a path into it followed by a path out of it, so this is
+    /// the product of the forward and backward counts. Each call propagates the whole manifold, so
+    /// use `timelines_through_map` to look up more than one cell.
+    pub fn count_timelines_through(&self, row: usize, col: usize) -> Option<usize> {
+        if row >= self.manifold.n_rows || col >= self.manifold.n_cols {
+            return None;
+        }
+        let (start_r, _) = self.manifold.get_start();
+        let forward = self.count_timelines_to();
+        let backward = self.count_timelines_from(start_r);
+        Some(forward[row][col] * backward[row][col])
+    }
+
+    /// `count_timelines_through` for every cell at once
+    pub fn timelines_through_map(&self) -> Vec<Vec<usize>> {
+        let (start_r, _) = self.manifold.get_start();
+        let forward = self.count_timelines_to();
+        let backward = self.count_timelines_from(start_r);
+        forward
+            .iter()
+            .zip(backward.iter())
+            .map(|(f_row, b_row)| f_row.iter().zip(b_row).map(|(f, b)| f * b).collect())
+            .collect()
+    }
+
+    pub fn run_p2(&self) -> usize {
+        let (pos_r, pos_c) = self.manifold.get_start();
+        self.count_timelines_from(pos_r)[pos_r][pos_c]
+    }
+
+    /// Number of timelines leaving each cell, for every row from `start_row` down
+    ///
+    /// A beam only ever moves down a row, so a cell's count is the sum of its children's counts on
+    /// the row below (or 1 if the beam exits the manifold). Filling the rows bottom-up makes this a
+    /// single O(rows x cols) pass. Rows above `start_row` are left at zero.
+    pub fn count_timelines_from(&self, start_row: usize) -> Vec<Vec<usize>> {
+        let mut counts = vec![vec![0; self.manifold.n_cols]; self.manifold.n_rows];
+        for pos_r in (start_row..self.manifold.n_rows).rev() {
+            for pos_c in 0..self.manifold.n_cols {
+                let evolved_beams = TachyonBeam { pos_r, pos_c }.evolve(&self.manifold);
+                counts[pos_r][pos_c] = if evolved_beams.is_empty() {
+                    1
+                } else {
+                    evolved_beams
+                        .iter()
+                        .map(|beam| counts[beam.pos_r][beam.pos_c])
+                        .sum()
+                };
+            }
+        }
+        counts
+    }
+}
+
+/// The example from the puzzle text, run with `--example`
+pub const EXAMPLE: &str = include_str!("../examples/example.txt");
+
+impl Solver for Manager {
+    type Output = ReturnType;
+    type Error = ParseError;
+    const EXAMPLE: &'static str = EXAMPLE;
+
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        TachyonManifold::new(input).map(Self::new)
+    }
+
+    fn part_one(&self) -> Result<ReturnType, SolveError> {
+        Ok(self.run_p1())
+    }
+
+    fn part_two(&self) -> Result<ReturnType, SolveError> {
+        Ok(self.run_p2())
+    }
+}
+
+/// Logic for part_one
+pub fn part_one(input: Manager) -> ReturnType {
+    input.run_p1()
+}
+
+/// Logic for part two
+pub fn part_two(input: Manager) -> ReturnType {
+    input.run_p2()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Function to split above into different inputs
+    fn parse_input_test(input: &str) -> Manager {
+        let manifold = TachyonManifold::new(input).unwrap();
+        Manager::new(manifold)
+    }
+
+    #[test]
+    fn test_one() {
+        let input = parse_input_test(EXAMPLE);
+        let output = part_one(input);
+
+        // TODO fill this out
+        assert_eq!(output, 21);
+    }
+
+    #[test]
+    fn test_render() {
+        let input = parse_input_test(EXAMPLE);
+        let rendered = input.render_coverage();
+        let lines: Vec<_> = rendered.lines().collect();
+        assert_eq!(lines[0], ".......S.......");
+        assert_eq!(lines[1], ".......|.......");
+        assert_eq!(lines[2], "......|^|......");
+        assert_eq!(lines[15], "|.|.|.|.|.|||.|");
+    }
+
+    #[test]
+    fn test_mirrors_and_absorbers() {
+        let manifold = "..S..
+..\\..
+...^.
+..X..
+.....";
+        let input = parse_input_test(manifold);
+        assert_eq!(
+            input.render_coverage(),
+            "..S..
+..\\|.
+..|^|
+..X.|
+....|
+"
+        );
+        assert_eq!(part_one(input.clone()), 1);
+        // The absorbed beam still ends a timeline
+        assert_eq!(part_two(input), 2);
+    }
+
+    #[test]
+    fn test_frontier() {
+        let input = parse_input_test(EXAMPLE);
+        assert_eq!(
+            input.propagate_frontier(),
+            Frontier {
+                splits: 21,
+                timelines: 40
+            }
+        );
+    }
+
+    #[test]
+    fn test_animation_frames() {
+        let input = parse_input_test(EXAMPLE);
+        let frames = input.animation_frames();
+        assert_eq!(frames.len(), 16);
+        assert!(!frames[0].contains('|'));
+        assert_eq!(frames[1].lines().nth(1), Some(".......|......."));
+        assert_eq!(frames[1].lines().nth(2), Some(".......^......."));
+        assert_eq!(frames.last(), Some(&input.render_coverage()));
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(TachyonManifold::new("").unwrap_err(), ParseError::Empty);
+        assert_eq!(
+            TachyonManifold::new("..S..\n..#..").unwrap_err(),
+            ParseError::InvalidEntry {
+                row: 2,
+                col: 3,
+                c: '#'
+            }
+        );
+        assert_eq!(
+            TachyonManifold::new("..S..\n...\n.....").unwrap_err(),
+            ParseError::RaggedRow {
+                row: 2,
+                expected: 5,
+                found: 3
+            }
+        );
+        assert_eq!(
+            TachyonManifold::new(".....\n.....").unwrap_err(),
+            ParseError::MissingStart
+        );
+        let duplicate = TachyonManifold::new("..S..\n.....\n.S...").unwrap_err();
+        assert_eq!(
+            duplicate,
+            ParseError::DuplicateStart {
+                row: 3,
+                col: 2,
+                first: (1, 3)
+            }
+        );
+        assert_eq!(
+            duplicate.to_string(),
+            "row 3, column 2: second start 'S', the first is at row 1, column 3"
+        );
+    }
+
+    #[test]
+    fn test_boundary_policies() {
+        let manifold = "S..
+^..
+...";
+        let run = |boundary| {
+            let manifold = TachyonManifold::new(manifold)
+                .unwrap()
+                .with_boundary(boundary);
+            let input = Manager::new(manifold);
+            (
+                input.propagate_frontier().splits,
+                input.clone().run_p2(),
+                input.render_coverage(),
+            )
+        };
+        // The right child survives even though the left one is lost
+        assert_eq!(
+            run(BoundaryPolicy::Clip),
+            (1, 1, "S..\n^|.\n.|.\n".to_string())
+        );
+        assert_eq!(
+            run(BoundaryPolicy::Wrap),
+            (1, 2, "S..\n^||\n.||\n".to_string())
+        );
+        // Both children land in the same column
+        assert_eq!(
+            run(BoundaryPolicy::Reflect),
+            (1, 2, "S..\n^|.\n.|.\n".to_string())
+        );
+    }
+
+    #[test]
+    fn test_timelines_through() {
+        let input = parse_input_test(EXAMPLE);
+        // Every timeline passes through the start and the first splitter's row
+        assert_eq!(input.count_timelines_through(0, 7), Some(40));
+        let map = input.timelines_through_map();
+        assert_eq!(map[1][7], 40);
+        assert_eq!(map[0][0], 0);
+
+        // The two sides of the first split share the timelines between them
+        assert_eq!(map[2][6] + map[2][8], 40);
+
+        // Every timeline leaves through exactly one cell of the bottom row
+        assert_eq!(map.last().unwrap().iter().sum::<usize>(), 40);
+
+        assert_eq!(input.count_timelines_through(16, 0), None);
+        assert_eq!(input.count_timelines_through(0, 15), None);
+    }
+
+    #[test]
+    fn test_generate_manifold() {
+        let generated = generate_manifold(15, 16, 0.5, 7);
+        assert_eq!(generated, generate_manifold(15, 16, 0.5, 7));
+        let manifold = TachyonManifold::new(&generated).unwrap();
+        assert_eq!((manifold.n_rows, manifold.n_cols), (16, 15));
+        assert_eq!(manifold.get_start(), (0, 7));
+
+        // Without splitters there is only ever one timeline
+        let input = parse_input_test(&generate_manifold(15, 16, 0.0, 7));
+        assert_eq!(part_two(input), 1);
+    }
+
+    #[test]
+    fn test_two() {
+        let input = parse_input_test(EXAMPLE);
+        let output = part_two(input);
+
+        // TODO fill this out
+        assert_eq!(output, 40);
+    }
+
+    #[test]
+    fn test_solver() {
+        let manager = Manager::parse(EXAMPLE).unwrap();
+        assert_eq!((manager.part_one(), manager.part_two()), (Ok(21), Ok(40)));
+    }
+}
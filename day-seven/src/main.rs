@@ -1,320 +1,167 @@
 //! Command line executable for running part one and part two
-use std::{
-    collections::{HashMap, HashSet},
-    fs::File,
-    io::{BufReader, Read},
-    time::Instant,
+use std::{path::Path, process::ExitCode, time::Instant};
+
+use anyhow::Context;
+use aoc_common::{
+    cancel::TimeoutArgs,
+    input::InputArgs,
+    log::LogArgs,
+    output::{self, OutputArgs, PartName, Record},
+    threads::ThreadArgs,
 };
-
 use clap::Parser;
+use day_seven::{
+    BoundaryPolicy, EXAMPLE, Manager, ParseError, ReturnType, TachyonManifold, generate_manifold,
+    part_one, part_two,
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Input file
-    #[arg(short)]
-    input_file: String,
+    // Not needed for `gen`
+    #[command(flatten)]
+    input: InputArgs,
 
-    #[command(subcommand)]
-    part: Part,
-}
+    /// Print the manifold with every cell a beam passes through marked
+    #[arg(long)]
+    render: bool,
 
-#[derive(clap::Subcommand, Debug)]
-enum Part {
-    Part1,
-    Part2,
-}
+    /// What happens to beams that would leave the sides of the manifold
+    #[arg(long, value_enum, default_value_t)]
+    boundary: BoundaryPolicy,
 
-fn main() {
-    let args = Args::parse();
+    #[command(flatten)]
+    output: OutputArgs,
 
-    let file = BufReader::new(File::open(args.input_file).expect("Cannot find file"));
+    #[command(flatten)]
+    log: LogArgs,
 
-    let start = Instant::now();
-    let answer = match args.part {
-        Part::Part1 => part_one(file),
-        Part::Part2 => part_two(file),
-    };
+    #[command(flatten)]
+    threads: ThreadArgs,
 
-    println!("{:?}", answer);
-    println!("Completed in {:?}", start.elapsed());
-}
+    #[command(flatten)]
+    timeout: TimeoutArgs,
 
-fn part_one(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file);
-    part_one_internal(input)
-}
-
-fn part_two(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file);
-    part_two_internal(input)
-}
-
-fn parse_input(mut file: BufReader<File>) -> Manager {
-    let mut s = String::new();
-    file.read_to_string(&mut s).unwrap();
-    let manifold = TachyonManifold::new(&s);
-    Manager::new(manifold)
+    #[command(subcommand)]
+    part: Part,
 }
 
-// TODO -- Update this with the return type
-type ReturnType = usize;
-
-/// Type of Spot
-#[derive(Debug, Clone)]
-enum TachyonEntry {
-    Start,
-    Splitter,
-    Open,
-}
-impl TachyonEntry {
-    pub fn from_char(c: &char) -> Self {
-        match c {
-            '.' => Self::Open,
-            '^' => Self::Splitter,
-            'S' => Self::Start,
-            _ => panic!("Not a valid entry"),
+#[derive(clap::Subcommand, Debug)]
+enum Part {
+    Part1,
+    Part2,
+    /// Write one text frame per row of propagation, showing the beams so far
+    Animate {
+        /// Directory to write `frame_XXXX.txt` files into
+        output_dir: String,
+    },
+    /// Count the timelines passing through a cell (0-indexed)
+    Through {
+        row: usize,
+        col: usize,
+    },
+    /// Print a random manifold in the puzzle format
+    Gen {
+        #[arg(long, default_value_t = 141)]
+        width: usize,
+        #[arg(long, default_value_t = 142)]
+        height: usize,
+        /// Chance of each cell on a splitter row being a splitter, from 0 to 1
+        #[arg(long, default_value_t = 0.3, value_parser = probability)]
+        density: f64,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+}
+impl Part {
+    fn name(&self) -> PartName {
+        match self {
+            Self::Part1 => 1.into(),
+            Self::Part2 => 2.into(),
+            Self::Animate { .. } => "animate".into(),
+            Self::Through { .. } => "through".into(),
+            Self::Gen { .. } => "gen".into(),
         }
     }
 }
 
-/// Tachyon Manifold
-#[derive(Debug, Clone)]
-struct TachyonManifold {
-    inner: Vec<Vec<TachyonEntry>>,
-    n_rows: usize,
-    n_cols: usize,
+fn main() -> ExitCode {
+    output::exit(try_main())
 }
-impl TachyonManifold {
-    pub fn new(input: &str) -> Self {
-        let inner: Vec<Vec<_>> = input
-            .lines()
-            .map(|line| line.chars().map(|c| TachyonEntry::from_char(&c)).collect())
-            .collect();
-        Self {
-            n_rows: inner.len(),
-            n_cols: inner[0].len(),
-            inner,
-        }
-    }
 
-    pub fn get_start(&self) -> (usize, usize) {
-        for idx_r in 0..self.n_rows {
-            for idx_c in 0..self.n_cols {
-                if matches!(self.inner[idx_r][idx_c], TachyonEntry::Start) {
-                    return (idx_r, idx_c);
-                }
-            }
-        }
-        panic!("Could not find start")
+fn try_main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    args.log.init();
+    args.threads.init()?;
+    args.timeout.init();
+
+    if let Part::Gen {
+        width,
+        height,
+        density,
+        seed,
+    } = args.part
+    {
+        print!("{}", generate_manifold(width, height, density, seed));
+        return Ok(());
     }
 
-    pub fn query_location(
-        &self,
-        idx_r: usize,
-        idx_c: usize,
-        offset_r: isize,
-        offset_c: isize,
-    ) -> Option<&TachyonEntry> {
-        let new_r = {
-            let t = idx_r as isize + offset_r;
-            if t < 0 {
-                return None;
-            } else {
-                t as usize
-            }
-        };
-        if new_r >= self.n_rows {
-            return None;
-        }
+    let s = args.input.read_to_string(EXAMPLE)?;
 
-        let new_c = {
-            let t = idx_c as isize + offset_c;
-            if t < 0 {
-                return None;
-            } else {
-                t as usize
-            }
-        };
-        if new_c >= self.n_cols {
-            return None;
-        }
-        Some(&self.inner[new_r][new_c])
+    let start = Instant::now();
+    let input = parse_input(&s, args.boundary).with_context(|| args.input.name().to_owned())?;
+    let parse = start.elapsed();
+    if args.render {
+        print!("{}", input.render_coverage());
     }
-}
 
-/// Tachyon Beam
-#[derive(Debug, Clone, Hash, Eq, PartialEq)]
-struct TachyonBeam {
-    pos_r: usize,
-    pos_c: usize,
-}
-impl TachyonBeam {
-    pub fn evolve(self, manifold: &TachyonManifold) -> Vec<Self> {
-        let mut v = Vec::new();
-        if let Some(entry) = manifold.query_location(self.pos_r, self.pos_c, 1, 0) {
-            match entry {
-                TachyonEntry::Start => panic!("This doesn't make sense"),
-                TachyonEntry::Splitter => {
-                    // We need to create a new on left and right
-                    // left
-                    if manifold
-                        .query_location(self.pos_r, self.pos_c, 1, -1)
-                        .is_some()
-                    {
-                        v.push(TachyonBeam {
-                            pos_r: self.pos_r + 1,
-                            pos_c: (self.pos_c as isize - 1) as usize,
-                        });
-                        // right
-                        if manifold
-                            .query_location(self.pos_r, self.pos_c, 1, 1)
-                            .is_some()
-                        {
-                            v.push(TachyonBeam {
-                                pos_r: self.pos_r + 1,
-                                pos_c: self.pos_c + 1,
-                            });
-                        }
-                    }
-                }
-                TachyonEntry::Open => {
-                    // If it is open, we can inhabit
-                    v.push(TachyonBeam {
-                        pos_r: self.pos_r + 1,
-                        pos_c: self.pos_c,
-                    })
-                }
-            }
-        };
-        v
-    }
-}
+    let name = args.part.name();
+    let start = Instant::now();
+    let answer = match args.part {
+        Part::Part1 => part_one(input),
+        Part::Part2 => part_two(input),
+        Part::Animate { output_dir } => write_frames(&input, &output_dir)?,
+        Part::Through { row, col } => input
+            .count_timelines_through(row, col)
+            .with_context(|| format!("row {row}, column {col} is outside the manifold"))?,
+        Part::Gen { .. } => unreachable!("Handled before reading input"),
+    };
 
-/// A Timeline is a set of locations that eventually reach the end
-#[derive(Debug, Clone, Hash, Eq, PartialEq)]
-struct Timeline(Vec<TachyonBeam>);
+    args.output.print(&Record {
+        day: 7,
+        part: name,
+        answer: answer.to_string(),
+        parse,
+        solve: start.elapsed(),
+    });
 
-/// Counter
-#[derive(Debug, Clone)]
-struct Manager {
-    manifold: TachyonManifold,
+    Ok(())
 }
-impl Manager {
-    pub fn new(manifold: TachyonManifold) -> Self {
-        Self { manifold }
-    }
-
-    pub fn run_p1(self) -> usize {
-        // Create the first beam
-        let (pos_r, pos_c) = self.manifold.get_start();
-        let mut beams = HashSet::new();
-        beams.insert(TachyonBeam { pos_r, pos_c });
-        let mut ctr = 0;
-        while !beams.is_empty() {
-            let mut new_beams = HashSet::new();
-            for beam in beams {
-                let evolved_beams = beam.evolve(&self.manifold);
-                if evolved_beams.len() == 2 {
-                    // We split, increment the pt1_ctr
-                    ctr += 1;
-                }
-                new_beams.extend(evolved_beams);
-            }
-            beams = new_beams;
-        }
-        ctr
-    }
-
-    pub fn run_p2(self) -> usize {
-        let (pos_r, pos_c) = self.manifold.get_start();
-        let current_timeline = Timeline(vec![TachyonBeam { pos_r, pos_c }]);
-        let mut prev_seen = HashMap::new();
-        Self::pt2_recursive_count(&self.manifold, &mut prev_seen, current_timeline)
-    }
 
-    pub fn pt2_recursive_count(
-        manifold: &TachyonManifold,
-        prev_seen: &mut HashMap<TachyonBeam, usize>,
-        current_timeline: Timeline,
-    ) -> usize {
-        let v = &current_timeline.0;
-        let beam = v.last().unwrap().clone();
-        // Check if we have seen this before
-        if let Some(i) = prev_seen.get(&beam) {
-            return *i;
-        }
-        let evolved_beams = beam.clone().evolve(manifold);
-        if evolved_beams.is_empty() {
-            return 1;
-        }
-        let mut count = 0;
-        for beam in evolved_beams {
-            let mut new_v = v.clone();
-            new_v.push(beam);
-            count += Self::pt2_recursive_count(manifold, prev_seen, Timeline(new_v));
-        }
-        // Store in previously seen for future
-        prev_seen.insert(beam, count);
-        count
+/// Parse a probability, which has to be from 0 to 1
+fn probability(s: &str) -> Result<f64, String> {
+    let p: f64 = s.parse().map_err(|e| format!("{e}"))?;
+    if (0.0..=1.0).contains(&p) {
+        Ok(p)
+    } else {
+        Err(format!("{p} is not between 0 and 1"))
     }
 }
 
-/// Internal logic for part_one
-fn part_one_internal(input: Manager) -> ReturnType {
-    input.run_p1()
+fn parse_input(s: &str, boundary: BoundaryPolicy) -> Result<Manager, ParseError> {
+    let manifold = TachyonManifold::new(s)?.with_boundary(boundary);
+    Ok(Manager::new(manifold))
 }
 
-/// Internal logic for part two
-fn part_two_internal(input: Manager) -> ReturnType {
-    input.run_p2()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    /// Function to modify for input to test
-    fn input_one() -> &'static str {
-        ".......S.......
-...............
-.......^.......
-...............
-......^.^......
-...............
-.....^.^.^.....
-...............
-....^.^...^....
-...............
-...^.^...^.^...
-...............
-..^...^.....^..
-...............
-.^.^.^.^.^...^.
-..............."
-    }
-
-    /// Function to split above into different inputs
-    fn parse_input_test(input: &str) -> Manager {
-        let manifold = TachyonManifold::new(input);
-        Manager::new(manifold)
-    }
-
-    #[test]
-    fn test_one() {
-        let input = parse_input_test(input_one());
-        let output = part_one_internal(input);
-
-        // TODO fill this out
-        assert_eq!(output, 21);
-    }
-
-    #[test]
-    fn test_two() {
-        let input = parse_input_test(input_one());
-        let output = part_two_internal(input);
-
-        // TODO fill this out
-        assert_eq!(output, 40);
+/// Write every animation frame to `output_dir`, returning the number of frames
+fn write_frames(input: &Manager, output_dir: &str) -> anyhow::Result<ReturnType> {
+    let output_dir = Path::new(output_dir);
+    std::fs::create_dir_all(output_dir)
+        .with_context(|| format!("cannot create {}", output_dir.display()))?;
+    let frames = input.animation_frames();
+    for (idx, frame) in frames.iter().enumerate() {
+        let path = output_dir.join(format!("frame_{idx:04}.txt"));
+        std::fs::write(&path, frame).with_context(|| format!("cannot write {}", path.display()))?;
     }
+    Ok(frames.len())
 }
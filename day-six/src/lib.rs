@@ -0,0 +1,243 @@
+//! Cephalopod math worksheets, read by rows for part one and by columns for part two
+use std::{
+    convert::Infallible,
+    io::{self, BufRead},
+};
+
+use aoc_common::solver::{SolveError, Solver};
+use sheet::{Boundary, EvalError, Number, Sheet, SheetError, to_grid};
+
+pub mod sheet;
+
+/// Parse a `--boundary` rule
+pub fn parse_boundary(rule: &str) -> Result<Boundary, String> {
+    if rule == "blank" {
+        return Ok(Boundary::Blank);
+    }
+    if let Some(run) = rule.strip_prefix("spaces:") {
+        return match run.parse() {
+            Ok(run) if run > 0 => Ok(Boundary::Spaces(run)),
+            _ => Err(format!("expected a positive run length, found {run:?}")),
+        };
+    }
+    if let Some(separator) = rule.strip_prefix("char:") {
+        let mut chars = separator.chars();
+        return match (chars.next(), chars.next()) {
+            (Some(separator), None) => Ok(Boundary::Separator(separator)),
+            _ => Err(format!("expected a single character, found {separator:?}")),
+        };
+    }
+    Err(format!(
+        "expected blank, spaces:N or char:C, found {rule:?}"
+    ))
+}
+
+pub fn parse_input(file: impl BufRead) -> io::Result<Vec<Vec<String>>> {
+    file.lines()
+        .map(|x| Ok(x?.split_whitespace().map(|x| x.to_owned()).collect()))
+        .collect()
+}
+
+/// Fields of each non-blank line, untrimmed
+pub fn parse_fields(file: impl BufRead, delimiter: char) -> io::Result<Vec<Vec<String>>> {
+    let mut rows = Vec::new();
+    for line in file.lines() {
+        let line = line?;
+        if !line.trim().is_empty() {
+            rows.push(line.split(delimiter).map(|x| x.to_owned()).collect());
+        }
+    }
+    Ok(rows)
+}
+
+pub fn parse_input2(file: impl BufRead) -> io::Result<Vec<Vec<char>>> {
+    let lines = file.lines().collect::<io::Result<Vec<String>>>()?;
+    Ok(to_grid(lines.iter().map(String::as_str)))
+}
+
+pub type ReturnType = usize;
+pub type InputType = Vec<Vec<String>>;
+
+/// How to evaluate a sheet once it is parsed
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Options {
+    pub parallel: bool,
+    pub boundary: Boundary,
+}
+
+/// Internal logic for part_one
+pub fn part_one_internal<N: Number>(input: InputType, options: Options) -> Result<N, SheetError> {
+    Ok(evaluate(&Sheet::from_rows(&input)?, options)?)
+}
+
+/// Internal logic for part two
+pub fn part_two_internal<N: Number>(
+    input: Vec<Vec<char>>,
+    options: Options,
+) -> Result<N, SheetError> {
+    Ok(evaluate(
+        &Sheet::from_columns(&input, options.boundary)?,
+        options,
+    )?)
+}
+
+/// Worksheet read both ways, by rows for part one and as a grid of characters for part two
+#[derive(Debug, Clone)]
+pub struct Worksheet {
+    rows: InputType,
+    grid: Vec<Vec<char>>,
+}
+
+/// The example from the puzzle text, run with `--example`
+pub const EXAMPLE: &str = include_str!("../examples/example.txt");
+
+impl Solver for Worksheet {
+    type Output = ReturnType;
+    /// Any text is a worksheet; malformed cells only fail once laid out for a part
+    type Error = Infallible;
+    const EXAMPLE: &'static str = EXAMPLE;
+
+    fn parse(input: &str) -> Result<Self, Infallible> {
+        Ok(Self {
+            rows: input
+                .lines()
+                .map(|x| x.split_whitespace().map(|x| x.to_owned()).collect())
+                .collect(),
+            grid: to_grid(input.lines()),
+        })
+    }
+
+    fn part_one(&self) -> Result<ReturnType, SolveError> {
+        part_one_internal(self.rows.clone(), Options::default()).map_err(SolveError::new)
+    }
+
+    fn part_two(&self) -> Result<ReturnType, SolveError> {
+        part_two_internal(self.grid.clone(), Options::default()).map_err(SolveError::new)
+    }
+}
+
+/// Evaluate a sheet, its groups in parallel if the options ask for it
+pub fn evaluate<N: Number>(sheet: &Sheet, options: Options) -> Result<N, EvalError> {
+    if options.parallel {
+        sheet.par_evaluate()
+    } else {
+        sheet.evaluate()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sheet::{Boundary, Operator, column_groups};
+
+    /// Function to split above into different inputs
+    fn parse_input_test(input: &str) -> Vec<Vec<String>> {
+        input
+            .lines()
+            .map(|x| x.split_whitespace().map(|x| x.to_owned()).collect())
+            .collect()
+    }
+
+    /// Function to split above into different inputs
+    fn parse_input_test2(input: &str) -> Vec<Vec<char>> {
+        to_grid(input.lines())
+    }
+
+    #[test]
+    fn test_one() {
+        let input = parse_input_test(EXAMPLE);
+        let output = part_one_internal::<ReturnType>(input, Options::default());
+
+        // TODO fill this out
+        assert_eq!(output, Ok(4277556));
+    }
+
+    #[test]
+    fn test_solver() {
+        let worksheet = Worksheet::parse(EXAMPLE).unwrap();
+        assert_eq!(
+            (worksheet.part_one(), worksheet.part_two()),
+            (Ok(4277556), Ok(3263827))
+        );
+        // Malformed cells are an error for the part reading them, not a panic
+        let worksheet = Worksheet::parse("1 x\n+ *").unwrap();
+        assert_eq!(
+            worksheet.part_one(),
+            Err(SolveError::new("group 2: \"x\" is not a number"))
+        );
+    }
+
+    #[test]
+    fn test_operators() {
+        let input = "20 10 12 9 12 7
+4  5  30 2 5  8
+-  /  min max concat *";
+        assert_eq!(
+            part_one_internal::<ReturnType>(parse_input_test(input), Options::default()),
+            Ok(16 + 2 + 12 + 9 + 125 + 56)
+        );
+        for (sign, expected) in [
+            (Operator::Sub, 5),
+            (Operator::Div, 1),
+            (Operator::Min, 3),
+            (Operator::Max, 12),
+            (Operator::Concat, 1243),
+        ] {
+            assert_eq!(sign.reduce([12usize, 4, 3]), Ok(expected), "{sign:?}");
+        }
+        assert_eq!(Operator::Concat.reduce([10usize, 0, 7]), Ok(1007));
+        // Part two reads multi-character operators starting at the group's first column
+        let input = "123 7
+456 8
+min +";
+        assert_eq!(
+            part_two_internal::<ReturnType>(parse_input_test2(input), Options::default()),
+            Ok(14 + 78)
+        );
+    }
+
+    #[test]
+    fn test_ragged_layout() {
+        // Trailing spaces trimmed, a short operator row and blank lines at the end
+        let input = "123 328  51 64\n 45 64  387 23\n  6 98  215 314\n*   +   *   +\n\n";
+        assert_eq!(
+            part_two_internal::<ReturnType>(parse_input_test2(input), Options::default()),
+            Ok(3263827)
+        );
+        // Tabs jump to the next multiple of 8 columns
+        let input = "12\t3\n4\t5\n+\t*";
+        assert_eq!(parse_input_test2(input)[0].len(), 9);
+        assert_eq!(
+            part_two_internal::<ReturnType>(parse_input_test2(input), Options::default()),
+            Ok(14 + 2 + 35)
+        );
+        assert_eq!(
+            column_groups(&parse_input_test2(input), Boundary::Blank),
+            vec![0..2, 8..9]
+        );
+    }
+
+    #[test]
+    fn test_parse_boundary() {
+        assert_eq!(parse_boundary("blank"), Ok(Boundary::Blank));
+        assert_eq!(parse_boundary("spaces:3"), Ok(Boundary::Spaces(3)));
+        assert_eq!(parse_boundary("char:|"), Ok(Boundary::Separator('|')));
+        for rule in ["spaces:0", "spaces:x", "char:", "char:ab", "tabs"] {
+            assert!(parse_boundary(rule).is_err(), "{rule}");
+        }
+    }
+
+    #[test]
+    fn test_two() {
+        let input = parse_input_test2(EXAMPLE);
+        let output = part_two_internal::<ReturnType>(input.clone(), Options::default());
+
+        // TODO fill this out
+        assert_eq!(output, Ok(3263827));
+        let parallel = Options {
+            parallel: true,
+            ..Options::default()
+        };
+        assert_eq!(part_two_internal(input, parallel), output);
+    }
+}
@@ -1,18 +1,78 @@
 //! Command line executable for running part one and part two
 use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-    time::Instant,
+    io::BufRead,
+    process::ExitCode,
+    time::{Duration, Instant},
 };
 
+use anyhow::{Context, bail};
+use aoc_common::{
+    cancel::TimeoutArgs,
+    input::InputArgs,
+    log::LogArgs,
+    output::{self, OutputArgs, Record},
+    threads::ThreadArgs,
+};
 use clap::Parser;
+use day_six::{
+    EXAMPLE, Options, ReturnType, evaluate, parse_boundary, parse_fields, parse_input,
+    parse_input2,
+    sheet::{
+        ArithError, Boundary, ColumnAccumulator, EvalError, Number, Rational, Sheet, SheetError,
+        layout_fields, transpose, transpose_grid,
+    },
+};
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Input file
-    #[arg(short)]
-    input_file: String,
+    #[command(flatten)]
+    input: InputArgs,
+
+    /// Kind of number the sheet is evaluated in
+    #[arg(long, value_enum, default_value_t)]
+    number: NumberType,
+
+    /// Accumulate integers in 128 bits, for sheets whose results overflow
+    #[arg(long)]
+    big: bool,
+
+    /// Evaluate the column groups in parallel
+    #[arg(long)]
+    parallel: bool,
+
+    /// Problems run across the rows, with the operators in the last column
+    #[arg(long)]
+    transposed: bool,
+
+    /// How the cells of the sheet are separated
+    #[arg(long, value_enum, default_value_t)]
+    format: Format,
+
+    /// Print each column group, its numbers, operator and result before the answer
+    #[arg(long)]
+    explain: bool,
+
+    /// What separates the groups in part two: `blank` columns, `spaces:N` for runs of at least N
+    /// blank columns, or `char:C` for columns of the character C
+    #[arg(long, default_value = "blank", value_parser = parse_boundary)]
+    boundary: Boundary,
+
+    /// Read part two a line at a time instead of loading the whole sheet
+    #[arg(long, conflicts_with_all = ["transposed", "format"])]
+    stream: bool,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(flatten)]
+    log: LogArgs,
+
+    #[command(flatten)]
+    threads: ThreadArgs,
+
+    #[command(flatten)]
+    timeout: TimeoutArgs,
 
     #[command(subcommand)]
     part: Part,
@@ -24,177 +84,149 @@ enum Part {
     Part2,
 }
 
-fn main() {
-    let args = Args::parse();
-
-    let file = BufReader::new(File::open(args.input_file).expect("Cannot find file"));
-
-    let start = Instant::now();
-    let answer = match args.part {
-        Part::Part1 => part_one(file),
-        Part::Part2 => part_two(file),
-    };
-
-    println!("{:?}", answer);
-    println!("Completed in {:?}", start.elapsed());
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum NumberType {
+    /// Whole numbers from zero up
+    #[default]
+    Unsigned,
+    /// Whole numbers, possibly negative
+    Signed,
+    /// Floating point, for decimals
+    Float,
+    /// Exact fractions, for decimals
+    Rational,
 }
 
-fn part_one(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file);
-    part_one_internal(input)
+#[derive(clap::ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+enum Format {
+    /// Aligned columns separated by whitespace
+    #[default]
+    Fixed,
+    /// Comma separated fields
+    Csv,
+    /// Tab separated fields
+    Tsv,
 }
-
-fn part_two(file: BufReader<File>) -> ReturnType {
-    let input = parse_input2(file);
-    part_two_internal(input)
+impl Format {
+    fn delimiter(self) -> Option<char> {
+        match self {
+            Self::Fixed => None,
+            Self::Csv => Some(','),
+            Self::Tsv => Some('\t'),
+        }
+    }
 }
 
-fn parse_input(file: BufReader<File>) -> Vec<Vec<String>> {
-    file.lines()
-        .map(|x| {
-            let x = x.unwrap();
-            x.split_whitespace().map(|x| x.to_owned()).collect()
-        })
-        .collect()
+fn main() -> ExitCode {
+    output::exit(try_main())
 }
 
-fn parse_input2(file: BufReader<File>) -> Vec<Vec<char>> {
-    // Go through and create a Vec<Vec<char>>
-    file.lines()
-        .map(|x| {
-            let x = x.unwrap();
-            x.chars().collect()
-        })
-        .collect()
-}
+fn try_main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    args.log.init();
+    args.threads.init()?;
+    args.timeout.init();
 
-type ReturnType = usize;
-type InputType = Vec<Vec<String>>;
-
-/// Internal logic for part_one
-fn part_one_internal(input: InputType) -> ReturnType {
-    let n_rows = input.len();
-    let n_cols = input[0].len();
-    let mut big_sum = 0;
-    for idx_c in 0..n_cols {
-        // Select the sign
-        let math = match input[n_rows - 1][idx_c].as_str() {
-            "*" => {
-                // Multiply all values
-                let mut mult = 1;
-                for input_r in input.iter().take(n_rows - 1) {
-                    mult *= input_r[idx_c].parse::<usize>().unwrap();
-                }
-                mult
-            }
-            "+" => {
-                // Add all values
-                let mut sum = 0;
-                for input_r in input.iter().take(n_rows - 1) {
-                    sum += input_r[idx_c].parse::<usize>().unwrap();
-                }
-                sum
+    let file = args.input.open(EXAMPLE)?;
+
+    let start = Instant::now();
+    let answer = match (args.number, args.big) {
+        (NumberType::Unsigned, false) => solve::<ReturnType>(&args, file),
+        (NumberType::Unsigned, true) => solve::<u128>(&args, file),
+        (NumberType::Signed, false) => solve::<i64>(&args, file),
+        (NumberType::Signed, true) => solve::<i128>(&args, file),
+        (NumberType::Float, _) => solve::<f64>(&args, file),
+        (NumberType::Rational, _) => solve::<Rational>(&args, file),
+    };
+    let (answer, parse) = match answer {
+        Ok(answer) => answer,
+        Err(e) => {
+            let integer = matches!(args.number, NumberType::Unsigned | NumberType::Signed);
+            let overflow = matches!(
+                e.downcast_ref(),
+                Some(SheetError::Eval(EvalError {
+                    error: ArithError::Overflow,
+                    ..
+                }))
+            );
+            if overflow && integer && !args.big {
+                bail!("{}: {e}, try --big", args.input.name());
             }
-            _ => panic!("Not a valid sign"),
-        };
-        big_sum += math;
-    }
-    big_sum
+            return Err(e).context(args.input.name().to_owned());
+        }
+    };
+
+    args.output.print(&Record {
+        day: 6,
+        part: match args.part {
+            Part::Part1 => 1,
+            Part::Part2 => 2,
+        }
+        .into(),
+        answer,
+        parse,
+        solve: start.elapsed() - parse,
+    });
+
+    Ok(())
 }
 
-/// Internal logic for part two
-fn part_two_internal(input: Vec<Vec<char>>) -> ReturnType {
-    // Right now, we have rows x columns of numbers. We need to convert this to, for each column,
-    // create a new entry that is rows by columns of numerical characters
-    let n_rows = input.len();
-    let n_cols = input[0].len();
-    let mut big_sum = 0;
-    let mut sign = None;
-    let mut tmp_value = 0;
-
-    for idx_c in 0..n_cols {
-        if sign.is_none() {
-            // If sign is None, then we need to determine the sign
-            sign = Some(input[n_rows - 1][idx_c]);
-            match sign {
-                Some('+') => tmp_value = 0,
-                Some('*') => tmp_value = 1,
-                _ => panic!("Not a valid value"),
+/// Run the chosen part, accumulating in `N`, with how long parsing the sheet took
+///
+/// A streamed sheet is evaluated as it is read, so none of its time counts as parsing.
+fn solve<N: Number>(args: &Args, file: impl BufRead) -> anyhow::Result<(String, Duration)> {
+    let start = Instant::now();
+    let mut parse = Duration::ZERO;
+    let options = Options {
+        parallel: args.parallel,
+        boundary: args.boundary,
+    };
+    let sheet = match args.part {
+        Part::Part1 => {
+            let mut input = match args.format.delimiter() {
+                None => parse_input(file)?,
+                Some(delimiter) => parse_fields(file, delimiter)?
+                    .into_iter()
+                    .map(|row| row.iter().map(|field| field.trim().to_owned()).collect())
+                    .collect(),
+            };
+            if args.transposed {
+                input = transpose(&input);
+            }
+            parse = start.elapsed();
+            Sheet::from_rows(&input)
+        }
+        Part::Part2 if args.stream => {
+            let mut accumulator = ColumnAccumulator::with_boundary(options.boundary);
+            for line in file.lines() {
+                accumulator.push_line(&line?);
             }
+            accumulator.finish()
         }
-        if is_all_space(&input, idx_c) {
-            // Take the value, add it to the big_sum and remove the sign
-            big_sum += tmp_value;
-            sign = None;
-        } else {
-            // Go down the row and do something with the value
-            let mut d = 0;
-            'a: for input_r in input.iter().take(n_rows - 1) {
-                if input_r[idx_c].is_whitespace() {
-                    continue 'a;
+        Part::Part2 => match args.format.delimiter() {
+            None => {
+                let mut input = parse_input2(file)?;
+                if args.transposed {
+                    input = transpose_grid(&input);
                 }
-                d = d * 10 + input_r[idx_c].to_digit(10).unwrap() as usize;
+                parse = start.elapsed();
+                Sheet::from_columns(&input, options.boundary)
             }
-            match sign {
-                Some('+') => tmp_value += d,
-                Some('*') => tmp_value *= d,
-                _ => panic!("Not a valid value"),
+            Some(delimiter) => {
+                let mut fields = parse_fields(file, delimiter)?;
+                if args.transposed {
+                    fields = transpose(&fields);
+                }
+                let (grid, groups) = layout_fields(&fields);
+                parse = start.elapsed();
+                Sheet::from_column_groups(&grid, groups)
             }
-        }
-    }
-    // Now, we need to actually add tmp_value to big_sum
-    big_sum + tmp_value
-}
-
-fn is_all_space(input: &[Vec<char>], idx_c: usize) -> bool {
-    for input_r in input.iter() {
-        if !input_r[idx_c].is_whitespace() {
-            return false;
-        }
-    }
-    true
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    /// Function to modify for input to test
-    fn input_one() -> &'static str {
-        "123 328  51 64 
- 45 64  387 23 
-  6 98  215 314
-*   +   *   +  "
-    }
-
-    /// Function to split above into different inputs
-    fn parse_input_test(input: &str) -> Vec<Vec<String>> {
-        input
-            .lines()
-            .map(|x| x.split_whitespace().map(|x| x.to_owned()).collect())
-            .collect()
+        },
     }
-
-    /// Function to split above into different inputs
-    fn parse_input_test2(input: &str) -> Vec<Vec<char>> {
-        input.lines().map(|x| x.chars().collect()).collect()
-    }
-
-    #[test]
-    fn test_one() {
-        let input = parse_input_test(input_one());
-        let output = part_one_internal(input);
-
-        // TODO fill this out
-        assert_eq!(output, 4277556);
-    }
-
-    #[test]
-    fn test_two() {
-        let input = parse_input_test2(input_one());
-        let output = part_two_internal(input);
-
-        // TODO fill this out
-        assert_eq!(output, 3263827);
+    .map_err(SheetError::from)?;
+    if args.explain {
+        print!("{}", sheet.explain::<N>());
     }
+    let answer: N = evaluate(&sheet, options).map_err(SheetError::from)?;
+    Ok((answer.to_string(), parse))
 }
@@ -0,0 +1,1203 @@
+//! Parsing a worksheet into groups of numbers with their operator, and evaluating them
+//!
+//! Each group is reduced by its own operator, then the group results are reduced by the outer
+//! operator: `+` unless a second footer row holding a single operator says otherwise.
+use std::{fmt, ops::Range};
+
+use num_rational::Ratio;
+use num_traits::{CheckedAdd, CheckedDiv, CheckedMul, CheckedSub};
+use rayon::prelude::*;
+
+/// Number type a sheet can be evaluated in
+pub trait Number: Copy + PartialOrd + Send + fmt::Display {
+    const ZERO: Self;
+    /// Read a number of the sheet, or `None` if this type can't hold it
+    fn parse(literal: &str) -> Option<Self>;
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_div(self, rhs: Self) -> Option<Self>;
+    /// `self` followed by the digits of `rhs`
+    fn concat(self, rhs: Self) -> Result<Self, ArithError>;
+}
+
+/// `lhs` shifted left by `shift`, with `rhs` appended away from zero
+fn append<N: Number>(lhs: N, shift: N, rhs: N) -> Result<N, ArithError> {
+    let shifted = lhs.checked_mul(shift).ok_or(ArithError::Overflow)?;
+    let appended = if lhs < N::ZERO {
+        shifted.checked_sub(rhs)
+    } else {
+        shifted.checked_add(rhs)
+    };
+    appended.ok_or(ArithError::Overflow)
+}
+
+macro_rules! impl_integer {
+    ($($t:ty),*) => {
+        $(
+            impl Number for $t {
+                const ZERO: Self = 0;
+                fn parse(literal: &str) -> Option<Self> {
+                    literal.parse().ok()
+                }
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+                fn checked_div(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_div(self, rhs)
+                }
+                fn concat(self, rhs: Self) -> Result<Self, ArithError> {
+                    // The logarithm is only missing for zero and negative numbers
+                    let digits = match rhs.checked_ilog10() {
+                        Some(log) => log + 1,
+                        None if rhs == 0 => 1,
+                        None => return Err(ArithError::NotWhole),
+                    };
+                    let shift = <$t>::checked_pow(10, digits).ok_or(ArithError::Overflow)?;
+                    append(self, shift, rhs)
+                }
+            }
+        )*
+    };
+}
+impl_integer!(usize, u128, i64, i128);
+
+/// Keep only finite results, so overflowing to infinity is an error
+fn finite(value: f64) -> Option<f64> {
+    value.is_finite().then_some(value)
+}
+
+impl Number for f64 {
+    const ZERO: Self = 0.0;
+    fn parse(literal: &str) -> Option<Self> {
+        literal.parse().ok()
+    }
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        finite(self + rhs)
+    }
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        finite(self - rhs)
+    }
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        finite(self * rhs)
+    }
+    fn checked_div(self, rhs: Self) -> Option<Self> {
+        finite(self / rhs)
+    }
+    fn concat(self, rhs: Self) -> Result<Self, ArithError> {
+        if rhs < 0.0 || rhs.fract() != 0.0 {
+            return Err(ArithError::NotWhole);
+        }
+        let digits = if rhs == 0.0 {
+            1
+        } else {
+            rhs.log10().floor() as i32 + 1
+        };
+        append(self, 10f64.powi(digits), rhs)
+    }
+}
+
+/// Exact fraction, so decimals like `0.1` add up without rounding
+pub type Rational = Ratio<i128>;
+
+impl Number for Rational {
+    const ZERO: Self = Ratio::new_raw(0, 1);
+    fn parse(literal: &str) -> Option<Self> {
+        let (whole, fraction) = literal.split_once('.').unwrap_or((literal, ""));
+        let numerator = format!("{whole}{fraction}").parse().ok()?;
+        let denominator = 10i128.checked_pow(fraction.len() as u32)?;
+        Some(Ratio::new(numerator, denominator))
+    }
+    fn checked_add(self, rhs: Self) -> Option<Self> {
+        CheckedAdd::checked_add(&self, &rhs)
+    }
+    fn checked_sub(self, rhs: Self) -> Option<Self> {
+        CheckedSub::checked_sub(&self, &rhs)
+    }
+    fn checked_mul(self, rhs: Self) -> Option<Self> {
+        CheckedMul::checked_mul(&self, &rhs)
+    }
+    fn checked_div(self, rhs: Self) -> Option<Self> {
+        CheckedDiv::checked_div(&self, &rhs)
+    }
+    fn concat(self, rhs: Self) -> Result<Self, ArithError> {
+        if !rhs.is_integer() || rhs < Self::ZERO {
+            return Err(ArithError::NotWhole);
+        }
+        let digits = rhs.to_integer().checked_ilog10().map_or(1, |log| log + 1);
+        let shift = 10i128.checked_pow(digits).ok_or(ArithError::Overflow)?;
+        append(self, Ratio::from_integer(shift), rhs)
+    }
+}
+
+/// Whether the token is a number: an optional `-`, then digits with at most one `.`
+fn is_literal(token: &str) -> bool {
+    let unsigned = token.strip_prefix('-').unwrap_or(token);
+    let (whole, fraction) = unsigned.split_once('.').unwrap_or((unsigned, ""));
+    !(whole.is_empty() && fraction.is_empty())
+        && whole
+            .chars()
+            .chain(fraction.chars())
+            .all(|c| c.is_ascii_digit())
+}
+
+/// `token` if it is a number
+fn literal(token: String) -> Result<String, String> {
+    if !is_literal(&token) {
+        return Err(format!("{token:?} is not a number"));
+    }
+    Ok(token)
+}
+
+/// Operator of a group, from its token in the operator row
+fn operator(token: &str) -> Result<Operator, String> {
+    Operator::parse(token).ok_or_else(|| format!("{token:?} is not an operator"))
+}
+
+/// A group of the sheet that doesn't hold an operator and numbers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LayoutError {
+    /// 1-indexed group, from the left
+    pub group: usize,
+    pub reason: String,
+}
+impl LayoutError {
+    /// Labeller for the errors of the group at 0-indexed `idx`
+    fn at(idx: usize) -> impl Fn(String) -> Self + Copy {
+        move |reason| Self {
+            group: idx + 1,
+            reason,
+        }
+    }
+}
+impl fmt::Display for LayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "group {}: {}", self.group, self.reason)
+    }
+}
+impl std::error::Error for LayoutError {}
+
+/// Why an operator could not produce a result
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArithError {
+    Overflow,
+    Underflow,
+    DivideByZero,
+    /// A number of the sheet doesn't fit the chosen type, e.g. a decimal in an integer
+    Unrepresentable,
+    /// `concat` of a negative or fractional number
+    NotWhole,
+    /// A group or sheet without any numbers to combine
+    NoNumbers,
+}
+impl fmt::Display for ArithError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "result too large"),
+            Self::Underflow => write!(f, "result too small"),
+            Self::DivideByZero => write!(f, "division by zero"),
+            Self::Unrepresentable => write!(f, "number not representable in the chosen type"),
+            Self::NotWhole => write!(f, "concat needs whole numbers that aren't negative"),
+            Self::NoNumbers => write!(f, "no numbers to combine"),
+        }
+    }
+}
+
+/// Failure evaluating a sheet
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EvalError {
+    /// 1-indexed group that failed, or `None` for the outer reduction
+    pub group: Option<usize>,
+    pub error: ArithError,
+}
+impl fmt::Display for EvalError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.group {
+            Some(group) => write!(f, "group {group}: {}", self.error),
+            None => write!(f, "combining the groups: {}", self.error),
+        }
+    }
+}
+impl std::error::Error for EvalError {}
+
+/// Failure laying out or evaluating a sheet
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SheetError {
+    Layout(LayoutError),
+    Eval(EvalError),
+}
+impl fmt::Display for SheetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Layout(e) => write!(f, "{e}"),
+            Self::Eval(e) => write!(f, "{e}"),
+        }
+    }
+}
+impl std::error::Error for SheetError {}
+impl From<LayoutError> for SheetError {
+    fn from(e: LayoutError) -> Self {
+        Self::Layout(e)
+    }
+}
+impl From<EvalError> for SheetError {
+    fn from(e: EvalError) -> Self {
+        Self::Eval(e)
+    }
+}
+
+/// Operator combining the numbers of a column
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operator {
+    Add,
+    Mul,
+    Sub,
+    Div,
+    Min,
+    Max,
+    /// Append the digits of each number to the previous ones
+    Concat,
+}
+impl fmt::Display for Operator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let token = match self {
+            Self::Add => "+",
+            Self::Mul => "*",
+            Self::Sub => "-",
+            Self::Div => "/",
+            Self::Min => "min",
+            Self::Max => "max",
+            Self::Concat => "concat",
+        };
+        write!(f, "{token}")
+    }
+}
+impl Operator {
+    /// Parse an operator token from the last row
+    pub fn parse(token: &str) -> Option<Self> {
+        match token {
+            "+" => Some(Self::Add),
+            "*" => Some(Self::Mul),
+            "-" => Some(Self::Sub),
+            "/" => Some(Self::Div),
+            "min" => Some(Self::Min),
+            "max" => Some(Self::Max),
+            "concat" => Some(Self::Concat),
+            _ => None,
+        }
+    }
+
+    pub fn apply<N: Number>(&self, lhs: N, rhs: N) -> Result<N, ArithError> {
+        match self {
+            Self::Add => lhs.checked_add(rhs).ok_or(ArithError::Overflow),
+            Self::Mul => lhs.checked_mul(rhs).ok_or(ArithError::Overflow),
+            Self::Sub => lhs.checked_sub(rhs).ok_or(ArithError::Underflow),
+            Self::Div if rhs == N::ZERO => Err(ArithError::DivideByZero),
+            Self::Div => lhs.checked_div(rhs).ok_or(ArithError::Overflow),
+            Self::Min => Ok(if rhs < lhs { rhs } else { lhs }),
+            Self::Max => Ok(if rhs > lhs { rhs } else { lhs }),
+            Self::Concat => lhs.concat(rhs),
+        }
+    }
+
+    /// Fold the numbers from left to right, starting from the first
+    pub fn reduce<N: Number>(&self, values: impl IntoIterator<Item = N>) -> Result<N, ArithError> {
+        let mut values = values.into_iter();
+        let first = values.next().ok_or(ArithError::NoNumbers)?;
+        values.try_fold(first, |acc, value| self.apply(acc, value))
+    }
+}
+
+/// Whether every token is an operator
+fn is_operator_row<S: AsRef<str>>(tokens: &[S]) -> bool {
+    !tokens.is_empty()
+        && tokens
+            .iter()
+            .all(|token| Operator::parse(token.as_ref()).is_some())
+}
+
+/// Outer operator, if the last two rows are both operators and the last has only one
+fn outer_operator<S: AsRef<str>>(inner: &[S], last: &[S]) -> Option<Operator> {
+    match last {
+        [token] if is_operator_row(inner) => Operator::parse(token.as_ref()),
+        _ => None,
+    }
+}
+
+/// Separate the outer operator row, if any, from the rest of a grid
+fn split_outer(grid: &[Vec<char>]) -> (&[Vec<char>], Operator) {
+    let tokens = |row: &[char]| -> Vec<String> {
+        let line: String = row.iter().collect();
+        line.split_whitespace().map(str::to_owned).collect()
+    };
+    match grid {
+        [rest @ .., inner, last] => match outer_operator(&tokens(inner), &tokens(last)) {
+            Some(outer) => (&grid[..rest.len() + 1], outer),
+            None => (grid, Operator::Add),
+        },
+        _ => (grid, Operator::Add),
+    }
+}
+
+/// Width of a tab stop when laying the sheet out in columns
+const TAB_WIDTH: usize = 8;
+
+/// Lay the lines out on a rectangular grid of characters
+///
+/// Tabs are expanded to the next tab stop and short lines are padded with spaces.
+pub fn to_grid<'a>(lines: impl Iterator<Item = &'a str>) -> Vec<Vec<char>> {
+    let mut grid: Vec<Vec<char>> = lines.map(expand_tabs).collect();
+    // Blank lines at the end would be taken for the operator row
+    while grid
+        .last()
+        .is_some_and(|row| row.iter().all(|c| c.is_whitespace()))
+    {
+        grid.pop();
+    }
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+    for row in grid.iter_mut() {
+        row.resize(width, ' ');
+    }
+    grid
+}
+
+/// Characters of the line with each tab expanded to the next tab stop
+fn expand_tabs(line: &str) -> Vec<char> {
+    let mut row = Vec::with_capacity(line.len());
+    for c in line.chars() {
+        if c == '\t' {
+            let next_stop = (row.len() / TAB_WIDTH + 1) * TAB_WIDTH;
+            row.resize(next_stop, ' ');
+        } else {
+            row.push(c);
+        }
+    }
+    row
+}
+
+/// Part two sheet read one line at a time, keeping only the number built so far in each column
+///
+/// Memory grows with the width of the sheet rather than its height. The footer lines are held
+/// back until the end, since only then is it known which lines are operators.
+#[derive(Debug, Clone, Default)]
+pub struct ColumnAccumulator {
+    columns: Vec<ColumnState>, // What has been read so far down each column
+    footer: Vec<Vec<char>>,    // Last two non-blank lines, not yet added to `columns`
+    boundary: Boundary,
+}
+impl ColumnAccumulator {
+    /// Accumulator splitting the groups by `boundary` rather than blank columns
+    pub fn with_boundary(boundary: Boundary) -> Self {
+        Self {
+            boundary,
+            ..Self::default()
+        }
+    }
+
+    pub fn push_line(&mut self, line: &str) {
+        let row = expand_tabs(line);
+        // Blank lines add no digits, and trailing ones must not become the operator row
+        if row.iter().all(|c| c.is_whitespace()) {
+            return;
+        }
+        self.footer.push(row);
+        if self.footer.len() > 2 {
+            let row = self.footer.remove(0);
+            self.add_digits(&row);
+        }
+    }
+
+    fn add_digits(&mut self, row: &[char]) {
+        if self.columns.len() < row.len() {
+            self.columns.resize(row.len(), ColumnState::Empty);
+        }
+        let separator = self.boundary.separator();
+        for (column, c) in self.columns.iter_mut().zip(row) {
+            if c.is_whitespace() {
+                continue;
+            }
+            column.push(*c, separator);
+        }
+    }
+
+    /// Group the columns under the operator row, as `Sheet::from_columns` would
+    pub fn finish(mut self) -> Result<Sheet, LayoutError> {
+        let footer = std::mem::take(&mut self.footer);
+        let (footer, outer) = split_outer(&footer);
+        let Some((operators, rows)) = footer.split_last() else {
+            return Ok(Sheet::empty());
+        };
+        for row in rows {
+            self.add_digits(row);
+        }
+        let width = self.columns.len().max(operators.len());
+        let mut operators = operators.clone();
+        operators.resize(width, ' ');
+        self.columns.resize(width, ColumnState::Empty);
+        // Boil each column down to one character the boundary rule judges the same way
+        let separator = self.boundary.separator();
+        let occupied: Vec<char> = self
+            .columns
+            .iter()
+            .zip(&operators)
+            .map(|(column, c)| match (column, separator) {
+                (ColumnState::Empty, _) => *c,
+                (ColumnState::Separators(_), Some(separator))
+                    if c.is_whitespace() || *c == separator =>
+                {
+                    separator
+                }
+                _ => '0',
+            })
+            .collect();
+        let groups = column_groups(&[occupied], self.boundary)
+            .into_iter()
+            .enumerate()
+            .map(|(idx, columns)| {
+                let token: String = operators[columns.clone()].iter().collect();
+                Ok(Group {
+                    operator: operator(token.trim()).map_err(LayoutError::at(idx))?,
+                    operands: self.columns[columns.clone()]
+                        .iter()
+                        .filter(|column| **column != ColumnState::Empty)
+                        .map(|column| column.literal(separator))
+                        .collect::<Result<_, _>>()
+                        .map_err(LayoutError::at(idx))?,
+                    columns,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Sheet { groups, outer })
+    }
+}
+
+/// What has been read down one column of a streamed sheet, without keeping its characters
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+enum ColumnState {
+    #[default]
+    Empty,
+    /// Nothing but this many of the boundary's separator
+    Separators(usize),
+    Number(ColumnNumber),
+    /// Characters that can't make a number, for the reason given
+    Invalid(String),
+}
+impl ColumnState {
+    /// Read the next character down the column
+    fn push(&mut self, c: char, separator: Option<char>) {
+        match self {
+            Self::Empty if Some(c) == separator => *self = Self::Separators(1),
+            Self::Empty => {
+                *self = Self::Number(ColumnNumber::default());
+                self.push(c, separator);
+            }
+            Self::Separators(count) if Some(c) == separator => *count += 1,
+            Self::Separators(_) => *self = Self::Invalid(format!("{c:?} follows a separator")),
+            Self::Number(number) => {
+                if let Err(reason) = number.push(c) {
+                    *self = Self::Invalid(reason);
+                }
+            }
+            Self::Invalid(_) => {}
+        }
+    }
+
+    /// The column's number as written
+    fn literal(&self, separator: Option<char>) -> Result<String, String> {
+        match self {
+            Self::Empty => Err("no digits".to_owned()),
+            Self::Separators(count) => {
+                literal(separator.into_iter().cycle().take(*count).collect())
+            }
+            Self::Number(number) => literal(number.to_string()),
+            Self::Invalid(reason) => Err(reason.clone()),
+        }
+    }
+}
+
+/// Number read down a column so far, as its sign and digits
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+struct ColumnNumber {
+    negative: bool,
+    whole: Digits,
+    /// Digits after the `.`, once there is one
+    fraction: Option<Digits>,
+}
+impl ColumnNumber {
+    fn push(&mut self, c: char) -> Result<(), String> {
+        if let Some(digit) = c.to_digit(10) {
+            return self
+                .fraction
+                .as_mut()
+                .unwrap_or(&mut self.whole)
+                .push(digit);
+        }
+        match (c, &self.fraction) {
+            ('-', None) if !self.negative && self.whole.count == 0 => self.negative = true,
+            ('.', None) => self.fraction = Some(Digits::default()),
+            _ => return Err(format!("{c:?} can't be part of a number here")),
+        }
+        Ok(())
+    }
+}
+impl fmt::Display for ColumnNumber {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negative {
+            write!(f, "-")?;
+        }
+        write!(f, "{}", self.whole)?;
+        if let Some(fraction) = &self.fraction {
+            write!(f, ".{fraction}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Run of decimal digits, kept as their value and how many there are, so leading zeros survive
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct Digits {
+    value: u128,
+    count: usize,
+}
+impl Digits {
+    fn push(&mut self, digit: u32) -> Result<(), String> {
+        self.value = self
+            .value
+            .checked_mul(10)
+            .and_then(|value| value.checked_add(digit.into()))
+            .ok_or("more digits than fit in 128 bits")?;
+        self.count += 1;
+        Ok(())
+    }
+}
+impl fmt::Display for Digits {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.count > 0 {
+            write!(f, "{:0width$}", self.value, width = self.count)?;
+        }
+        Ok(())
+    }
+}
+
+/// Lay delimiter separated fields out side by side, one blank column apart
+///
+/// Spaces inside the fields are kept, so their digits line up as written. Returns the grid and
+/// the columns of each field, which become the column groups.
+pub fn layout_fields(rows: &[Vec<String>]) -> (Vec<Vec<char>>, Vec<Range<usize>>) {
+    let mut widths: Vec<usize> = Vec::new();
+    for row in rows {
+        widths.resize(widths.len().max(row.len()), 0);
+        for (width, field) in widths.iter_mut().zip(row) {
+            *width = (*width).max(field.chars().count());
+        }
+    }
+    let mut groups = Vec::with_capacity(widths.len());
+    let mut start = 0;
+    for width in &widths {
+        groups.push(start..start + width);
+        start += width + 1;
+    }
+    let grid = rows
+        .iter()
+        .map(|row| {
+            let mut line = Vec::with_capacity(start);
+            for (columns, field) in groups.iter().zip(row) {
+                line.extend(field.chars());
+                line.resize(columns.end + 1, ' ');
+            }
+            line.resize(start.saturating_sub(1), ' ');
+            line
+        })
+        .collect();
+    (grid, groups)
+}
+
+/// Swap rows and columns, for sheets whose problems run across the rows
+///
+/// Every row must be as long as the first.
+pub fn transpose<T: Clone>(rows: &[Vec<T>]) -> Vec<Vec<T>> {
+    let width = rows.first().map_or(0, Vec::len);
+    (0..width)
+        .map(|idx_c| rows.iter().map(|row| row[idx_c].clone()).collect())
+        .collect()
+}
+
+/// Transpose a grid from `to_grid`, dropping the blank rows left by its padding
+pub fn transpose_grid(grid: &[Vec<char>]) -> Vec<Vec<char>> {
+    let mut transposed = transpose(grid);
+    while transposed
+        .last()
+        .is_some_and(|row| row.iter().all(|c| c.is_whitespace()))
+    {
+        transposed.pop();
+    }
+    transposed
+}
+
+/// Which columns of a part two sheet separate its groups
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum Boundary {
+    /// Every column that is blank in every row
+    #[default]
+    Blank,
+    /// Runs of at least this many blank columns; shorter runs belong to the group around them
+    Spaces(usize),
+    /// Columns holding this character, and otherwise only blanks
+    Separator(char),
+}
+impl Boundary {
+    /// Character of the separator columns, if the groups are split by one
+    fn separator(self) -> Option<char> {
+        match self {
+            Self::Separator(separator) => Some(separator),
+            _ => None,
+        }
+    }
+}
+
+/// Column ranges of each group, found between the columns that `boundary` picks out
+pub fn column_groups(grid: &[Vec<char>], boundary: Boundary) -> Vec<Range<usize>> {
+    let width = grid.first().map_or(0, Vec::len);
+    let is_blank = |idx_c: usize| grid.iter().all(|row| row[idx_c].is_whitespace());
+    let is_separator: Vec<bool> = match boundary {
+        Boundary::Blank => (0..width).map(is_blank).collect(),
+        Boundary::Spaces(min_run) => {
+            let mut is_separator: Vec<bool> = (0..width).map(is_blank).collect();
+            let mut idx_c = 0;
+            while idx_c < width {
+                let run = is_separator[idx_c..]
+                    .iter()
+                    .take_while(|blank| **blank)
+                    .count();
+                // Blanks at either edge can't split anything, so they always go
+                let inner = idx_c > 0 && idx_c + run < width;
+                if inner && run < min_run {
+                    is_separator[idx_c..idx_c + run].fill(false);
+                }
+                idx_c += run.max(1);
+            }
+            is_separator
+        }
+        Boundary::Separator(separator) => (0..width)
+            .map(|idx_c| {
+                !is_blank(idx_c)
+                    && grid
+                        .iter()
+                        .all(|row| row[idx_c] == separator || row[idx_c].is_whitespace())
+            })
+            .collect(),
+    };
+    let mut groups = Vec::new();
+    let mut start = None;
+    for (idx_c, is_separator) in is_separator.into_iter().enumerate() {
+        match (start, is_separator) {
+            (None, false) => start = Some(idx_c),
+            (Some(group_start), true) => {
+                groups.push(group_start..idx_c);
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(group_start) = start {
+        groups.push(group_start..width);
+    }
+    groups
+}
+
+/// One problem of the sheet: numbers combined by an operator
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Group {
+    /// Columns of the sheet the group spans -- token positions for whitespace separated sheets
+    pub columns: Range<usize>,
+    pub operator: Operator,
+    /// Numbers as written, read in whichever `Number` type the sheet is evaluated in
+    pub operands: Vec<String>,
+}
+impl Group {
+    pub fn evaluate<N: Number>(&self) -> Result<N, ArithError> {
+        let values = self
+            .operands
+            .iter()
+            .map(|operand| N::parse(operand).ok_or(ArithError::Unrepresentable))
+            .collect::<Result<Vec<N>, _>>()?;
+        self.operator.reduce(values)
+    }
+}
+/// The operands with the operator between them, e.g. `1 * 24 * 356`
+impl fmt::Display for Group {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (idx, operand) in self.operands.iter().enumerate() {
+            if idx > 0 {
+                write!(f, " {} ", self.operator)?;
+            }
+            write!(f, "{operand}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Every problem of a sheet, from left to right, and how to combine their results
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Sheet {
+    pub groups: Vec<Group>,
+    pub outer: Operator,
+}
+impl Sheet {
+    /// Part one layout: whitespace separated tokens, one problem per token column, with the
+    /// operators on the last row
+    pub fn from_rows(rows: &[Vec<String>]) -> Result<Self, LayoutError> {
+        let (rows, outer) = match rows {
+            [.., inner, last] => match outer_operator(inner, last) {
+                Some(outer) => (&rows[..rows.len() - 1], outer),
+                None => (rows, Operator::Add),
+            },
+            _ => (rows, Operator::Add),
+        };
+        let Some((operators, rows)) = rows.split_last() else {
+            return Ok(Self::empty());
+        };
+        let groups = operators
+            .iter()
+            .enumerate()
+            .map(|(idx_c, token)| {
+                let operands = rows
+                    .iter()
+                    .enumerate()
+                    .map(|(idx_r, row)| match row.get(idx_c) {
+                        Some(cell) => literal(cell.clone()),
+                        None => Err(format!("no number in row {}", idx_r + 1)),
+                    })
+                    .collect::<Result<_, _>>()
+                    .map_err(LayoutError::at(idx_c))?;
+                Ok(Group {
+                    columns: idx_c..idx_c + 1,
+                    operator: operator(token).map_err(LayoutError::at(idx_c))?,
+                    operands,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { groups, outer })
+    }
+
+    /// Part two layout: each column of a group, read top to bottom, is one number, and the
+    /// operator sits somewhere under the group in the last row. Groups are split by `boundary`.
+    pub fn from_columns(grid: &[Vec<char>], boundary: Boundary) -> Result<Self, LayoutError> {
+        // The outer operator's position doesn't matter, so keep it out of the column groups
+        let (inner, _) = split_outer(grid);
+        Self::from_column_groups(grid, column_groups(inner, boundary))
+    }
+
+    /// Part two layout with the columns of each group already known, e.g. from `layout_fields`
+    pub fn from_column_groups(
+        grid: &[Vec<char>],
+        groups: Vec<Range<usize>>,
+    ) -> Result<Self, LayoutError> {
+        let (grid, outer) = split_outer(grid);
+        let Some((operators, rows)) = grid.split_last() else {
+            return Ok(Self::empty());
+        };
+        let groups = groups
+            .into_iter()
+            .enumerate()
+            .map(|(idx, columns)| {
+                let token: String = operators[columns.clone()].iter().collect();
+                let operands = columns
+                    .clone()
+                    .filter_map(|idx_c| {
+                        let digits: String = rows
+                            .iter()
+                            .map(|row| row[idx_c])
+                            .filter(|c| !c.is_whitespace())
+                            .collect();
+                        // A column under a long operator may hold no digits at all
+                        (!digits.is_empty()).then(|| literal(digits))
+                    })
+                    .collect::<Result<_, _>>()
+                    .map_err(LayoutError::at(idx))?;
+                Ok(Group {
+                    columns,
+                    operator: operator(token.trim()).map_err(LayoutError::at(idx))?,
+                    operands,
+                })
+            })
+            .collect::<Result<_, _>>()?;
+        Ok(Self { groups, outer })
+    }
+
+    fn empty() -> Self {
+        Self {
+            groups: Vec::new(),
+            outer: Operator::Add,
+        }
+    }
+
+    /// Results of every group reduced by the outer operator, computed in `N`
+    ///
+    /// A sheet without groups evaluates to zero
+    pub fn evaluate<N: Number>(&self) -> Result<N, EvalError> {
+        let results = self.groups.iter().enumerate().map(evaluate_group).collect();
+        self.combine(results)
+    }
+
+    /// Same as `evaluate`, with the groups evaluated in parallel
+    pub fn par_evaluate<N: Number>(&self) -> Result<N, EvalError> {
+        let results = self
+            .groups
+            .par_iter()
+            .enumerate()
+            .map(evaluate_group)
+            .collect();
+        self.combine(results)
+    }
+
+    /// One line per group with its columns, numbers, operator and result, then how the results
+    /// are combined
+    pub fn explain<N: Number>(&self) -> String {
+        let mut lines: String = self
+            .groups
+            .iter()
+            .enumerate()
+            .map(|(idx, group)| {
+                let result = match group.evaluate::<N>() {
+                    Ok(result) => result.to_string(),
+                    Err(e) => e.to_string(),
+                };
+                format!(
+                    "group {} (columns {}-{}): {group} = {result}\n",
+                    idx + 1,
+                    group.columns.start,
+                    group.columns.end - 1,
+                )
+            })
+            .collect();
+        lines.push_str(&format!("results combined with {}\n", self.outer));
+        lines
+    }
+
+    /// Reduce the group results by the outer operator, failing on the leftmost failed group
+    fn combine<N: Number>(&self, results: Vec<Result<N, EvalError>>) -> Result<N, EvalError> {
+        let results = results.into_iter().collect::<Result<Vec<N>, _>>()?;
+        if results.is_empty() {
+            return Ok(N::ZERO);
+        }
+        self.outer
+            .reduce(results)
+            .map_err(|error| EvalError { group: None, error })
+    }
+}
+
+/// Evaluate the group at 0-indexed `idx`, labelling any error with its position
+fn evaluate_group<N: Number>((idx, group): (usize, &Group)) -> Result<N, EvalError> {
+    group.evaluate().map_err(|error| EvalError {
+        group: Some(idx + 1),
+        error,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Vec<Vec<char>> {
+        to_grid("123 328  51 64 \n 45 64  387 23 \n  6 98  215 314\n*   +   *   +  ".lines())
+    }
+
+    fn operands<T: ToString>(values: &[T]) -> Vec<String> {
+        values.iter().map(T::to_string).collect()
+    }
+
+    #[test]
+    fn test_from_columns() {
+        let sheet = Sheet::from_columns(&sample(), Boundary::Blank).unwrap();
+        assert_eq!(
+            sheet.groups[0],
+            Group {
+                columns: 0..3,
+                operator: Operator::Mul,
+                operands: operands(&[1, 24, 356]),
+            }
+        );
+        let columns: Vec<_> = sheet.groups.iter().map(|g| g.columns.clone()).collect();
+        assert_eq!(columns, vec![0..3, 4..7, 8..11, 12..15]);
+        assert_eq!(sheet.groups[3].operands, operands(&[623, 431, 4]));
+        assert_eq!(sheet.groups[3].evaluate(), Ok(1058usize));
+    }
+
+    #[test]
+    fn test_outer_operator() {
+        // The `*` of the outer row sits in what would otherwise be a separator column
+        let grid = to_grid("12  3\n4   5\n+   *\n   *".lines());
+        let sheet = Sheet::from_columns(&grid, Boundary::Blank).unwrap();
+        assert_eq!(sheet.outer, Operator::Mul);
+        assert_eq!(sheet.groups.len(), 2);
+        assert_eq!(sheet.evaluate(), Ok((14 + 2) * 35usize));
+        // Without the outer row the results are summed
+        let sheet = Sheet::from_columns(&grid[..3], Boundary::Blank).unwrap();
+        assert_eq!(sheet.outer, Operator::Add);
+        assert_eq!(sheet.evaluate(), Ok(14 + 2 + 35usize));
+
+        let rows: Vec<Vec<String>> = ["2 3", "4 5", "+ *", "max"]
+            .iter()
+            .map(|line| line.split_whitespace().map(str::to_owned).collect())
+            .collect();
+        assert_eq!(Sheet::from_rows(&rows).unwrap().evaluate(), Ok(15usize));
+        // A number row above the operators means there is no outer row
+        assert_eq!(Sheet::from_rows(&rows[1..3]).unwrap().outer, Operator::Add);
+    }
+
+    #[test]
+    fn test_transposed() {
+        // Problems on the rows, operators in the last column
+        let lines: Vec<String> = transpose(&sample())
+            .iter()
+            .map(|column| column.iter().collect())
+            .collect();
+        assert_eq!(lines[0], "1  *");
+        let grid = to_grid(lines.iter().map(String::as_str));
+        let grid = transpose_grid(&grid);
+        assert_eq!(grid, sample());
+        assert_eq!(
+            Sheet::from_columns(&grid, Boundary::Blank)
+                .unwrap()
+                .evaluate(),
+            Ok(3263827usize)
+        );
+
+        let rows: Vec<Vec<String>> = ["123 45 6 *", "328 64 98 +"]
+            .iter()
+            .map(|line| line.split_whitespace().map(str::to_owned).collect())
+            .collect();
+        let sheet = Sheet::from_rows(&transpose(&rows)).unwrap();
+        assert_eq!(sheet.evaluate(), Ok(33210 + 490usize));
+    }
+
+    #[test]
+    fn test_fields() {
+        let csv = "123,328, 51,64 \n 45,64 ,387,23 \n  6,98 ,215,314\n*  ,+  ,*  ,+  ";
+        let rows: Vec<Vec<String>> = csv
+            .lines()
+            .map(|line| line.split(',').map(str::to_owned).collect())
+            .collect();
+        let (grid, groups) = layout_fields(&rows);
+        let sheet = Sheet::from_column_groups(&grid, groups).unwrap();
+        assert_eq!(
+            sheet,
+            Sheet::from_columns(&sample(), Boundary::Blank).unwrap()
+        );
+        // Each field is a group, even where a blank column runs through it
+        let rows = vec![
+            vec!["1 2".to_owned(), "3".to_owned()],
+            vec!["+".to_owned(), "*".to_owned()],
+        ];
+        let (grid, groups) = layout_fields(&rows);
+        assert_eq!(groups, vec![0..3, 4..5]);
+        assert_eq!(
+            Sheet::from_column_groups(&grid, groups).unwrap().evaluate(),
+            Ok(3 + 3usize)
+        );
+    }
+
+    #[test]
+    fn test_accumulator() {
+        for input in [
+            "123 328  51 64 \n 45 64  387 23 \n  6 98  215 314\n*   +   *   +  ",
+            "12\t3\n\n4\t5\n+\t*\n\n",
+            "12  3\n4   5\n+   *\n   *",
+            "7\n+",
+            // Signs, decimal points and leading zeros are kept as written
+            "-0 1\n00 .\n.2 5\n5\n+  *",
+            "",
+        ] {
+            let mut accumulator = ColumnAccumulator::default();
+            for line in input.lines() {
+                accumulator.push_line(line);
+            }
+            let expected = Sheet::from_columns(&to_grid(input.lines()), Boundary::Blank).unwrap();
+            assert_eq!(accumulator.finish().unwrap(), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_layout_errors() {
+        let rows = |input: &str| -> Vec<Vec<String>> {
+            input
+                .lines()
+                .map(|line| line.split_whitespace().map(str::to_owned).collect())
+                .collect()
+        };
+        let error = |group, reason: &str| LayoutError {
+            group,
+            reason: reason.to_owned(),
+        };
+        assert_eq!(
+            Sheet::from_rows(&rows("1 x\n+ *")),
+            Err(error(2, "\"x\" is not a number"))
+        );
+        assert_eq!(
+            Sheet::from_rows(&rows("1 2\n+ %")),
+            Err(error(2, "\"%\" is not an operator"))
+        );
+        assert_eq!(
+            Sheet::from_rows(&rows("1 2\n3\n+ *")),
+            Err(error(2, "no number in row 2"))
+        );
+        let grid = to_grid("12 3\n4  x\n+  *".lines());
+        assert_eq!(
+            Sheet::from_columns(&grid, Boundary::Blank),
+            Err(error(2, "\"3x\" is not a number"))
+        );
+        let mut accumulator = ColumnAccumulator::default();
+        for line in ["12 3", "4  5", "+  %"] {
+            accumulator.push_line(line);
+        }
+        assert_eq!(
+            accumulator.finish(),
+            Err(error(2, "\"%\" is not an operator"))
+        );
+        let nines = format!("{}+", "9\n".repeat(40));
+        for (input, reason) in [
+            ("1\n-\n2\n+", "'-' can't be part of a number here"),
+            ("-\n.\n+", "\"-.\" is not a number"),
+            (nines.as_str(), "more digits than fit in 128 bits"),
+        ] {
+            let mut accumulator = ColumnAccumulator::default();
+            for line in input.lines() {
+                accumulator.push_line(line);
+            }
+            assert_eq!(accumulator.finish(), Err(error(1, reason)), "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_explain() {
+        let mut sheet = Sheet::from_columns(&sample(), Boundary::Blank).unwrap();
+        sheet.groups[1].operator = Operator::Sub;
+        let explained = sheet.explain::<usize>();
+        let lines: Vec<&str> = explained.lines().collect();
+        assert_eq!(lines[0], "group 1 (columns 0-2): 1 * 24 * 356 = 8544");
+        assert_eq!(lines[1], "group 2 (columns 4-6): 369 - 248 - 8 = 113");
+        assert_eq!(lines[4], "results combined with +");
+        sheet.groups[1].operands = operands(&[1, 2]);
+        assert!(
+            sheet
+                .explain::<usize>()
+                .contains("1 - 2 = result too small")
+        );
+    }
+
+    #[test]
+    fn test_boundary() {
+        // Numbers spaced out inside their groups, with wider gaps between the groups
+        let input = "1 2   3 4\n5 6   7 8\n+     *  ";
+        let grid = to_grid(input.lines());
+        assert_eq!(column_groups(&grid, Boundary::Blank).len(), 4);
+        assert_eq!(column_groups(&grid, Boundary::Spaces(2)), vec![0..3, 6..9]);
+        let spaced = Sheet::from_columns(&grid, Boundary::Spaces(2)).unwrap();
+        assert_eq!(spaced.evaluate(), Ok(15 + 26 + 37 * 48usize));
+
+        let input = "12|3 4\n 5|6 7\n+ |*  ";
+        let grid = to_grid(input.lines());
+        let boundary = Boundary::Separator('|');
+        assert_eq!(column_groups(&grid, boundary), vec![0..2, 3..6]);
+        let sheet = Sheet::from_columns(&grid, boundary).unwrap();
+        assert_eq!(sheet.evaluate(), Ok(1 + 25 + 36 * 47usize));
+
+        for (input, boundary) in [
+            ("1 2   3 4\n5 6   7 8\n+     *  ", Boundary::Spaces(2)),
+            ("12|3 4\n 5|6 7\n+ |*  ", boundary),
+        ] {
+            let mut accumulator = ColumnAccumulator::with_boundary(boundary);
+            for line in input.lines() {
+                accumulator.push_line(line);
+            }
+            let expected = Sheet::from_columns(&to_grid(input.lines()), boundary).unwrap();
+            assert_eq!(accumulator.finish().unwrap(), expected, "{input:?}");
+        }
+    }
+
+    #[test]
+    fn test_checked() {
+        let mut sheet = Sheet::from_columns(&sample(), Boundary::Blank).unwrap();
+        sheet.groups[2].operands = operands(&[usize::MAX / 2, 3]);
+        assert_eq!(
+            sheet.evaluate::<usize>(),
+            Err(EvalError {
+                group: Some(3),
+                error: ArithError::Overflow
+            })
+        );
+        // Wider accumulation fits it
+        let expected = (usize::MAX / 2) as u128 * 3 + 8544 + 625 + 1058;
+        assert_eq!(sheet.evaluate::<u128>(), Ok(expected));
+
+        sheet.groups[2].operands = operands(&[usize::MAX]);
+        assert_eq!(sheet.evaluate::<usize>().unwrap_err().group, None);
+        // The leftmost failure is reported, whichever group fails first in parallel
+        sheet.groups[0].operands = operands(&[1, 0]);
+        sheet.groups[0].operator = Operator::Div;
+        for _ in 0..10 {
+            assert_eq!(sheet.par_evaluate::<usize>().unwrap_err().group, Some(1));
+        }
+        assert_eq!(
+            Operator::Sub.reduce([3usize, 4]),
+            Err(ArithError::Underflow)
+        );
+        assert_eq!(
+            Operator::Div.reduce([3usize, 0]),
+            Err(ArithError::DivideByZero)
+        );
+        assert_eq!(
+            Operator::Concat.reduce([u128::MAX / 10, 9]),
+            Err(ArithError::Overflow)
+        );
+        assert_eq!(
+            Operator::Add.reduce(Vec::<usize>::new()),
+            Err(ArithError::NoNumbers)
+        );
+    }
+
+    #[test]
+    fn test_number_types() {
+        let rows: Vec<Vec<String>> = ["-3 2.5 0.1", "4 2 0.2", "* + +"]
+            .iter()
+            .map(|line| line.split_whitespace().map(str::to_owned).collect())
+            .collect();
+        let sheet = Sheet::from_rows(&rows).unwrap();
+        assert_eq!(
+            sheet.evaluate::<usize>().unwrap_err(),
+            EvalError {
+                group: Some(1),
+                error: ArithError::Unrepresentable
+            }
+        );
+        assert_eq!(sheet.evaluate::<i64>().unwrap_err().group, Some(2));
+        // Exact fractions don't pick up rounding errors
+        assert_eq!(sheet.evaluate::<Rational>(), Ok(Ratio::new(-36, 5)));
+        let float = sheet.evaluate::<f64>().unwrap();
+        assert!((float - -7.2).abs() < 1e-9);
+        assert_eq!(Rational::parse("-1.25"), Some(Ratio::new(-5, 4)));
+
+        // Part two reads signs and points down the columns
+        let grid = to_grid("- 1\n2 .\n5 5\n* +".lines());
+        assert_eq!(
+            Sheet::from_columns(&grid, Boundary::Blank)
+                .unwrap()
+                .evaluate(),
+            Ok(-23.5)
+        );
+        assert_eq!(Operator::Concat.reduce([-1i64, 23]), Ok(-123));
+        assert_eq!(Operator::Concat.reduce([1.5, 2.0]), Ok(17.0));
+        assert_eq!(
+            Operator::Concat.reduce([1i64, -2]),
+            Err(ArithError::NotWhole)
+        );
+        assert_eq!(Operator::Min.reduce([2.5, -1.0, 3.0]), Ok(-1.0));
+    }
+}
@@ -0,0 +1,305 @@
+//! Fewest button presses to set up each machine, for its indicator lights in part one and its
+//! joltage counters in part two
+use aoc_common::{
+    cancel,
+    gf2::{self, Equation},
+    input::LineError,
+    solver::{SolveError, Solver},
+};
+use good_lp::{
+    Expression, Solution as _, SolverModel as _, constraint, default_solver, variable, variables,
+};
+use rayon::prelude::*;
+use tracing::warn;
+
+/// Machine
+#[derive(Debug, Clone)]
+struct Machine {
+    /// Light diagram
+    light_diagram: IndicatorLights,
+    /// Button wiring schematics
+    buttons: Vec<Button>,
+    /// Joltage requirements
+    joltage_requirements: Vec<u32>, // This will change
+}
+impl Machine {
+    /// Parse `[lights] (buttons)... {joltages}`, failing with the reason
+    pub fn from_line(line: &str) -> Result<Machine, String> {
+        let line = line.trim();
+        // Parse indicator lights
+        let (lights, rest) = line
+            .strip_prefix('[')
+            .and_then(|rest| rest.split_once(']'))
+            .ok_or("expected indicator lights in [...]")?;
+        let light_diagram = IndicatorLights::from_str(lights)?;
+
+        let (buttons, joltages) = rest
+            .split_once('{')
+            .ok_or("expected joltage requirements in {...}")?;
+        let buttons = buttons
+            .split_whitespace()
+            .map(Button::from_str)
+            .collect::<Result<_, _>>()?;
+
+        let joltage_requirements = joltages
+            .strip_suffix('}')
+            .ok_or("expected joltage requirements to end with }")?
+            .split(",")
+            .map(|v| v.parse().map_err(|e| format!("joltage {v:?}: {e}")))
+            .collect::<Result<_, _>>()?;
+        Ok(Self {
+            light_diagram,
+            buttons,
+            joltage_requirements,
+        })
+    }
+
+    fn build_equations(&self) -> Vec<Equation> {
+        self.light_diagram
+            .inner
+            .iter()
+            .enumerate()
+            .map(|(light_idx, status)| {
+                let mut row = 0u64;
+
+                for (btn_idx, btn) in self.buttons.iter().enumerate() {
+                    if btn.lights_affected.contains(&light_idx) {
+                        row |= 1 << btn_idx;
+                    }
+                }
+                let rhs = matches!(status, LightStatus::On);
+                Equation { row, rhs }
+            })
+            .collect()
+    }
+
+    fn build_joltage_equations(&self) -> Vec<(u64, u32)> {
+        self.joltage_requirements
+            .iter()
+            .enumerate()
+            .map(|(idx, target)| {
+                let mut row = 0u64;
+                for (j, button) in self.buttons.iter().enumerate() {
+                    if button.lights_affected.contains(&idx) {
+                        row |= 1 << j;
+                    }
+                }
+                (row, *target)
+            })
+            .collect()
+    }
+
+    /// Fewest presses that light the indicators, or `None` if no presses do
+    pub fn find_min_button_presses(&self) -> Option<usize> {
+        let solutions = gf2::solve(self.build_equations(), self.buttons.len())?;
+        Some(solutions.fewest_ones().count_ones() as usize)
+    }
+
+    /// Fewest presses that meet the joltage requirements, or `None` if no presses do
+    pub fn find_min_button_presses_2(&self) -> Option<usize> {
+        let eqs = self.build_joltage_equations();
+        let n_buttons = self.buttons.len();
+
+        // Define variables
+        let mut vars = variables!();
+        // They are all non-negative
+        let x: Vec<_> = (0..n_buttons)
+            .map(|_| vars.add(variable().min(0).integer()))
+            .collect();
+        // Opjective is to minimize the total button presses
+        let mut objective = Expression::from(0);
+        for v in &x {
+            objective += *v;
+        }
+
+        // Build the problem
+        let mut problem = vars.minimise(objective).using(default_solver);
+
+        // Add constraints
+        for (row, rhs) in eqs {
+            let mut expr = Expression::from(0);
+            for (j, x_j) in x.iter().enumerate() {
+                if (row >> j) & 1 == 1 {
+                    expr += x_j;
+                }
+            }
+            problem = problem.with(constraint!(expr == rhs as f64));
+        }
+
+        // solve
+        let solution = problem.solve().ok()?;
+
+        // Extract integer solution
+        Some(x.iter().map(|v| solution.value(*v).round() as usize).sum())
+    }
+}
+
+/// Indicator lights
+#[derive(Debug, Clone)]
+struct IndicatorLights {
+    inner: Vec<LightStatus>,
+}
+impl IndicatorLights {
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        let inner = s
+            .chars()
+            .map(LightStatus::try_from)
+            .collect::<Result<_, _>>()?;
+        Ok(Self { inner })
+    }
+}
+
+/// Light statuses
+#[derive(Debug, Clone)]
+enum LightStatus {
+    On,
+    Off,
+}
+impl TryFrom<char> for LightStatus {
+    type Error = String;
+
+    fn try_from(value: char) -> Result<Self, String> {
+        match value {
+            '#' => Ok(Self::On),
+            '.' => Ok(Self::Off),
+            _ => Err(format!("{value:?} is not a light, expected '#' or '.'")),
+        }
+    }
+}
+
+/// Button
+#[derive(Debug, Clone)]
+struct Button {
+    lights_affected: Vec<usize>,
+}
+impl Button {
+    pub fn from_str(s: &str) -> Result<Self, String> {
+        // Comes in the form of (...), where ... can be any number of buttons
+        let inner = s
+            .trim()
+            .strip_prefix('(')
+            .and_then(|s| s.strip_suffix(')'))
+            .ok_or_else(|| format!("expected a button in (...), found {s:?}"))?;
+        let lights_affected = inner
+            .split(",")
+            .map(|c| c.parse().map_err(|e| format!("button light {c:?}: {e}")))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { lights_affected })
+    }
+}
+
+/// Every machine, one per line of the input
+#[derive(Debug, Clone)]
+pub struct Factory {
+    machines: Vec<Machine>,
+}
+
+/// The example from the puzzle text, run with `--example`
+pub const EXAMPLE: &str = include_str!("../examples/example.txt");
+
+impl Solver for Factory {
+    type Output = usize;
+    type Error = LineError;
+    const EXAMPLE: &'static str = EXAMPLE;
+
+    fn parse(s: &str) -> Result<Self, LineError> {
+        let machines = s
+            .lines()
+            .enumerate()
+            .map(|(idx, line)| Machine::from_line(line).map_err(|e| LineError::new(idx + 1, e)))
+            .collect::<Result<_, _>>()?;
+        Ok(Self { machines })
+    }
+
+    fn part_one(&self) -> Result<usize, SolveError> {
+        self.machines
+            .par_iter()
+            .enumerate()
+            .map(|(idx, machine)| {
+                machine
+                    .find_min_button_presses()
+                    .ok_or_else(|| unsolvable(idx))
+            })
+            .sum()
+    }
+
+    /// Machines are solved one at a time, as the LP solver is not safe to share across threads
+    fn part_two(&self) -> Result<usize, SolveError> {
+        let mut presses = 0;
+        for (done, machine) in self.machines.iter().enumerate() {
+            if cancel::is_cancelled() {
+                warn!(
+                    presses,
+                    "Stopped early, after {done} of {} machines",
+                    self.machines.len()
+                );
+                break;
+            }
+            presses += machine
+                .find_min_button_presses_2()
+                .ok_or_else(|| unsolvable(done))?;
+        }
+        Ok(presses)
+    }
+}
+
+/// No presses of the buttons of the machine at 0-indexed `idx` reach its target
+fn unsolvable(idx: usize) -> SolveError {
+    SolveError::new(format!("machine {} has no solution", idx + 1))
+}
+
+pub fn part_one(s: &str) -> anyhow::Result<usize> {
+    Ok(Factory::parse(s)?.part_one()?)
+}
+
+pub fn part_two(s: &str) -> anyhow::Result<usize> {
+    Ok(Factory::parse(s)?.part_two()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one() {
+        let output = part_one(EXAMPLE).unwrap();
+
+        // TODO fill this out
+        assert_eq!(output, 7);
+    }
+
+    #[test]
+    fn test_two() {
+        let output = part_two(EXAMPLE).unwrap();
+
+        // TODO fill this out
+        assert_eq!(output, 33);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = |line| Factory::parse(line).unwrap_err().to_string();
+        assert_eq!(
+            error("[.#x] (0) {1}"),
+            "line 1: 'x' is not a light, expected '#' or '.'"
+        );
+        assert_eq!(
+            error("[.#] (0,a) {1}"),
+            "line 1: button light \"a\": invalid digit found in string"
+        );
+        assert_eq!(
+            error("[.#] (0) 1,2"),
+            "line 1: expected joltage requirements in {...}"
+        );
+    }
+
+    #[test]
+    fn test_unsolvable() {
+        // No button reaches the second light or counter
+        let factory =
+            Factory::parse("[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}\n[.#] (0) {1,1}")
+                .unwrap();
+        assert_eq!(factory.part_one(), Err(unsolvable(1)));
+        assert_eq!(factory.part_two(), Err(unsolvable(1)));
+    }
+}
@@ -1,17 +1,35 @@
 //! Command line executable for running part one and part two
-use std::time::Instant;
-
-use clap::Parser;
-use good_lp::{
-    Expression, Solution as _, SolverModel as _, constraint, default_solver, variable, variables,
+use std::{process::ExitCode, time::Instant};
+
+use anyhow::Context;
+use aoc_common::{
+    cancel::{self, TimeoutArgs},
+    input::InputArgs,
+    log::LogArgs,
+    output::{self, OutputArgs, Record},
+    solver::Solver,
+    threads::ThreadArgs,
 };
+use clap::Parser;
+use day_ten::Factory;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Input file
-    #[arg(short)]
-    input_file: String,
+    #[command(flatten)]
+    input: InputArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(flatten)]
+    log: LogArgs,
+
+    #[command(flatten)]
+    threads: ThreadArgs,
+
+    #[command(flatten)]
+    timeout: TimeoutArgs,
 
     #[command(subcommand)]
     part: Part,
@@ -23,319 +41,38 @@ enum Part {
     Part2,
 }
 
-fn main() {
+fn main() -> ExitCode {
+    output::exit(try_main())
+}
+
+fn try_main() -> anyhow::Result<()> {
     let args = Args::parse();
+    args.log.init();
+    args.threads.init()?;
+    args.timeout.init();
 
     // Read to a string
-    let s = std::fs::read_to_string(args.input_file).expect("Failed to read file");
+    let s = args.input.read_to_string(Factory::EXAMPLE)?;
 
     let start = Instant::now();
-    let answer = match args.part {
-        Part::Part1 => part_one(&s),
-        Part::Part2 => part_two(&s),
-    };
-
-    println!("{:?}", answer);
-    println!("Completed in {:?}", start.elapsed());
-}
-
-/// Machine
-#[derive(Debug, Clone)]
-struct Machine {
-    /// Light diagram
-    light_diagram: IndicatorLights,
-    /// Button wiring schematics
-    buttons: Vec<Button>,
-    /// Joltage requirements
-    joltage_requirements: Vec<u32>, // This will change
-}
-impl Machine {
-    pub fn from_line(line: &str) -> Machine {
-        let line = line.trim();
-        // Parse indicator lights
-        let idx_start = line.find("[").unwrap();
-        let idx_stop = line.find("]").unwrap();
-        let light_diagram = IndicatorLights::from_str(&line[idx_start + 1..idx_stop]);
-
-        let idx_start = idx_stop + 2;
-        let idx_stop = line.find("{").unwrap() - 2;
-        let buttons = line[idx_start..=idx_stop]
-            .split_whitespace()
-            .map(Button::from_str)
-            .collect();
-
-        let idx_start = idx_stop + 3;
-        let idx_end = line.len() - 1;
-        let joltage_requirements = line[idx_start..idx_end]
-            .split(",")
-            .map(|v| v.parse().unwrap())
-            .collect();
-        Self {
-            light_diagram,
-            buttons,
-            joltage_requirements,
-        }
-    }
-
-    fn build_equations(&self) -> Vec<Equation> {
-        self.light_diagram
-            .inner
-            .iter()
-            .enumerate()
-            .map(|(light_idx, status)| {
-                let mut row = 0u64;
-
-                for (btn_idx, btn) in self.buttons.iter().enumerate() {
-                    if btn.lights_affected.contains(&light_idx) {
-                        row |= 1 << btn_idx;
-                    }
-                }
-                let rhs = matches!(status, LightStatus::On);
-                Equation { row, rhs }
-            })
-            .collect()
-    }
-
-    fn build_joltage_equations(&self) -> Vec<(u64, u32)> {
-        self.joltage_requirements
-            .iter()
-            .enumerate()
-            .map(|(idx, target)| {
-                let mut row = 0u64;
-                for (j, button) in self.buttons.iter().enumerate() {
-                    if button.lights_affected.contains(&idx) {
-                        row |= 1 << j;
-                    }
-                }
-                (row, *target)
-            })
-            .collect()
-    }
-
-    /// Use Gaussian elimination to simplify the equations
-    ///
-    /// Trying to solve Ax = b (mod 2)
-    fn gaussian_elim_gf2(mut eqs: Vec<Equation>, n_buttons: usize) -> Option<(u64, Vec<u64>)> {
-        // Bookkeeping -- which row is the pivot off the column `col`
-        let mut pivot_col = vec![None; n_buttons];
-        // Current pivot row during elimination
-        let mut row = 0;
-
-        // Iterate through the various columns trying to remove redundent scenarios
-        for (col, item) in pivot_col.iter_mut().enumerate() {
-            // Look for a row >= row where variable col appears with coefficient 1
-            let pivot = (row..eqs.len()).find(|&r| (eqs[r].row >> col) & 1 == 1);
-            if pivot.is_none() {
-                // If non exist, this variable is free, because this button does not affect any of
-                // the outcomes -- skip this column
-                continue;
-            }
-            let pivot = pivot.unwrap();
-
-            // Standard Gaussian elimination -- swap pivot row upward, record where the pivot lives
-            eqs.swap(row, pivot);
-            *item = Some(row);
-
-            // Eliminate this button from all other rows
-            for r in 0..eqs.len() {
-                // if row r has a 1 in this pivot column, then subtract pivot row from it -- this
-                // zeros out column col in row r and preserves the equation's validity
-                if r != row && ((eqs[r].row >> col) & 1) == 1 {
-                    eqs[r].row ^= eqs[row].row;
-                    eqs[r].rhs ^= eqs[row].rhs;
-                }
-            }
-            row += 1;
-        }
-
-        // Consistency check -- detects 0 == 1 mod 2
-        for eq in &eqs {
-            if eq.row == 0 && eq.rhs {
-                return None; // No solution
-            }
-        }
-        // Particular solution (set free vars = 0)
-        // Build one concrete solution x
-        let mut particular = 0u64;
-        for (col, item) in pivot_col.iter().enumerate() {
-            if let Some(r) = item
-                && eqs[*r].rhs
-            {
-                particular |= 1 << col;
-            }
-        }
-
-        // Nullspace basis
-        let mut nullspace = Vec::new();
-        for free_col in 0..n_buttons {
-            if pivot_col[free_col].is_none() {
-                // Start with free variable == 1, all others == 0
-                let mut vec = 1u64 << free_col;
-                // Enforces A vec = 0 -- turning on this free variable forces some pivot variables
-                // to flip, so overall effect is no lights change
-                for (col, item) in pivot_col.iter().enumerate() {
-                    if let Some(r) = item
-                        && ((eqs[*r].row >> free_col) & 1) == 1
-                    {
-                        vec |= 1 << col;
-                    }
-                }
-                nullspace.push(vec);
-            }
-        }
-
-        Some((particular, nullspace))
-    }
-
-    pub fn find_min_button_presses(&self) -> usize {
-        let equations = self.build_equations();
-        let n_buttons = self.buttons.len();
-
-        let (particular, nullspace) =
-            Self::gaussian_elim_gf2(equations, n_buttons).expect("Machine has no solution");
-
-        let mut best = particular.count_ones() as usize;
-        let k = nullspace.len();
-
-        // Brute force nullspace (usually small)
-        for mask in 0..(1u64 << k) {
-            let mut x = particular;
-            for (i, nspace) in nullspace.iter().enumerate() {
-                if (mask >> i) & 1 == 1 {
-                    x ^= nspace;
-                }
-            }
-            best = best.min(x.count_ones() as usize);
-        }
+    let solver = Factory::parse(&s).with_context(|| args.input.name().to_owned())?;
+    let parse = start.elapsed();
 
-        best
-    }
-
-    pub fn find_min_button_presses_2(&self) -> usize {
-        let eqs = self.build_joltage_equations();
-        let n_buttons = self.buttons.len();
-
-        // Define variables
-        let mut vars = variables!();
-        // They are all non-negative
-        let x: Vec<_> = (0..n_buttons)
-            .map(|_| vars.add(variable().min(0).integer()))
-            .collect();
-        // Opjective is to minimize the total button presses
-        let mut objective = Expression::from(0);
-        for v in &x {
-            objective += *v;
-        }
-
-        // Build the problem
-        let mut problem = vars.minimise(objective).using(default_solver);
-
-        // Add constraints
-        for (row, rhs) in eqs {
-            let mut expr = Expression::from(0);
-            for (j, x_j) in x.iter().enumerate() {
-                if (row >> j) & 1 == 1 {
-                    expr += x_j;
-                }
-            }
-            problem = problem.with(constraint!(expr == rhs as f64));
-        }
-
-        // solve
-        let solution = problem.solve().expect("LP infeasible");
-
-        // Extract integer solution
-        x.iter().map(|v| solution.value(*v).round() as usize).sum()
-    }
-}
-
-/// Indicator lights
-#[derive(Debug, Clone)]
-struct IndicatorLights {
-    inner: Vec<LightStatus>,
-}
-impl IndicatorLights {
-    pub fn from_str(s: &str) -> Self {
-        let inner = s.chars().map(|c| c.into()).collect();
-        Self { inner }
-    }
-}
-
-/// Light statuses
-#[derive(Debug, Clone)]
-enum LightStatus {
-    On,
-    Off,
-}
-impl From<char> for LightStatus {
-    fn from(value: char) -> Self {
-        match value {
-            '#' => Self::On,
-            '.' => Self::Off,
-            _ => panic!("Not valid"),
-        }
-    }
-}
-
-/// Button
-#[derive(Debug, Clone)]
-struct Button {
-    lights_affected: Vec<usize>,
-}
-impl Button {
-    pub fn from_str(s: &str) -> Self {
-        // Assume comes in the form of (...), where ... can be any number of buttons
-        // Remove the ends
-        let mut s = s.trim();
-        s = &s[1..s.len() - 1];
-        let lights_affected = s.split(",").map(|c| c.parse().unwrap()).collect();
-        Self { lights_affected }
-    }
-}
-
-/// One equtions: (row * x) = rhs (mod 2)
-#[derive(Debug, Clone)]
-struct Equation {
-    row: u64, // assuming <=64 buttons, bitset
-    rhs: bool,
-}
-
-fn part_one(s: &str) -> usize {
-    s.lines().map(Machine::from_line).fold(0, |accum, machine| {
-        accum + machine.find_min_button_presses()
-    })
-}
-
-fn part_two(s: &str) -> usize {
-    s.lines().map(Machine::from_line).fold(0, |accum, machine| {
-        accum + machine.find_min_button_presses_2()
-    })
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    /// Function to modify for input to test
-    fn input_one() -> &'static str {
-        "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
-[...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
-[.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}"
-    }
-
-    #[test]
-    fn test_one() {
-        let output = part_one(input_one());
-
-        // TODO fill this out
-        assert_eq!(output, 7);
-    }
-
-    #[test]
-    fn test_two() {
-        let output = part_two(input_one());
-
-        // TODO fill this out
-        assert_eq!(output, 33);
-    }
+    let start = Instant::now();
+    let (part, answer) = match args.part {
+        Part::Part1 => (1, solver.part_one()?),
+        Part::Part2 => (2, solver.part_two()?),
+    };
+    // A solver stopped early only has a partial answer
+    cancel::check()?;
+
+    args.output.print(&Record {
+        day: 10,
+        part: part.into(),
+        answer: answer.to_string(),
+        parse,
+        solve: start.elapsed(),
+    });
+
+    Ok(())
 }
@@ -0,0 +1,700 @@
+//! Largest joltage from turning on batteries in each bank, two of them for part one and twelve
+//! for part two
+use std::{fmt, io::BufRead};
+
+use aoc_common::solver::{SolveError, Solver};
+use num_bigint::BigUint;
+
+pub mod pick;
+
+/// Number of batteries the puzzle turns on in each bank for part one
+pub const PART_ONE_DIGITS: usize = 2;
+/// Number of batteries the puzzle turns on in each bank for part two
+pub const PART_TWO_DIGITS: usize = 12;
+
+/// Map every line holding batteries, along with its 1-indexed number, skipping blank lines and
+/// lines of only separators
+pub fn parse_input<F, T>(file: impl BufRead, f: F) -> Result<Vec<(usize, T)>, ParseError>
+where
+    F: Fn(&str) -> Result<T, BankError>,
+{
+    let mut parsed = Vec::new();
+    for (idx, line) in file.lines().enumerate() {
+        let line = line.map_err(|e| ParseError::Read {
+            line: idx + 1,
+            reason: e.to_string(),
+        })?;
+        if !line.chars().any(char::is_alphanumeric) {
+            continue;
+        }
+        let item = f(&line).map_err(|e| match e {
+            BankError::NotBattery(character) => ParseError::NotBattery {
+                line: idx + 1,
+                character,
+            },
+            BankError::Base(base) => ParseError::Base(base),
+        })?;
+        parsed.push((idx + 1, item));
+    }
+    Ok(parsed)
+}
+
+/// Error from reading the banks, with 1-indexed lines
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseError {
+    /// Character that isn't a battery
+    NotBattery { line: usize, character: char },
+    /// The line could not be read, for the reason given
+    Read { line: usize, reason: String },
+    /// Base that digits can't be written in
+    Base(u32),
+}
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotBattery { line, character } => {
+                write!(f, "line {line}: expected a battery, found {character:?}")
+            }
+            Self::Read { line, reason } => write!(f, "line {line}: failed to read: {reason}"),
+            Self::Base(base) => write!(f, "{}", BankError::Base(*base)),
+        }
+    }
+}
+impl std::error::Error for ParseError {}
+
+/// Why a line is not a bank of batteries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BankError {
+    /// Character that isn't a battery
+    NotBattery(char),
+    /// Base outside 2 to 36, the bases a digit can be written in
+    Base(u32),
+}
+impl fmt::Display for BankError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotBattery(character) => write!(f, "expected a battery, found {character:?}"),
+            Self::Base(base) => write!(f, "base {base} is not between 2 and 36"),
+        }
+    }
+}
+impl std::error::Error for BankError {}
+
+/// Why a bank has no joltage, or the total can't be added up
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JoltageError {
+    /// The bank has too few batteries to turn on `k` of them at least `gap` positions apart
+    TooShort { bank: String, k: usize, gap: usize },
+    /// The total doesn't fit the type it is added up in
+    Overflow,
+}
+impl fmt::Display for JoltageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooShort { bank, k, gap } => {
+                let len = bank.chars().count();
+                write!(f, "bank {bank}: cannot turn on {k} of its {len} batteries")?;
+                if *gap > 1 {
+                    write!(f, " {gap} apart")?;
+                }
+                Ok(())
+            }
+            Self::Overflow => write!(f, "the joltage overflows {} bits", ReturnType::BITS),
+        }
+    }
+}
+impl std::error::Error for JoltageError {}
+
+/// Joltage of a bank, or the total of several
+pub trait Joltage: Sized + Ord + fmt::Display {
+    fn zero() -> Self;
+    /// `self` with `digit` written after it in `base`, or `None` if it no longer fits
+    fn push_digit(self, digit: usize, base: u32) -> Option<Self>;
+    fn add(self, other: Self) -> Option<Self>;
+}
+impl Joltage for usize {
+    fn zero() -> Self {
+        0
+    }
+    fn push_digit(self, digit: usize, base: u32) -> Option<Self> {
+        self.checked_mul(base as usize)?.checked_add(digit)
+    }
+    fn add(self, other: Self) -> Option<Self> {
+        self.checked_add(other)
+    }
+}
+impl Joltage for BigUint {
+    fn zero() -> Self {
+        BigUint::ZERO
+    }
+    fn push_digit(self, digit: usize, base: u32) -> Option<Self> {
+        Some(self * base + digit)
+    }
+    fn add(self, other: Self) -> Option<Self> {
+        Some(self + other)
+    }
+}
+
+/// Which joltage to pick out of each bank
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Goal {
+    Largest,
+    Smallest,
+    /// Smallest, starting with a zero only if every choice does
+    SmallestNoLeadingZero,
+}
+impl Goal {
+    /// Rank of `digit` in the `first` or a later place of the joltage, the lowest being the best
+    fn rank(self, digit: usize, first: bool) -> isize {
+        match self {
+            Self::Largest => -(digit as isize),
+            Self::SmallestNoLeadingZero if first && digit == 0 => isize::MAX,
+            Self::Smallest | Self::SmallestNoLeadingZero => digit as isize,
+        }
+    }
+}
+
+/// Bank of batteries, each a digit in `base`
+#[derive(Debug, Clone)]
+pub struct BatteryBank {
+    batteries: String,
+    base: u32,
+}
+impl BatteryBank {
+    /// Batteries of a line, skipping whitespace and separators, failing on the first character
+    /// that is neither or a base that digits can't be written in
+    pub fn parse(line: &str, base: u32) -> Result<Self, BankError> {
+        if !(2..=36).contains(&base) {
+            return Err(BankError::Base(base));
+        }
+        let mut batteries = String::with_capacity(line.len());
+        for c in line.chars() {
+            if c.is_digit(base) {
+                batteries.push(c);
+            } else if c.is_alphanumeric() {
+                return Err(BankError::NotBattery(c));
+            }
+        }
+        Ok(Self { batteries, base })
+    }
+
+    /// Find the best digit you can get for `goal`, made out of k options, failing if the bank
+    /// has fewer than `k` batteries or the joltage doesn't fit
+    pub fn find_k<J: Joltage>(&self, k: usize, goal: Goal) -> Result<J, JoltageError> {
+        self.find_k_with_indices(k, goal)
+            .map(|(joltage, _)| joltage)
+    }
+
+    /// Same as `find_k`, along with the positions of the chosen batteries
+    pub fn find_k_with_indices<J: Joltage>(
+        &self,
+        k: usize,
+        goal: Goal,
+    ) -> Result<(J, Vec<usize>), JoltageError> {
+        let digits = self.digits();
+        let indices = pick::pick_k(&digits, k, goal).ok_or_else(|| self.too_short(k, 1))?;
+        Ok((self.joltage(&digits, &indices)?, indices))
+    }
+
+    /// Same as `find_k_with_indices`, with the chosen batteries at least `gap` positions apart
+    pub fn find_k_spaced_with_indices<J: Joltage>(
+        &self,
+        k: usize,
+        gap: usize,
+        goal: Goal,
+    ) -> Result<(J, Vec<usize>), JoltageError> {
+        let digits = self.digits();
+        let indices =
+            pick::pick_k_spaced(&digits, k, gap, goal).ok_or_else(|| self.too_short(k, gap))?;
+        Ok((self.joltage(&digits, &indices)?, indices))
+    }
+
+    /// Same as `find_k`, searching recursively
+    pub fn find_k_recursive<J: Joltage>(&self, k: usize, goal: Goal) -> Result<J, JoltageError> {
+        let digits = self.digits();
+        let indices =
+            pick::pick_k_recursive(&digits, k, goal).ok_or_else(|| self.too_short(k, 1))?;
+        self.joltage(&digits, &indices)
+    }
+
+    /// Number read off the chosen digits, in order
+    fn joltage<J: Joltage>(&self, digits: &[usize], indices: &[usize]) -> Result<J, JoltageError> {
+        indices
+            .iter()
+            .try_fold(J::zero(), |acc, idx| {
+                acc.push_digit(digits[*idx], self.base)
+            })
+            .ok_or(JoltageError::Overflow)
+    }
+
+    fn too_short(&self, k: usize, gap: usize) -> JoltageError {
+        JoltageError::TooShort {
+            bank: self.batteries.clone(),
+            k,
+            gap,
+        }
+    }
+
+    fn digits(&self) -> Vec<usize> {
+        self.batteries
+            .chars()
+            // Only digits in the base, which `parse` checked, make it into the bank
+            .map(|c| c.to_digit(self.base).unwrap() as usize)
+            .collect()
+    }
+}
+
+// TODO -- Update this with the return type
+pub type ReturnType = usize;
+pub type VectorType = BatteryBank;
+pub type VectorType2 = VectorType;
+
+/// Map a line to a VectorType
+pub fn map_one(input: &str, base: u32) -> Result<VectorType, BankError> {
+    BatteryBank::parse(input, base)
+}
+
+/// Map a line to a VectorType
+pub fn map_two(input: &str, base: u32) -> Result<VectorType2, BankError> {
+    map_one(input, base)
+}
+
+/// How to pick the batteries of each bank
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Number of batteries to turn on
+    pub k: usize,
+    pub recursive: bool,
+    pub explain: bool,
+    pub top: Option<usize>,
+    /// Fewest positions between two batteries turned on
+    pub gap: usize,
+    pub goal: Goal,
+}
+impl Options {
+    /// The largest joltage of `k` batteries, picked with the stack as in the puzzle
+    pub fn new(k: usize) -> Self {
+        Self {
+            k,
+            recursive: false,
+            explain: false,
+            top: None,
+            gap: 1,
+            goal: Goal::Largest,
+        }
+    }
+
+    fn joltage<J: Joltage>(&self, bank: &BatteryBank) -> Result<J, JoltageError> {
+        if self.recursive {
+            bank.find_k_recursive(self.k, self.goal)
+        } else {
+            self.joltage_with_indices(bank).map(|(joltage, _)| joltage)
+        }
+    }
+
+    /// Joltage of the bank along with the positions of the chosen batteries
+    fn joltage_with_indices<J: Joltage>(
+        &self,
+        bank: &BatteryBank,
+    ) -> Result<(J, Vec<usize>), JoltageError> {
+        if self.gap > 1 {
+            bank.find_k_spaced_with_indices(self.k, self.gap, self.goal)
+        } else {
+            bank.find_k_with_indices(self.k, self.goal)
+        }
+    }
+}
+
+/// Each bank with its joltage, and a line marking the chosen batteries under it
+pub fn explain(input: &[BatteryBank], options: Options) -> Result<String, JoltageError> {
+    input
+        .iter()
+        .map(|bank| {
+            // Joltages of any length can be shown, whatever they are added up in
+            let (joltage, indices) = options.joltage_with_indices::<BigUint>(bank)?;
+            let mut marks = vec![' '; bank.batteries.chars().count()];
+            for idx in indices {
+                marks[idx] = '^';
+            }
+            let marks: String = marks.into_iter().collect();
+            Ok(format!(
+                "{}  {joltage}\n{}\n",
+                bank.batteries,
+                marks.trim_end()
+            ))
+        })
+        .collect()
+}
+
+/// The `n` banks with the largest joltages, largest first, with the lines they are on
+pub fn top<J: Joltage>(
+    input: &[BatteryBank],
+    lines: &[usize],
+    n: usize,
+    options: Options,
+) -> Result<Vec<(usize, J)>, JoltageError> {
+    let mut joltages = lines
+        .iter()
+        .zip(input)
+        .map(|(line, bank)| Ok((*line, options.joltage::<J>(bank)?)))
+        .collect::<Result<Vec<_>, _>>()?;
+    // Stable, so of equal joltages the earliest line comes first
+    joltages.sort_by(|a, b| b.1.cmp(&a.1));
+    joltages.truncate(n);
+    Ok(joltages)
+}
+
+/// Every bank of decimal batteries in the input
+#[derive(Debug, Clone)]
+pub struct Banks {
+    banks: Vec<BatteryBank>,
+}
+
+/// The example from the puzzle text, run with `--example`
+pub const EXAMPLE: &str = include_str!("../examples/example.txt");
+
+impl Solver for Banks {
+    type Output = ReturnType;
+    type Error = ParseError;
+    const EXAMPLE: &'static str = EXAMPLE;
+
+    fn parse(input: &str) -> Result<Self, ParseError> {
+        let banks = parse_input(input.as_bytes(), |line| map_one(line, 10))?
+            .into_iter()
+            .map(|(_, bank)| bank)
+            .collect();
+        Ok(Self { banks })
+    }
+
+    fn part_one(&self) -> Result<ReturnType, SolveError> {
+        part_one_internal(self.banks.clone(), Options::new(PART_ONE_DIGITS))
+            .map_err(SolveError::new)
+    }
+
+    fn part_two(&self) -> Result<ReturnType, SolveError> {
+        part_two_internal(self.banks.clone(), Options::new(PART_TWO_DIGITS))
+            .map_err(SolveError::new)
+    }
+}
+
+/// Internal logic for part_one
+pub fn part_one_internal<J: Joltage>(
+    input: Vec<VectorType>,
+    options: Options,
+) -> Result<J, JoltageError> {
+    input.into_iter().try_fold(J::zero(), |acc, bat| {
+        acc.add(options.joltage(&bat)?)
+            .ok_or(JoltageError::Overflow)
+    })
+}
+
+/// Internal logic for part two
+pub fn part_two_internal<J: Joltage>(
+    input: Vec<VectorType2>,
+    options: Options,
+) -> Result<J, JoltageError> {
+    input.into_iter().try_fold(J::zero(), |acc, bat| {
+        acc.add(options.joltage(&bat)?)
+            .ok_or(JoltageError::Overflow)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::pick::tests::{brute_force, digits, goal};
+
+    /// Function to split above into different inputs
+    fn parse_input_test<F, T>(input: &str, f: F) -> Vec<T>
+    where
+        F: Fn(&str, u32) -> Result<T, BankError>,
+    {
+        let parsed = parse_input(input.as_bytes(), |line| f(line, 10)).unwrap();
+        parsed.into_iter().map(|(_, item)| item).collect()
+    }
+
+    /// Bank of decimal batteries
+    fn bank(batteries: &str) -> BatteryBank {
+        BatteryBank::parse(batteries, 10).unwrap()
+    }
+
+    /// Pick `k` batteries with the stack
+    fn options(k: usize) -> Options {
+        Options::new(k)
+    }
+
+    #[test]
+    fn test_one() {
+        let input = parse_input_test(EXAMPLE, map_one);
+        let output = part_one_internal::<ReturnType>(input, options(PART_ONE_DIGITS));
+
+        // TODO fill this out
+        assert_eq!(output, Ok(357));
+    }
+
+    #[test]
+    fn test_solver() {
+        let banks = Banks::parse(EXAMPLE).unwrap();
+        assert_eq!(
+            (banks.part_one(), banks.part_two()),
+            (Ok(357), Ok(3121910778619))
+        );
+    }
+
+    #[test]
+    fn test_two() {
+        let input = parse_input_test(EXAMPLE, map_two);
+        let output = part_two_internal::<ReturnType>(input, options(PART_TWO_DIGITS));
+
+        // TODO fill this out
+        assert_eq!(output, Ok(3121910778619));
+    }
+
+    #[test]
+    fn test_parse() {
+        // Separators, spaces, a trailing carriage return and blank lines are skipped
+        let dirty = "9876 5432-1111,111\r\n\n  \n--\n811111111111119\n";
+        let input = parse_input_test(dirty, map_one);
+        assert_eq!(input.len(), 2);
+        assert_eq!(input[0].batteries, "987654321111111");
+        assert_eq!(
+            part_one_internal::<ReturnType>(input, options(2)),
+            Ok(98 + 89)
+        );
+        assert_eq!(
+            parse_input("123\n\n45x6".as_bytes(), |line| map_one(line, 10)).unwrap_err(),
+            ParseError::NotBattery {
+                line: 3,
+                character: 'x'
+            }
+        );
+        assert_eq!(
+            parse_input(&b"123\n\xff\n"[..], |line| map_one(line, 10)).unwrap_err(),
+            ParseError::Read {
+                line: 2,
+                reason: "stream did not contain valid UTF-8".to_string()
+            }
+        );
+        assert_eq!(
+            parse_input("abc".as_bytes(), |line| map_two(line, 10))
+                .unwrap_err()
+                .to_string(),
+            "line 1: expected a battery, found 'a'"
+        );
+    }
+
+    #[test]
+    fn test_top() {
+        let (lines, input): (Vec<usize>, Vec<BatteryBank>) =
+            parse_input(format!("\n{}", EXAMPLE).as_bytes(), |line| {
+                map_two(line, 10)
+            })
+            .unwrap()
+            .into_iter()
+            .unzip();
+        assert_eq!(lines, vec![2, 3, 4, 5]);
+        assert_eq!(
+            top::<ReturnType>(&input, &lines, 2, options(12)),
+            Ok(vec![(2, 987654321111), (5, 888911112111)])
+        );
+        // Ties keep the order of the lines, and asking for too many gives them all
+        let top_all = top::<ReturnType>(&input, &lines, 10, options(1)).unwrap();
+        assert_eq!(top_all, vec![(2, 9), (3, 9), (5, 9), (4, 8)]);
+    }
+
+    #[test]
+    fn test_base() {
+        let b = BatteryBank::parse("1f 0e 9a", 16).unwrap();
+        assert_eq!(b.find_k(2, Goal::Largest), Ok(0xfe_usize));
+        assert_eq!(b.find_k(3, Goal::SmallestNoLeadingZero), Ok(0x109_usize));
+        assert_eq!(b.find_k_recursive(6, Goal::Largest), Ok(0x1f0e9a_usize));
+        assert_eq!(
+            BatteryBank::parse("12g", 16).unwrap_err(),
+            BankError::NotBattery('g')
+        );
+        // Digits past the base are rejected like letters
+        assert_eq!(
+            BatteryBank::parse("1012", 2).unwrap_err(),
+            BankError::NotBattery('2')
+        );
+        for base in [0, 1, 37, u32::MAX] {
+            assert_eq!(
+                BatteryBank::parse("1", base).unwrap_err(),
+                BankError::Base(base)
+            );
+        }
+        assert_eq!(
+            parse_input("1".as_bytes(), |line| map_one(line, 37))
+                .unwrap_err()
+                .to_string(),
+            "base 37 is not between 2 and 36"
+        );
+        let b = BatteryBank::parse("1011", 2).unwrap();
+        assert_eq!(b.find_k(3, Goal::Largest), Ok(0b111_usize));
+        assert_eq!(b.find_k(3, Goal::Smallest), Ok(0b011_usize));
+    }
+
+    #[test]
+    fn test_in_depth() {
+        // let b = BatteryBank("987654321111111".to_string());
+        // assert_eq!(b.find_largest(), 98);
+        // assert_eq!(b.find_largest_k(12), 987654321111);
+
+        let b = bank("818181911112111");
+        assert_eq!(b.find_k(12, Goal::Largest), Ok(888911112111_usize));
+    }
+
+    #[test]
+    fn test_big() {
+        let bank = "98765432198765432198765432111";
+        let input = parse_input_test(&format!("{bank}\n{bank}"), map_two);
+        assert_eq!(
+            part_two_internal::<ReturnType>(input.clone(), options(20)),
+            Err(JoltageError::Overflow)
+        );
+        let joltage: BigUint = "9876543298765432198765432111".parse().unwrap();
+        assert_eq!(
+            part_two_internal(input.clone(), options(28)),
+            Ok(joltage * 2u32)
+        );
+        let recursive = Options {
+            recursive: true,
+            ..options(28)
+        };
+        assert_eq!(
+            part_two_internal(input, recursive),
+            part_two_internal::<BigUint>(
+                parse_input_test(&format!("{bank}\n{bank}"), map_two),
+                options(28)
+            )
+        );
+    }
+
+    #[test]
+    fn test_explain() {
+        let input = parse_input_test(EXAMPLE, map_one);
+        assert_eq!(
+            input[3].find_k_with_indices(2, Goal::Largest),
+            Ok((92_usize, vec![6, 11]))
+        );
+        assert_eq!(
+            explain(&input[1..3], options(2)).unwrap(),
+            "811111111111119  89\n^             ^\n234234234234278  78\n             ^^\n"
+        );
+        // Ties go to the earliest battery
+        let picked = bank("9919").find_k_with_indices(2, Goal::Largest);
+        assert_eq!(picked, Ok((99_usize, vec![0, 1])));
+    }
+
+    #[test]
+    fn test_min() {
+        let b = bank("818181911112111");
+        assert_eq!(b.find_k(4, Goal::Smallest), Ok(1111_usize));
+        let b = bank("30201");
+        assert_eq!(b.find_k(3, Goal::Smallest), Ok(1_usize));
+        assert_eq!(b.find_k(3, Goal::SmallestNoLeadingZero), Ok(201_usize));
+        let b = bank("0091");
+        assert_eq!(b.find_k(2, Goal::SmallestNoLeadingZero), Ok(91_usize));
+        // No choice but a leading zero
+        assert_eq!(b.find_k(3, Goal::SmallestNoLeadingZero), Ok(1_usize));
+    }
+
+    #[test]
+    fn test_digits() {
+        let input = parse_input_test(EXAMPLE, map_one);
+        assert_eq!(
+            part_one_internal::<ReturnType>(input.clone(), options(3)),
+            Ok(987 + 819 + 478 + 921)
+        );
+        assert_eq!(
+            part_two_internal::<ReturnType>(input.clone(), options(1)),
+            Ok(9 + 9 + 8 + 9)
+        );
+        // Every battery of the bank
+        assert_eq!(
+            part_two_internal::<ReturnType>(input[..1].to_vec(), options(15)),
+            Ok(987654321111111)
+        );
+        // More than the bank holds, with the stack or recursively
+        let too_short = Err(JoltageError::TooShort {
+            bank: "987654321111111".to_string(),
+            k: 16,
+            gap: 1,
+        });
+        assert_eq!(input[0].find_k::<ReturnType>(16, Goal::Largest), too_short);
+        assert_eq!(
+            input[0].find_k_recursive::<ReturnType>(16, Goal::SmallestNoLeadingZero),
+            too_short
+        );
+        assert_eq!(
+            Banks::parse("987").unwrap().part_two(),
+            Err(SolveError::new(
+                "bank 987: cannot turn on 12 of its 3 batteries"
+            ))
+        );
+    }
+
+    #[test]
+    fn test_gap() {
+        let input = parse_input_test(EXAMPLE, map_one);
+        let spaced = Options {
+            gap: 2,
+            ..options(2)
+        };
+        assert_eq!(
+            part_one_internal::<ReturnType>(input.clone(), spaced),
+            Ok(97 + 89 + 48 + 92)
+        );
+        assert_eq!(
+            explain(&input[2..3], spaced).unwrap(),
+            "234234234234278  48\n  ^           ^\n"
+        );
+    }
+
+    #[test]
+    fn test_gap_too_wide() {
+        assert_eq!(
+            bank("1234")
+                .find_k_spaced_with_indices::<ReturnType>(3, 2, Goal::Largest)
+                .unwrap_err()
+                .to_string(),
+            "bank 1234: cannot turn on 3 of its 4 batteries 2 apart"
+        );
+        // The banks are too short, so the total is an error rather than a panic
+        let spaced = Options {
+            gap: 8,
+            ..options(3)
+        };
+        assert!(matches!(
+            part_one_internal::<ReturnType>(parse_input_test(EXAMPLE, map_one), spaced),
+            Err(JoltageError::TooShort { gap: 8, .. })
+        ));
+    }
+
+    proptest! {
+        /// Every way of picking agrees with the best choice of batteries out of every choice
+        #[test]
+        fn prop_bank_matches_brute_force(
+            digits in digits(),
+            k in 0_usize..13,
+            gap in 1_usize..4,
+            goal in goal(),
+        ) {
+            let batteries: String = digits.iter().map(|digit| digit.to_string()).collect();
+            let bank = bank(&batteries);
+            let read = |indices: &[usize]| -> ReturnType {
+                indices.iter().fold(0, |acc, idx| acc * 10 + digits[*idx])
+            };
+            let best = brute_force(&digits, k, gap, goal).map(|indices| (read(&indices), indices));
+            let found = bank.find_k_spaced_with_indices::<ReturnType>(k, gap, goal).ok();
+            prop_assert_eq!(&found, &best);
+            if gap == 1 {
+                let joltage = best.map(|(joltage, _)| joltage);
+                prop_assert_eq!(bank.find_k(k, goal).ok(), joltage);
+                prop_assert_eq!(bank.find_k_recursive(k, goal).ok(), joltage);
+            }
+        }
+    }
+}
@@ -1,175 +1,186 @@
 //! Command line executable for running part one and part two
-use std::{
-    fs::File,
-    io::{BufRead, BufReader},
-    time::Instant,
+use std::{process::ExitCode, time::Instant};
+
+use anyhow::{Context, bail};
+use aoc_common::{
+    cancel::TimeoutArgs,
+    input::InputArgs,
+    log::LogArgs,
+    output::{self, OutputArgs, Record},
+    threads::ThreadArgs,
 };
-
 use clap::Parser;
+use day_three::{
+    BatteryBank, EXAMPLE, Goal, Joltage, JoltageError, Options, PART_ONE_DIGITS, PART_TWO_DIGITS,
+    ReturnType, explain, map_one, map_two, parse_input, part_one_internal, part_two_internal, top,
+};
+use num_bigint::BigUint;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Input file
-    #[arg(short)]
-    input_file: String,
+    #[command(flatten)]
+    input: InputArgs,
 
-    #[command(subcommand)]
-    part: Part,
-}
+    /// Number of batteries to turn on in each bank (defaults to 2 for part one, 12 for part two)
+    #[arg(short = 'k', long)]
+    digits: Option<usize>,
 
-#[derive(clap::Subcommand, Debug)]
-enum Part {
-    Part1,
-    Part2,
-}
+    /// Pick the batteries with the recursive search instead of the stack, to cross-check them
+    #[arg(long)]
+    recursive: bool,
 
-fn main() {
-    let args = Args::parse();
+    /// Print each bank with its chosen batteries marked before the answer
+    #[arg(long)]
+    explain: bool,
 
-    let file = BufReader::new(File::open(args.input_file).expect("Cannot find file"));
+    /// List the N banks with the largest joltages and their line numbers before the answer
+    #[arg(long, value_name = "N")]
+    top: Option<usize>,
 
-    let start = Instant::now();
-    let answer = match args.part {
-        Part::Part1 => part_one(file),
-        Part::Part2 => part_two(file),
-    };
+    /// Base the batteries are written in, from 2 to 36, with letters past 9
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(2..=36))]
+    base: u32,
 
-    println!("{:?}", answer);
-    println!("Completed in {:?}", start.elapsed());
-}
+    /// Add up the joltages in arbitrary precision, for totals that don't fit in a usize
+    #[arg(long)]
+    big: bool,
 
-fn part_one(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file, map_one);
-    part_one_internal(input)
-}
+    /// Pick the smallest joltage of each bank instead of the largest
+    #[arg(long)]
+    min: bool,
 
-fn part_two(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file, map_two);
-    part_two_internal(input)
-}
+    /// With --min, start each joltage with a zero only when the bank leaves no other choice
+    #[arg(long, requires = "min")]
+    no_leading_zero: bool,
 
-fn parse_input<F, T>(file: BufReader<File>, f: F) -> Vec<T>
-where
-    F: Fn(&str) -> T,
-{
-    file.lines().map(|x| f(x.unwrap().as_str())).collect()
-}
+    /// Turn on batteries at least G positions apart, picked by dynamic programming
+    #[arg(
+        long,
+        value_name = "G",
+        default_value_t = 1,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..),
+        conflicts_with = "recursive"
+    )]
+    gap: usize,
 
-/// Bank of batteries
-#[derive(Debug, Clone)]
-pub struct BatteryBank(String);
-impl BatteryBank {
-    /// Find the largest digit you can get, made out of k options
-    pub fn find_largest_k(&self, k: usize) -> usize {
-        let digits: Vec<usize> = self
-            .0
-            .chars()
-            .map(|c| c.to_digit(10).unwrap() as usize)
-            .collect();
-        Self::pick_k(&digits, k)
-    }
+    #[command(flatten)]
+    output: OutputArgs,
 
-    /// Function that will pick k digits out of a list of characters
-    fn pick_k(digits: &[usize], n_digits_to_select: usize) -> usize {
-        // Base case -- there are no digits left to select
-        if n_digits_to_select == 0 {
-            // We can return 0 because we are going to accumulate
-            return 0;
-        }
+    #[command(flatten)]
+    log: LogArgs,
 
-        // We must pick k digits, so the search window ends at len-k
-        let window_end_inclusive = digits.len() - n_digits_to_select;
-
-        // Find max digit in this window
-        let (max_idx, max_digit) = digits[..=window_end_inclusive]
-            .iter()
-            .enumerate()
-            .rev() // Must reverse because max_by_key selects the
-            // last one in a tie
-            .max_by_key(|(_, d)| **d)
-            .unwrap();
-
-        // Place it in the correct power-of-10 position
-        let rest = Self::pick_k(&digits[max_idx + 1..], n_digits_to_select - 1);
-        max_digit * 10usize.pow((n_digits_to_select - 1) as u32) + rest
-    }
-}
+    #[command(flatten)]
+    threads: ThreadArgs,
 
-// TODO -- Update this with the return type
-type ReturnType = usize;
-type VectorType = BatteryBank;
-type VectorType2 = VectorType;
+    #[command(flatten)]
+    timeout: TimeoutArgs,
 
-/// Map a line to a VectorType
-fn map_one(input: &str) -> VectorType {
-    BatteryBank(input.to_string())
+    #[command(subcommand)]
+    part: Part,
 }
 
-/// Map a line to a VectorType
-fn map_two(input: &str) -> VectorType2 {
-    map_one(input)
+#[derive(clap::Subcommand, Debug)]
+enum Part {
+    Part1,
+    Part2,
 }
+impl Part {
+    fn number(&self) -> u8 {
+        match self {
+            Self::Part1 => 1,
+            Self::Part2 => 2,
+        }
+    }
 
-/// Internal logic for part_one
-fn part_one_internal(input: Vec<VectorType>) -> ReturnType {
-    input
-        .into_iter()
-        .fold(0_usize, |acc, bat| acc + bat.find_largest_k(2))
+    /// Number of batteries the puzzle turns on in each bank
+    fn default_digits(&self) -> usize {
+        match self {
+            Self::Part1 => PART_ONE_DIGITS,
+            Self::Part2 => PART_TWO_DIGITS,
+        }
+    }
 }
 
-/// Internal logic for part two
-fn part_two_internal(input: Vec<VectorType2>) -> ReturnType {
-    input
-        .into_iter()
-        .fold(0_usize, |acc, bat| acc + bat.find_largest_k(12))
+fn main() -> ExitCode {
+    output::exit(try_main())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    /// Function to modify for input to test
-    fn input_one() -> &'static str {
-        "987654321111111
-811111111111119
-234234234234278
-818181911112111"
-    }
+fn try_main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    args.log.init();
+    args.threads.init()?;
+    args.timeout.init();
+
+    let file = args.input.open(EXAMPLE)?;
+
+    let options = Options {
+        k: args.digits.unwrap_or(args.part.default_digits()),
+        recursive: args.recursive,
+        explain: args.explain,
+        top: args.top,
+        gap: args.gap,
+        goal: match (args.min, args.no_leading_zero) {
+            (false, _) => Goal::Largest,
+            (true, false) => Goal::Smallest,
+            (true, true) => Goal::SmallestNoLeadingZero,
+        },
+    };
 
-    /// Function to split above into different inputs
-    fn parse_input_test<F, T>(input: &str, f: F) -> Vec<T>
-    where
-        F: Fn(&str) -> T,
-    {
-        input.lines().map(f).collect()
-    }
+    let start = Instant::now();
+    let input = match args.part {
+        Part::Part1 => parse_input(file, |line| map_one(line, args.base)),
+        Part::Part2 => parse_input(file, |line| map_two(line, args.base)),
+    };
+    let (lines, input): (Vec<usize>, Vec<BatteryBank>) = input
+        .with_context(|| args.input.name().to_owned())?
+        .into_iter()
+        .unzip();
+    let parse = start.elapsed();
 
-    #[test]
-    fn test_one() {
-        let input = parse_input_test(input_one(), map_one);
-        let output = part_one_internal(input);
+    let start = Instant::now();
+    let answer = if args.big {
+        solve::<BigUint>(&args.part, input, &lines, options)
+    } else {
+        solve::<ReturnType>(&args.part, input, &lines, options)
+    };
+    let answer = match answer {
+        Ok(answer) => answer,
+        Err(e @ JoltageError::Overflow) => bail!("{}: {e}, try --big", args.input.name()),
+        Err(e) => bail!("{}: {e}", args.input.name()),
+    };
 
-        // TODO fill this out
-        assert_eq!(output, 357);
-    }
+    args.output.print(&Record {
+        day: 3,
+        part: args.part.number().into(),
+        answer,
+        parse,
+        solve: start.elapsed(),
+    });
 
-    #[test]
-    fn test_two() {
-        let input = parse_input_test(input_one(), map_two);
-        let output = part_two_internal(input);
+    Ok(())
+}
 
-        // TODO fill this out
-        assert_eq!(output, 3121910778619);
+/// Run the chosen part, adding up in `J`
+///
+/// The banks come from the 1-indexed `lines` of the input
+fn solve<J: Joltage>(
+    part: &Part,
+    input: Vec<BatteryBank>,
+    lines: &[usize],
+    options: Options,
+) -> Result<String, JoltageError> {
+    if options.explain {
+        print!("{}", explain(&input, options)?);
     }
-
-    #[test]
-    fn test_in_depth() {
-        // let b = BatteryBank("987654321111111".to_string());
-        // assert_eq!(b.find_largest(), 98);
-        // assert_eq!(b.find_largest_k(12), 987654321111);
-
-        let b = BatteryBank("818181911112111".to_string());
-        assert_eq!(b.find_largest_k(12), 888911112111);
+    if let Some(n) = options.top {
+        for (line, joltage) in top::<J>(&input, lines, n, options)? {
+            println!("line {line}: {joltage}");
+        }
     }
+    let answer: J = match part {
+        Part::Part1 => part_one_internal(input, options)?,
+        Part::Part2 => part_two_internal(input, options)?,
+    };
+    Ok(answer.to_string())
 }
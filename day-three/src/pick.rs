@@ -0,0 +1,220 @@
+//! Picking which batteries of a bank to turn on, out of its digits
+//!
+//! Every function gives the positions of the digits picked, in order, with the earliest digit
+//! kept of any that tie.
+use std::iter;
+
+use aoc_common::seq;
+
+use crate::Goal;
+
+/// Positions of the `k` digits making the best joltage for `goal`, picked in one pass with the
+/// shared greedy subsequence
+///
+/// Of equal digits, the earliest is kept, like the recursive search does. `None` if there are
+/// fewer than `k` digits.
+pub fn pick_k(digits: &[usize], k: usize, goal: Goal) -> Option<Vec<usize>> {
+    if goal == Goal::SmallestNoLeadingZero && k > 0 {
+        // Ranking zeros apart in the first place would break the order the stack relies on,
+        // so the first battery is picked on its own
+        let first =
+            (0..=digits.len().checked_sub(k)?).min_by_key(|idx| goal.rank(digits[*idx], true))?;
+        let rest = pick_k(&digits[first + 1..], k - 1, Goal::Smallest)?;
+        return Some(
+            iter::once(first)
+                .chain(rest.into_iter().map(|idx| first + 1 + idx))
+                .collect(),
+        );
+    }
+    seq::best_subsequence_by(digits, k, |digit, other| {
+        goal.rank(*digit, false) < goal.rank(*other, false)
+    })
+}
+
+/// Positions of the `k` digits at least `gap` positions apart making the best joltage for `goal`,
+/// found by dynamic programming
+///
+/// `starts[m][p]` is where the best `m` digits from position `p` on begin, if that many fit.
+/// They go on with the best `m - 1` digits from `gap` further, so either the digit at `p`
+/// leads or the best `m` digits past it are kept, whichever is better.
+///
+/// `None` if there are too few digits for `k` of them that far apart.
+///
+/// # Panics
+/// If `gap` is zero
+pub fn pick_k_spaced(digits: &[usize], k: usize, gap: usize, goal: Goal) -> Option<Vec<usize>> {
+    assert!(gap > 0, "batteries must be at least one position apart");
+    let len = digits.len();
+    // Every `gap` digits hold at most one pick
+    if k > len.div_ceil(gap) {
+        return None;
+    }
+    let mut starts: Vec<Vec<Option<usize>>> = vec![vec![None; len + 1]; k + 1];
+    // Positions of the best `m` digits from `p` on
+    let follow = |starts: &[Vec<Option<usize>>], mut p: usize, m: usize| -> Vec<usize> {
+        (1..=m)
+            .rev()
+            .map(|m| {
+                let idx = starts[m][p].expect("only followed where the digits fit");
+                p = (idx + gap).min(len);
+                idx
+            })
+            .collect()
+    };
+    for m in 1..=k {
+        for p in (0..len).rev() {
+            let rest = (p + gap).min(len);
+            let skip = starts[m][p + 1];
+            let best = if m > 1 && starts[m - 1][rest].is_none() {
+                skip
+            } else if skip.is_none() {
+                Some(p)
+            } else {
+                // Only the first digit of the whole joltage is ranked on its own
+                let ranks = |indices: Vec<usize>| -> Vec<isize> {
+                    indices
+                        .into_iter()
+                        .enumerate()
+                        .map(|(place, idx)| goal.rank(digits[idx], m == k && place == 0))
+                        .collect()
+                };
+                let lead = ranks(iter::once(p).chain(follow(&starts, rest, m - 1)).collect());
+                // Of equal digits the earliest battery leads
+                if lead <= ranks(follow(&starts, p + 1, m)) {
+                    Some(p)
+                } else {
+                    skip
+                }
+            };
+            starts[m][p] = best;
+        }
+    }
+    if k > 0 && starts[k][0].is_none() {
+        return None;
+    }
+    Some(follow(&starts, 0, k))
+}
+
+/// Same as [`pick_k`], searching recursively for the best digit of each place in turn
+pub fn pick_k_recursive(digits: &[usize], k: usize, goal: Goal) -> Option<Vec<usize>> {
+    if k > digits.len() {
+        return None;
+    }
+    let mut picked = Vec::with_capacity(k);
+    search(digits, k, goal, 0, &mut picked);
+    Some(picked)
+}
+
+/// Pick `n_digits_to_select` digits out of `digits`, adding their positions past `offset` to
+/// `picked`
+fn search(
+    digits: &[usize],
+    n_digits_to_select: usize,
+    goal: Goal,
+    offset: usize,
+    picked: &mut Vec<usize>,
+) {
+    // Base case -- there are no digits left to select
+    if n_digits_to_select == 0 {
+        return;
+    }
+
+    // We must pick k digits, so the search window ends at len-k
+    let window_end_inclusive = digits.len() - n_digits_to_select;
+
+    // Find best digit in this window -- min_by_key selects the first one in a tie
+    let first = picked.is_empty();
+    let (best_idx, _) = digits[..=window_end_inclusive]
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, d)| goal.rank(**d, first))
+        .unwrap();
+
+    // It goes before every digit picked after it
+    picked.push(offset + best_idx);
+    search(
+        &digits[best_idx + 1..],
+        n_digits_to_select - 1,
+        goal,
+        offset + best_idx + 1,
+        picked,
+    );
+}
+
+/// Brute force and strategies, shared with the tests of the whole bank
+#[cfg(test)]
+pub(crate) mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+
+    /// Best choice of `k` positions at least `gap` apart out of every choice, ranking the digits
+    /// place by place and taking the earliest positions on ties
+    pub(crate) fn brute_force(
+        digits: &[usize],
+        k: usize,
+        gap: usize,
+        goal: Goal,
+    ) -> Option<Vec<usize>> {
+        (0_u32..1 << digits.len())
+            .filter(|mask| mask.count_ones() as usize == k)
+            .map(|mask| {
+                (0..digits.len())
+                    .filter(|idx| mask & (1 << idx) != 0)
+                    .collect::<Vec<usize>>()
+            })
+            .filter(|picked| picked.windows(2).all(|w| w[1] - w[0] >= gap))
+            .min_by_key(|picked| {
+                let ranks: Vec<isize> = picked
+                    .iter()
+                    .enumerate()
+                    .map(|(place, idx)| goal.rank(digits[*idx], place == 0))
+                    .collect();
+                (ranks, picked.clone())
+            })
+    }
+
+    pub(crate) fn goal() -> impl Strategy<Value = Goal> {
+        prop_oneof![
+            Just(Goal::Largest),
+            Just(Goal::Smallest),
+            Just(Goal::SmallestNoLeadingZero),
+        ]
+    }
+
+    /// Up to a dozen digits, few enough values that ties and zeros are common
+    pub(crate) fn digits() -> impl Strategy<Value = Vec<usize>> {
+        prop::collection::vec(0_usize..4, 0..12)
+    }
+
+    proptest! {
+        #[test]
+        fn prop_pick_k_matches_brute_force(digits in digits(), k in 0_usize..12, goal in goal()) {
+            let k = k.min(digits.len());
+            let best = brute_force(&digits, k, 1, goal);
+            prop_assert_eq!(pick_k(&digits, k, goal), best.clone());
+            prop_assert_eq!(pick_k_recursive(&digits, k, goal), best);
+        }
+
+        #[test]
+        fn prop_pick_k_too_many(digits in digits(), extra in 1_usize..4, goal in goal()) {
+            let k = digits.len() + extra;
+            prop_assert_eq!(pick_k(&digits, k, goal), None);
+            prop_assert_eq!(pick_k_recursive(&digits, k, goal), None);
+        }
+
+        #[test]
+        fn prop_pick_k_spaced_matches_brute_force(
+            digits in digits(),
+            k in 0_usize..6,
+            gap in 1_usize..4,
+            goal in goal(),
+        ) {
+            // Past as many as fit that far apart, neither finds a choice
+            prop_assert_eq!(
+                pick_k_spaced(&digits, k, gap, goal),
+                brute_force(&digits, k, gap, goal)
+            );
+        }
+    }
+}
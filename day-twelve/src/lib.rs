@@ -0,0 +1,220 @@
+//! Whether the presents fit under the trees, for the only part of the last day
+use aoc_common::{
+    cancel,
+    input::LineError,
+    progress::{self, ParallelProgressIterator},
+    solver::{SolveError, Solver},
+};
+use rayon::prelude::*;
+use tracing::warn;
+
+#[derive(Debug, Clone, Eq, Hash, PartialEq)]
+struct Shape {
+    grid: Vec<Vec<bool>>,
+}
+impl Shape {
+    /// One row of a shape, like `#.#`, failing with the reason
+    pub fn parse_row(line: &str) -> Result<Vec<bool>, String> {
+        line.trim()
+            .chars()
+            .map(|c| match c {
+                '#' => Ok(true),
+                '.' => Ok(false),
+                _ => Err(format!("{c:?} is not part of a shape, expected '#' or '.'")),
+            })
+            .collect()
+    }
+    pub fn size(&self) -> usize {
+        self.grid.iter().fold(0_usize, |accum, v| {
+            accum + v.iter().map(|x| *x as usize).sum::<usize>()
+        })
+    }
+}
+
+/// Represent a region
+#[derive(Debug, Clone)]
+struct Region {
+    width: usize,
+    height: usize,
+    shape_counts: Vec<usize>,
+}
+impl Region {
+    /// Parse `WxH: counts`, failing with the reason
+    pub fn from_line(line: &str) -> Result<Self, String> {
+        let (size, counts) = line
+            .trim()
+            .split_once(":")
+            .ok_or_else(|| format!("expected `WxH: counts`, found {line:?}"))?;
+        let (width, height) = size
+            .split_once("x")
+            .ok_or_else(|| format!("expected a size WxH, found {size:?}"))?;
+        let number = |x: &str| x.parse().map_err(|e| format!("{x:?} is not a number: {e}"));
+        let shape_counts = counts
+            .split_whitespace()
+            .map(number)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self {
+            width: number(width)?,
+            height: number(height)?,
+            shape_counts,
+        })
+    }
+
+    pub fn area(&self) -> usize {
+        self.width * self.height
+    }
+}
+
+/// Parsing State
+#[derive(Debug, Clone)]
+enum ParsingState {
+    NewShape,
+    InProgress,
+    ParsingRegions,
+}
+
+/// Driver
+#[derive(Debug, Clone)]
+pub struct Driver {
+    shapes: Vec<Shape>,
+    regions: Vec<Region>,
+}
+
+/// The example from the puzzle text, run with `--example`
+pub const EXAMPLE: &str = include_str!("../examples/example.txt");
+
+impl Solver for Driver {
+    type Output = usize;
+    type Error = LineError;
+    const EXAMPLE: &'static str = EXAMPLE;
+    const HAS_PART_TWO: bool = false;
+
+    fn parse(s: &str) -> Result<Self, LineError> {
+        let mut shapes = Vec::new();
+        let mut regions = Vec::new();
+        let mut state = ParsingState::NewShape;
+        let mut lines_for_shape = Vec::new();
+
+        // Regions count the shapes defined above them, so check each against those
+        let region = |idx: usize, line: &str, n_shapes: usize| {
+            let region = Region::from_line(line).map_err(|e| LineError::new(idx + 1, e))?;
+            if region.shape_counts.len() > n_shapes {
+                return Err(LineError::new(
+                    idx + 1,
+                    format!(
+                        "counts {} shapes, but only {n_shapes} are defined",
+                        region.shape_counts.len()
+                    ),
+                ));
+            }
+            Ok(region)
+        };
+
+        // Shape logic
+
+        for (idx, line) in s.lines().enumerate() {
+            match state {
+                ParsingState::NewShape => {
+                    let (_, rest) = line.trim().split_once(":").ok_or_else(|| {
+                        LineError::new(
+                            idx + 1,
+                            format!("expected a shape `N:` or a region, found {line:?}"),
+                        )
+                    })?;
+                    if rest.is_empty() {
+                        lines_for_shape.clear();
+                        state = ParsingState::InProgress;
+                    } else {
+                        // We are now in the regions portion
+                        state = ParsingState::ParsingRegions;
+                        // We do not want to miss this line... so parse
+                        regions.push(region(idx, line, shapes.len())?);
+                    }
+                }
+                ParsingState::InProgress => {
+                    if line.is_empty() {
+                        // We have reached the end -> go to start of new shape
+                        state = ParsingState::NewShape;
+                        // add the new shape
+                        shapes.push(Shape {
+                            grid: std::mem::take(&mut lines_for_shape),
+                        });
+                    } else {
+                        lines_for_shape
+                            .push(Shape::parse_row(line).map_err(|e| LineError::new(idx + 1, e))?);
+                    }
+                }
+                ParsingState::ParsingRegions => {
+                    regions.push(region(idx, line, shapes.len())?);
+                }
+            }
+        }
+        Ok(Self { shapes, regions })
+    }
+
+    fn part_one(&self) -> Result<usize, SolveError> {
+        // Let's solve this heuristically instead
+        let regions = progress::bar(self.regions.len() as u64, "regions");
+        let fits = self
+            .regions
+            .par_iter()
+            .progress_with(regions)
+            .take_any_while(|_| !cancel::is_cancelled())
+            .filter(|region| {
+                let min_size: f32 = region
+                    .shape_counts
+                    .iter()
+                    .enumerate()
+                    .map(|(shape_idx, count)| {
+                        let size = self.shapes[shape_idx].size();
+                        size * count
+                    })
+                    .sum::<usize>() as f32;
+                min_size * 1.2 <= region.area() as f32
+            })
+            .count();
+        if cancel::is_cancelled() {
+            warn!(fits, "Stopped early, with the regions found to fit so far");
+        }
+        Ok(fits)
+    }
+
+    fn part_two(&self) -> Result<usize, SolveError> {
+        Err(SolveError::new("the last day has no part two"))
+    }
+}
+
+pub fn part_one(s: &str) -> anyhow::Result<usize> {
+    Ok(Driver::parse(s)?.part_one()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one() {
+        let output = part_one(EXAMPLE).unwrap();
+
+        // TODO fill this out
+        assert_eq!(output, 2);
+    }
+
+    #[test]
+    fn test_parse_error() {
+        let error = |input| Driver::parse(input).unwrap_err().to_string();
+        assert_eq!(
+            error("0:\n#x\n"),
+            "line 2: 'x' is not part of a shape, expected '#' or '.'"
+        );
+        assert_eq!(
+            error("0:\n#.\n\n4x4: 1 2"),
+            "line 4: counts 2 shapes, but only 1 are defined"
+        );
+        assert_eq!(
+            error("4by4: 1"),
+            "line 1: expected a size WxH, found \"4by4\""
+        );
+    }
+}
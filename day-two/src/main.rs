@@ -1,254 +1,188 @@
 //! Command line executable for running part one and part two
 use std::{
-    fs::File,
-    io::{BufReader, Read},
-    time::Instant,
+    io::{self, BufWriter, Write},
+    process::ExitCode,
+    time::{Duration, Instant},
 };
 
+use anyhow::{Context, bail};
+use aoc_common::{
+    cancel::TimeoutArgs,
+    input::InputArgs,
+    log::LogArgs,
+    output::{self, OutputArgs, Record},
+    threads::ThreadArgs,
+};
 use clap::Parser;
+use day_two::{
+    EXAMPLE, ParseError, Range, RangeReader, Report, ReportFormat, ReturnType, Total,
+    constructed_sum, invalid_count, part_one_internal, part_two_internal, patterns::Repeats,
+    write_list,
+};
+use num_bigint::BigUint;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Input file
-    #[arg(short)]
-    input_file: String,
-
-    #[command(subcommand)]
-    part: Part,
-}
-
-#[derive(clap::Subcommand, Debug)]
-enum Part {
-    Part1,
-    Part2,
-}
-
-fn main() {
-    let args = Args::parse();
+    #[command(flatten)]
+    input: InputArgs,
 
-    let file = BufReader::new(File::open(args.input_file).expect("Cannot find file"));
+    /// Build the invalid IDs from their repeated block instead of checking every ID
+    #[arg(long)]
+    construct: bool,
 
-    let start = Instant::now();
-    let answer = match args.part {
-        Part::Part1 => part_one(file),
-        Part::Part2 => part_two(file),
-    };
+    /// Sum in arbitrary precision, for IDs whose sum overflows 128 bits
+    #[arg(long)]
+    big: bool,
 
-    println!("{:?}", answer);
-    println!("Completed in {:?}", start.elapsed());
-}
+    /// Print each invalid ID with the range holding it, instead of the sum
+    #[arg(long)]
+    list: bool,
 
-fn part_one(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file);
-    part_one_internal(input)
-}
+    /// Print each range's count of invalid IDs and their subtotal, and the grand total
+    #[arg(long, value_enum, conflicts_with = "list")]
+    per_range: Option<ReportFormat>,
 
-fn part_two(file: BufReader<File>) -> ReturnType {
-    let input = parse_input(file);
-    part_two_internal(input)
-}
-
-fn parse_input(mut file: BufReader<File>) -> Vec<Range> {
-    let mut s = String::new();
-    file.read_to_string(&mut s).expect("Failed to read");
-    s.split(",").map(Range::new).collect()
-}
+    /// Count the invalid IDs instead of summing them, in closed form with --construct
+    #[arg(long, conflicts_with_all = ["list", "per_range"])]
+    count: bool,
 
-// TODO -- Update this with the return type
-type ReturnType = usize;
-type VectorType = Range;
-type VectorType2 = Range;
-
-/// Range Entry to validate
-#[derive(Debug, Clone)]
-pub struct RangeEntry(usize);
-impl RangeEntry {
-    /// Validate according to the rules outlined
-    pub fn validate(&self) -> bool {
-        !Self::check_for_repeats(self.0) && !Self::check_if_starts_with_zero(self.0)
-    }
+    #[command(flatten)]
+    output: OutputArgs,
 
-    pub fn validate_two(&self) -> bool {
-        !Self::check_for_repeats_part2(self.0) && !Self::check_if_starts_with_zero(self.0)
-    }
+    #[command(flatten)]
+    log: LogArgs,
 
-    fn check_if_starts_with_zero(val: usize) -> bool {
-        let s = val.to_string();
-        s.chars().next().expect("No characters") == '0'
-    }
+    #[command(flatten)]
+    threads: ThreadArgs,
 
-    fn check_for_repeats(val: usize) -> bool {
-        let s = val.to_string();
-        // The possible max length of a pattern is the floor of the length of the string
-        let l = (s.len() as f32 / 2.0).ceil() as usize;
-        // Determine the number of times you'll have a first pointer
-        let n_chunks = s.len() / l;
-        let base_iter = s.chars();
-        for chunk_num in 0..n_chunks {
-            let first_chunk: String = base_iter.clone().skip(chunk_num * l).take(l).collect();
-            let second_chunk: String = base_iter
-                .clone()
-                .skip((chunk_num + 1) * l)
-                .take(l)
-                .collect();
-            if first_chunk == second_chunk {
-                return true;
-            }
-        }
-        false
-    }
+    #[command(flatten)]
+    timeout: TimeoutArgs,
 
-    /// We must go through, divide into different size chunks, and see if all chunks are the same
-    /// So, we can start with 1 to max size of chunks
-    /// The first chunk is the truth
-    /// Then, look at all other chunks and see if they match
-    fn check_for_repeats_part2(val: usize) -> bool {
-        let s = val.to_string();
-        // The possible max length of a pattern is the floor of the length of the string
-        let max_length = (s.len() as f32 / 2.0).floor() as usize;
-        // Look at chunk sizes from 1 to the max_length
-        for l in 1..=max_length {
-            // We can only use this chunk length if we can evenly divide the number of chunks
-            if !s.len().is_multiple_of(l) {
-                continue;
-            }
-            // Determine the number of times you'll have a first pointer
-            let n_chunks = s.len() / l;
-            let base_iter = s.chars();
-            let first_chunk: String = base_iter.clone().take(l).collect();
-            let mut chunks_are_all_the_same = true;
-            for chunk_num in 1..n_chunks {
-                // Determine the first chunk
-                // Continue to look at chunks until
-                let second_chunk: String = base_iter.clone().skip(chunk_num * l).take(l).collect();
-                chunks_are_all_the_same = chunks_are_all_the_same && first_chunk == second_chunk;
-            }
-            if chunks_are_all_the_same {
-                return true;
-            }
-        }
-        false
-    }
+    #[command(subcommand)]
+    part: Part,
 }
 
-/// Range -- consists of 2 range entries
-#[derive(Debug, Clone)]
-pub struct Range {
-    left: usize,
-    right: usize,
+#[derive(clap::Subcommand, Debug)]
+enum Part {
+    Part1,
+    Part2,
 }
-impl Range {
-    pub fn new(entry: &str) -> Self {
-        let mut vals = entry.split("-");
-        Self {
-            left: vals.next().expect("Need 2 sides").trim().parse().unwrap(),
-            right: vals.next().expect("Need 2 sides").trim().parse().unwrap(),
-        }
-    }
-
-    pub fn invalid_ids(&self) -> Vec<usize> {
-        let mut v = Vec::new();
-        for val in self.left..=self.right {
-            if !RangeEntry(val).validate() {
-                v.push(val)
-            }
+impl Part {
+    fn number(&self) -> u8 {
+        match self {
+            Self::Part1 => 1,
+            Self::Part2 => 2,
         }
-        v
     }
 
-    pub fn invalid_ids_part2(&self) -> Vec<usize> {
-        let mut v = Vec::new();
-        for val in self.left..=self.right {
-            if !RangeEntry(val).validate_two() {
-                v.push(val)
-            }
+    fn repeats(&self) -> Repeats {
+        match self {
+            Self::Part1 => Repeats::Twice,
+            Self::Part2 => Repeats::AtLeastTwice,
         }
-        v
     }
 }
 
-/// Internal logic for part_one
-fn part_one_internal(input: Vec<VectorType>) -> ReturnType {
-    input.into_iter().fold(0, |acc, range| {
-        acc + range.invalid_ids().into_iter().sum::<ReturnType>()
-    })
-}
-
-/// Internal logic for part two
-fn part_two_internal(input: Vec<VectorType2>) -> ReturnType {
-    input.into_iter().fold(0, |acc, range| {
-        acc + range.invalid_ids_part2().into_iter().sum::<ReturnType>()
-    })
+fn main() -> ExitCode {
+    output::exit(try_main())
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    /// Function to modify for input to test
-    fn input_one() -> &'static str {
-        "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,1698522-1698528,446443-446449,38593856-38593862,565653-565659,824824821-824824827,2121212118-2121212124"
-    }
-
-    /// Function to split above into different inputs
-    fn parse_input_test(input: &str) -> Vec<Range> {
-        input.split(",").map(Range::new).collect()
+fn try_main() -> anyhow::Result<()> {
+    let args = Args::parse();
+    args.log.init();
+    args.threads.init()?;
+    args.timeout.init();
+
+    let file = args.input.open(EXAMPLE)?;
+    // Ranges are handled as they are read, so a malformed one ends the input where it is found
+    // and is reported once what was read before it has been handled
+    let mut error = None;
+    let input = RangeReader::new(file).map_while(|range| range.map_err(|e| error = Some(e)).ok());
+
+    if args.list {
+        let mut out = BufWriter::new(io::stdout().lock());
+        write_list(&mut out, input, args.part.repeats(), args.construct)
+            .and_then(|()| out.flush())
+            .context("cannot write the list")?;
+        return check_read(&args, error);
+    }
+
+    if let Some(format) = args.per_range {
+        let input: Vec<Range> = input.collect();
+        check_read(&args, error)?;
+        let mut out = BufWriter::new(io::stdout().lock());
+        let written = if args.big {
+            Report::<BigUint>::new(&input, args.part.repeats(), args.construct)
+                .map(|report| report.write(&mut out, format))
+        } else {
+            Report::<ReturnType>::new(&input, args.part.repeats(), args.construct)
+                .map(|report| report.write(&mut out, format))
+        };
+        let Some(written) = written else {
+            bail!(
+                "{}: the sum overflows 128 bits, try --big",
+                args.input.name()
+            );
+        };
+        written
+            .and_then(|()| out.flush())
+            .context("cannot write the report")?;
+        return Ok(());
     }
 
-    #[test]
-    fn test_one() {
-        let input = parse_input_test(input_one());
-        let output = part_one_internal(input);
-
-        // TODO fill this out
-        assert_eq!(output, 1227775554);
-    }
+    let start = Instant::now();
+    let answer = if args.big {
+        solve::<BigUint>(&args, input)
+    } else {
+        solve::<ReturnType>(&args, input)
+    };
+    check_read(&args, error)?;
+    let Some(answer) = answer else {
+        bail!(
+            "{}: the sum overflows 128 bits, try --big",
+            args.input.name()
+        );
+    };
 
-    #[test]
-    fn test_two() {
-        let input = parse_input_test(input_one());
-        let output = part_two_internal(input);
+    args.output.print(&Record {
+        day: 2,
+        part: args.part.number().into(),
+        answer,
+        // Ranges are read as they are summed, so parsing is part of solving
+        parse: Duration::ZERO,
+        solve: start.elapsed(),
+    });
 
-        // TODO fill this out
-        assert_eq!(output, 4174379265);
-    }
-
-    #[test]
-    fn test_range_entry() {
-        assert!(RangeEntry::check_for_repeats(11));
-        assert!(RangeEntry::check_for_repeats(22));
-        assert!(!RangeEntry::check_for_repeats(23));
-        assert!(RangeEntry::check_for_repeats(6464));
-        assert!(RangeEntry::check_for_repeats(123123));
-        assert!(!RangeEntry::check_for_repeats(123124));
-    }
+    Ok(())
+}
 
-    #[test]
-    fn test_range_entry_part2() {
-        assert!(RangeEntry::check_for_repeats_part2(11));
-        assert!(RangeEntry::check_for_repeats_part2(22));
-        assert!(!RangeEntry::check_for_repeats_part2(23));
-        assert!(RangeEntry::check_for_repeats_part2(6464));
-        assert!(RangeEntry::check_for_repeats_part2(123123));
-        assert!(RangeEntry::check_for_repeats_part2(565656));
-        assert!(RangeEntry::check_for_repeats_part2(824824824));
-        assert!(!RangeEntry::check_for_repeats_part2(123124));
+/// Fail with the range that stopped the input early, if there was one
+fn check_read(args: &Args, error: Option<ParseError>) -> anyhow::Result<()> {
+    match error {
+        Some(e) => bail!("{}: {e}", args.input.name()),
+        None => Ok(()),
     }
+}
 
-    #[test]
-    fn test_part_one_deeper() {
-        let r = Range::new("11-22");
-        assert_eq!(r.invalid_ids(), vec![11, 22]);
-        let r = Range::new("95-115");
-        assert_eq!(r.invalid_ids(), vec![99]);
-    }
+/// Number of ranges read before summing them in parallel
+const CHUNK_RANGES: usize = 1 << 12;
 
-    #[test]
-    fn test_part_two_deeper() {
-        let r = Range::new("11-22");
-        assert_eq!(r.invalid_ids_part2(), vec![11, 22]);
-        let r = Range::new("95-115");
-        assert_eq!(r.invalid_ids_part2(), vec![99, 111]);
+/// Run the chosen part, summing in `S` a chunk of ranges at a time
+fn solve<S: Total>(args: &Args, mut input: impl Iterator<Item = Range>) -> Option<String> {
+    let mut answer = S::zero();
+    loop {
+        let chunk: Vec<Range> = input.by_ref().take(CHUNK_RANGES).collect();
+        if chunk.is_empty() {
+            return Some(answer.to_string());
+        }
+        let sum: S = match (&args.part, args.construct) {
+            _ if args.count => invalid_count(&chunk, args.part.repeats(), args.construct)?,
+            (Part::Part1, false) => part_one_internal(chunk)?,
+            (Part::Part2, false) => part_two_internal(chunk)?,
+            (Part::Part1, true) => constructed_sum(&chunk, Repeats::Twice)?,
+            (Part::Part2, true) => constructed_sum(&chunk, Repeats::AtLeastTwice)?,
+        };
+        answer = answer.add(sum)?;
     }
 }
@@ -0,0 +1,264 @@
+//! Building the invalid IDs directly from the block of digits they repeat
+//!
+//! An ID of `length` digits repeating a block of `period` digits is the block times
+//! `1 + 10^period + 10^(2 period) + ...`, so every invalid ID of a range can be found from the
+//! blocks whose multiple lands inside it, without looking at any of the valid IDs.
+use std::{borrow::Cow, fmt, sync::OnceLock};
+
+use crate::Id;
+
+/// Most decimal digits an `Id` can have
+const MAX_DIGITS: u32 = Id::MAX.ilog10() + 1;
+
+/// How often the block has to repeat for an ID to be invalid
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Repeats {
+    /// Exactly twice, as in part one
+    Twice,
+    /// Two or more times, as in part two
+    AtLeastTwice,
+}
+
+/// Number of decimal digits of `value`
+fn digits(value: Id) -> u32 {
+    value.checked_ilog10().map_or(1, |log| log + 1)
+}
+
+/// Proper divisors of `length`, worked out once for every length an `Id` can have
+fn divisors(length: u32) -> Cow<'static, [u32]> {
+    static DIVISORS: OnceLock<Vec<Vec<u32>>> = OnceLock::new();
+    let find = |length: u32| -> Vec<u32> {
+        (1..length)
+            .filter(|divisor| length.is_multiple_of(*divisor))
+            .collect()
+    };
+    let cached = DIVISORS.get_or_init(|| (0..=MAX_DIGITS).map(find).collect());
+    match cached.get(length as usize) {
+        Some(divisors) => Cow::Borrowed(divisors),
+        // Only arbitrary precision IDs are longer
+        None => Cow::Owned(find(length)),
+    }
+}
+
+/// Lengths of the blocks that can make up an ID of `length` digits, in increasing order
+pub fn periods(length: u32, repeats: Repeats) -> Cow<'static, [u32]> {
+    match repeats {
+        Repeats::Twice if length.is_multiple_of(2) => Cow::Owned(vec![length / 2]),
+        Repeats::Twice => Cow::Borrowed(&[]),
+        Repeats::AtLeastTwice => divisors(length),
+    }
+}
+
+/// Whether the digits of `id` are one block repeated as often as `repeats` asks
+///
+/// Takes any integer that prints its digits, so IDs past the top of an `Id` can be checked too.
+pub fn is_repeated(id: impl fmt::Display, repeats: Repeats) -> bool {
+    let digits = id.to_string();
+    let digits = digits.as_bytes();
+    periods(digits.len() as u32, repeats).iter().any(|&period| {
+        let (block, rest) = digits.split_at(period as usize);
+        rest.chunks(block.len()).all(|chunk| chunk == block)
+    })
+}
+
+/// `1 + 10^period + 10^(2 period) + ...` up to `length` digits, if it fits
+fn multiplier(length: u32, period: u32) -> Option<Id> {
+    (0..length / period).try_fold(0 as Id, |acc, idx| {
+        acc.checked_add(Id::checked_pow(10, idx * period)?)
+    })
+}
+
+/// Number of blocks of `period` digits whose multiple is at most `bound`
+fn blocks_up_to(bound: Id, period: u32, multiplier: Id) -> Id {
+    let last = (Id::pow(10, period) - 1).min(bound / multiplier);
+    (last + 1).saturating_sub(Id::pow(10, period - 1))
+}
+
+/// Number of invalid IDs from 1 to `bound`, without building them
+fn count_up_to(bound: Id, repeats: Repeats) -> Id {
+    let mut count = 0;
+    for length in 1..=digits(bound) {
+        let top = if length < digits(bound) {
+            Id::pow(10, length) - 1
+        } else {
+            bound
+        };
+        let periods = periods(length, repeats);
+        // IDs repeating a block also repeat every block made of copies of their shortest one, so
+        // the IDs of each shortest period are those of the period less those of its divisors
+        let mut shortest: Vec<Id> = Vec::with_capacity(periods.len());
+        for (idx, &period) in periods.iter().enumerate() {
+            let repeating = multiplier(length, period)
+                .map_or(0, |multiplier| blocks_up_to(top, period, multiplier));
+            let shorter: Id = periods[..idx]
+                .iter()
+                .zip(&shortest)
+                .filter(|(divisor, _)| period.is_multiple_of(**divisor))
+                .map(|(_, count)| count)
+                .sum();
+            shortest.push(repeating - shorter);
+        }
+        count += shortest.iter().sum::<Id>();
+    }
+    count
+}
+
+/// Number of invalid IDs in `left..=right`, in a handful of steps per digit length
+pub fn count(left: Id, right: Id, repeats: Repeats) -> Id {
+    if left > right {
+        return 0;
+    }
+    count_up_to(right, repeats)
+        - left
+            .checked_sub(1)
+            .map_or(0, |below| count_up_to(below, repeats))
+}
+
+/// Every invalid ID in `left..=right`, sorted
+pub fn invalid_ids(left: Id, right: Id, repeats: Repeats) -> Vec<Id> {
+    if left > right {
+        return Vec::new();
+    }
+    let mut found = Vec::with_capacity(usize::try_from(count(left, right, repeats)).unwrap_or(0));
+    for length in digits(left)..=digits(right) {
+        let start = found.len();
+        for &period in periods(length, repeats).iter() {
+            let Some(multiplier) = multiplier(length, period) else {
+                continue;
+            };
+            // Blocks can't start with a zero, and their multiple has to land in the range
+            let first = Id::pow(10, period - 1).max(left.div_ceil(multiplier));
+            let last = (Id::pow(10, period) - 1).min(right / multiplier);
+            for block in first..=last {
+                // A block that repeats itself is found again with its own, shorter, period
+                if repeats == Repeats::AtLeastTwice && is_repeated(block, repeats) {
+                    continue;
+                }
+                found.push(block * multiplier);
+            }
+        }
+        // Different periods interleave
+        found[start..].sort_unstable();
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::prelude::*;
+
+    use super::*;
+    use crate::tests::{ids, range};
+
+    /// Whether the digits are some shorter prefix written out again and again, by trying every one
+    fn naive_repeated(id: Id, repeats: Repeats) -> bool {
+        let digits = id.to_string();
+        let len = digits.len();
+        (1..len).any(|period| {
+            let copies = len / period;
+            let allowed = match repeats {
+                Repeats::Twice => copies == 2,
+                Repeats::AtLeastTwice => copies >= 2,
+            };
+            allowed && digits[..period].repeat(copies) == digits
+        })
+    }
+
+    fn repeats() -> impl Strategy<Value = Repeats> {
+        prop_oneof![Just(Repeats::Twice), Just(Repeats::AtLeastTwice)]
+    }
+
+    #[test]
+    fn test_matches_scan() {
+        for entry in [
+            "11-22",
+            "95-115",
+            "998-1012",
+            "1-100000",
+            "1188511880-1188511890",
+        ] {
+            let range = range(entry);
+            assert_eq!(
+                invalid_ids(range.left, range.right, Repeats::Twice),
+                ids(&range, Repeats::Twice),
+                "{entry}"
+            );
+            assert_eq!(
+                invalid_ids(range.left, range.right, Repeats::AtLeastTwice),
+                ids(&range, Repeats::AtLeastTwice),
+                "{entry}"
+            );
+        }
+        // 9 + 90 + ... + 900000 blocks of 1 to 6 digits, without checking a trillion IDs
+        let wide = invalid_ids(1, Id::pow(10, 12), Repeats::Twice);
+        assert_eq!(wide.len(), 999_999);
+        assert_eq!(wide.last(), Some(&999_999_999_999));
+        assert_eq!(invalid_ids(20, 10, Repeats::Twice), Vec::<Id>::new());
+        assert_eq!(
+            invalid_ids(Id::MAX - 10, Id::MAX, Repeats::AtLeastTwice),
+            Vec::<Id>::new()
+        );
+    }
+
+    #[test]
+    fn test_count() {
+        for (left, right) in [
+            (11, 22),
+            (95, 115),
+            (1, 9),
+            (1, 1_000_000),
+            (1212, 1212),
+            (20, 10),
+        ] {
+            for repeats in [Repeats::Twice, Repeats::AtLeastTwice] {
+                assert_eq!(
+                    count(left, right, repeats),
+                    invalid_ids(left, right, repeats).len() as Id,
+                    "{left}-{right} {repeats:?}"
+                );
+            }
+        }
+        assert_eq!(count(1, Id::pow(10, 12), Repeats::Twice), 999_999);
+        // Up against the top of u128, where the blocks of 13 digits repeat three times
+        let left = Id::MAX - Id::pow(10, 27);
+        assert_eq!(
+            count(left, Id::MAX, Repeats::AtLeastTwice),
+            invalid_ids(left, Id::MAX, Repeats::AtLeastTwice).len() as Id
+        );
+        assert!(count(0, Id::MAX, Repeats::AtLeastTwice) > count(0, Id::MAX, Repeats::Twice));
+        assert_eq!(periods(12, Repeats::AtLeastTwice)[..], [1, 2, 3, 4, 6]);
+        assert_eq!(periods(12, Repeats::Twice)[..], [6]);
+        assert_eq!(
+            periods(MAX_DIGITS + 1, Repeats::AtLeastTwice)[..],
+            [1, 2, 4, 5, 8, 10, 20]
+        );
+    }
+
+    proptest! {
+        #[test]
+        fn prop_repeated_matches_naive(id in any::<Id>(), repeats in repeats()) {
+            prop_assert_eq!(is_repeated(id, repeats), naive_repeated(id, repeats));
+        }
+
+        #[test]
+        fn prop_repeated_blocks(block in 1_u64..100_000, copies in 2_usize..6) {
+            let id: Id = block.to_string().repeat(copies).parse().unwrap();
+            prop_assert!(is_repeated(id, Repeats::AtLeastTwice));
+            prop_assert_eq!(is_repeated(id, Repeats::Twice), naive_repeated(id, Repeats::Twice));
+        }
+
+        #[test]
+        fn prop_invalid_ids_match_naive(
+            left in 0 as Id..2_000_000,
+            width in 0 as Id..5_000,
+            repeats in repeats(),
+        ) {
+            let right = left + width;
+            let naive: Vec<Id> = (left.max(1)..=right)
+                .filter(|id| naive_repeated(*id, repeats))
+                .collect();
+            prop_assert_eq!(count(left, right, repeats), naive.len() as Id);
+            prop_assert_eq!(invalid_ids(left, right, repeats), naive);
+        }
+    }
+}
@@ -53,6 +53,9 @@
               toolchainDev
               gdb
               cargo-generate
+              # For aoc-py, the solvers in Python
+              python3
+              maturin
             ];
             buildInputs = [
               pkgs.cbc
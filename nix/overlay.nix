@@ -1,14 +1,22 @@
 flakeInputs: final: prev: rec {
   # Set up my toolchains the way I would like them
   toolchain = flakeInputs.fenix.packages.${final.system}.stable.toolchain;
-  toolchainDev = flakeInputs.fenix.packages.${final.system}.complete.withComponents [
-    "cargo"
-    "clippy"
-    "rustc"
-    "rustfmt"
-    "rust-analyzer"
-    "rust-docs"
-    "rust-src"
-    "rust-std"
-  ];
+  toolchainDev =
+    let
+      fenix = flakeInputs.fenix.packages.${final.system};
+    in
+    fenix.combine [
+      (fenix.complete.withComponents [
+        "cargo"
+        "clippy"
+        "rustc"
+        "rustfmt"
+        "rust-analyzer"
+        "rust-docs"
+        "rust-src"
+        "rust-std"
+      ])
+      # For aoc-wasm, the solvers in the browser
+      fenix.targets.wasm32-unknown-unknown.latest.rust-std
+    ];
 }
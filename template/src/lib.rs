@@ -0,0 +1,55 @@
+//! TODO -- Describe the puzzle
+use aoc_common::{
+    input::LineError,
+    solver::{SolveError, Solver},
+};
+
+// TODO -- Update this with the return type
+pub type ReturnType = usize;
+
+/// Driver
+#[derive(Debug, Clone)]
+pub struct Driver {}
+
+// TODO -- Paste the example from the puzzle text into examples/example.txt
+/// The example from the puzzle text, run with `--example`
+pub const EXAMPLE: &str = include_str!("../examples/example.txt");
+
+impl Solver for Driver {
+    type Output = ReturnType;
+    type Error = LineError;
+    const EXAMPLE: &'static str = EXAMPLE;
+
+    fn parse(input: &str) -> Result<Self, LineError> {
+        todo!()
+    }
+
+    fn part_one(&self) -> Result<ReturnType, SolveError> {
+        todo!()
+    }
+
+    fn part_two(&self) -> Result<ReturnType, SolveError> {
+        todo!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_one() {
+        let output = Driver::parse(EXAMPLE).unwrap().part_one();
+
+        // TODO fill this out
+        assert_eq!(output, Ok(0));
+    }
+
+    #[test]
+    fn test_two() {
+        let output = Driver::parse(EXAMPLE).unwrap().part_two();
+
+        // TODO fill this out
+        assert_eq!(output, Ok(0));
+    }
+}
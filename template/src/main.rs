@@ -1,14 +1,35 @@
 //! Command line executable for running part one and part two
-use std::time::Instant;
-
+use std::{process::ExitCode, time::Instant};
+
+use anyhow::Context;
+use aoc_common::{
+    cancel::{self, TimeoutArgs},
+    input::InputArgs,
+    log::LogArgs,
+    output::{self, OutputArgs, Record},
+    solver::Solver,
+    threads::ThreadArgs,
+};
 use clap::Parser;
+use {{crate_name}}::Driver;
 
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
-    /// Input file
-    #[arg(short)]
-    input_file: String,
+    #[command(flatten)]
+    input: InputArgs,
+
+    #[command(flatten)]
+    output: OutputArgs,
+
+    #[command(flatten)]
+    log: LogArgs,
+
+    #[command(flatten)]
+    threads: ThreadArgs,
+
+    #[command(flatten)]
+    timeout: TimeoutArgs,
 
     #[command(subcommand)]
     part: Part,
@@ -20,53 +41,39 @@ enum Part {
     Part2,
 }
 
-fn main() {
+fn main() -> ExitCode {
+    output::exit(try_main())
+}
+
+fn try_main() -> anyhow::Result<()> {
     let args = Args::parse();
+    args.log.init();
+    args.threads.init()?;
+    args.timeout.init();
 
     // Read to a string
-    let s = std::fs::read_to_string(args.input_file).expect("Failed to read file");
+    let s = args.input.read_to_string(Driver::EXAMPLE)?;
 
     let start = Instant::now();
-    let answer = match args.part {
-        Part::Part1 => part_one(&s),
-        Part::Part2 => part_two(&s),
-    };
+    let driver = Driver::parse(&s).with_context(|| args.input.name().to_owned())?;
+    let parse = start.elapsed();
 
-    println!("{:?}", answer);
-    println!("Completed in {:?}", start.elapsed());
-}
-
-fn part_one(s: &str) -> usize {
-    todo!()
-}
-
-fn part_two(s: &str) -> usize {
-    todo!()
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
-
-    /// Function to modify for input to test
-    fn input_one() -> &'static str {
-        // TODO input
-        todo!();
-    }
-
-    #[test]
-    fn test_one() {
-        let output = part_one(input_one());
-
-        // TODO fill this out
-        assert_eq!(output, 0);
-    }
-
-    #[test]
-    fn test_two() {
-        let output = part_two(input_one());
-
-        // TODO fill this out
-        assert_eq!(output, 0);
-    }
+    let start = Instant::now();
+    let (part, answer) = match args.part {
+        Part::Part1 => (1, driver.part_one()?),
+        Part::Part2 => (2, driver.part_two()?),
+    };
+    // A solver stopped early only has a partial answer
+    cancel::check()?;
+
+    args.output.print(&Record {
+        // TODO -- Update this with the day
+        day: 0,
+        part: part.into(),
+        answer: answer.to_string(),
+        parse,
+        solve: start.elapsed(),
+    });
+
+    Ok(())
 }